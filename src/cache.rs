@@ -0,0 +1,142 @@
+//! 文章解析缓存
+//!
+//! 解析一篇文章要渲染 Markdown（comrak、代码高亮、公式），是构建中最耗时的部分。解析结果（front matter
+//! 与渲染后的 HTML）只取决于文件内容、相对路径与解析选项，因此按内容哈希缓存到 `.rustpress-cache/posts.json`，
+//! 内容未变的文章直接从缓存读取。rustpress 版本或解析选项（`[markdown]`、`[highlight]`、摘要与阅读速度）
+//! 变化时整个缓存失效；本次未用到的条目（已删除的文章）在保存时丢弃。付费文章的解析结果含明文正文，
+//! 构建时在保存前移除，每次构建重新解析。
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::manifest::hash_bytes;
+use crate::post::ParseOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// 缓存目录名（位于项目根，即 md_dir 的上级目录）
+pub const CACHE_DIR: &str = ".rustpress-cache";
+/// 文章解析缓存文件名
+pub const POSTS_CACHE_FILE: &str = "posts.json";
+/// 缓存格式版本，格式变化时递增
+const CACHE_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    /// 写入缓存的 rustpress 版本
+    generator: String,
+    /// 解析选项指纹
+    options: String,
+    /// 源文件（相对 md_dir，`/` 分隔）-> 缓存条目
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// 文件内容哈希
+    hash: String,
+    /// 解析结果（不含源文件路径、修改时间等依赖文件系统的字段）
+    post: Value,
+}
+
+/// 文章解析缓存（可在并行解析中共享）
+#[derive(Debug)]
+pub struct PostCache {
+    path: PathBuf,
+    options: String,
+    previous: BTreeMap<String, CacheEntry>,
+    current: Mutex<BTreeMap<String, CacheEntry>>,
+    hits: AtomicUsize,
+}
+
+impl PostCache {
+    /// 打开缓存目录下的文章缓存；文件不存在、无法解析或版本、解析选项不符时从空缓存开始
+    pub fn open<P: AsRef<Path>>(dir: P, options: &ParseOptions) -> Self {
+        let path = dir.as_ref().join(POSTS_CACHE_FILE);
+        let options = options_fingerprint(options);
+        let previous = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|file| {
+                file.version == CACHE_VERSION
+                    && file.generator == env!("CARGO_PKG_VERSION")
+                    && file.options == options
+            })
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        PostCache { path, options, previous, current: Mutex::new(BTreeMap::new()), hits: AtomicUsize::new(0) }
+    }
+
+    /// 按源文件与内容哈希读取缓存的解析结果
+    pub fn get(&self, key: &str, hash: &str) -> Option<Value> {
+        let entry = self.previous.get(key).filter(|entry| entry.hash == hash)?;
+        self.current.lock().unwrap().insert(key.to_string(), entry.clone());
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(entry.post.clone())
+    }
+
+    /// 写入新的解析结果
+    pub fn insert(&self, key: String, hash: String, post: Value) {
+        self.current.lock().unwrap().insert(key, CacheEntry { hash, post });
+    }
+
+    /// 移除条目，保存时不再写入（如构建期加密的付费文章）
+    pub fn remove(&self, key: &str) {
+        self.current.lock().unwrap().remove(key);
+    }
+
+    /// 本次命中缓存的文章数
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// 本次缓存的文章数（命中与新写入）
+    pub fn len(&self) -> usize {
+        self.current.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 保存本次用到的条目
+    pub fn save(&self) -> Result<()> {
+        let current = self.current.lock().unwrap();
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            generator: env!("CARGO_PKG_VERSION").to_string(),
+            options: self.options.clone(),
+            entries: current.clone(),
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::Other(format!("无法创建缓存目录 {:?}: {}", parent, e)))?;
+        }
+        std::fs::write(&self.path, serde_json::to_string(&file)?)
+            .map_err(|e| Error::Other(format!("无法写入文章解析缓存 {:?}: {}", self.path, e)))
+    }
+}
+
+/// 是否启用文章解析缓存（`[cache] enable`，默认开启）
+pub fn enabled(config: &Config) -> bool {
+    config
+        .data
+        .get("cache")
+        .and_then(|v| v.get("enable"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+/// 缓存目录：项目根（md_dir 的上级目录）下的 `.rustpress-cache`
+pub fn cache_dir(md_dir: &Path) -> PathBuf {
+    crate::utils::project_root(md_dir).join(CACHE_DIR)
+}
+
+/// 解析选项指纹：任一选项变化都会改变解析结果
+pub fn options_fingerprint(options: &ParseOptions) -> String {
+    hash_bytes(format!("{:?}", options).as_bytes())
+}
@@ -0,0 +1,90 @@
+//! 提示块（callout）模块
+//!
+//! 支持 GitHub 风格的 `> [!NOTE]` 与 VuePress / VitePress 风格的容器语法：
+//!
+//! ```text
+//! > [!TIP] 可选标题          :::tip 可选标题
+//! > 内容                     内容
+//!                            :::
+//! ```
+//!
+//! 两种写法统一输出为 `<div class="callout callout-tip">` + `<p class="callout-title">`，
+//! `:::details` 输出为可折叠的 `<details>`。未写标题时使用 `[markdown.callouts]` 中的本地化默认标题。
+
+use crate::utils::escape_html;
+use comrak::nodes::{AlertType, Node, NodeValue};
+use comrak::Arena;
+use std::collections::BTreeMap;
+
+/// 支持的提示块类型及默认标题
+pub const DEFAULT_TITLES: &[(&str, &str)] = &[
+    ("note", "注意"),
+    ("info", "信息"),
+    ("tip", "提示"),
+    ("important", "重要"),
+    ("warning", "警告"),
+    ("caution", "小心"),
+    ("danger", "危险"),
+    ("details", "详情"),
+];
+
+/// 默认标题表（类型 -> 标题）
+pub fn default_titles() -> BTreeMap<String, String> {
+    DEFAULT_TITLES
+        .iter()
+        .map(|(kind, title)| (kind.to_string(), title.to_string()))
+        .collect()
+}
+
+/// 将文档中的提示块替换为 callout HTML；未知类型的 `:::` 容器保持 comrak 默认输出
+pub fn transform<'a>(arena: &'a Arena<'a>, root: Node<'a>, titles: &BTreeMap<String, String>) {
+    let callouts: Vec<(Node<'a>, String, Option<String>)> = root
+        .descendants()
+        .filter_map(|node| {
+            let (kind, title) = match &node.data.borrow().value {
+                NodeValue::Alert(alert) => (alert_kind(alert.alert_type).to_string(), alert.title.clone()),
+                NodeValue::BlockDirective(directive) => {
+                    let info = directive.info.trim();
+                    let (kind, title) = info.split_once(char::is_whitespace).unwrap_or((info, ""));
+                    let title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+                    (kind.to_lowercase(), title)
+                }
+                _ => return None,
+            };
+            titles.contains_key(&kind).then_some((node, kind, title))
+        })
+        .collect();
+
+    for (node, kind, title) in callouts {
+        let title = escape_html(title.as_deref().unwrap_or_else(|| titles[&kind].as_str()));
+        let (open, close) = if kind == "details" {
+            (
+                format!("<details class=\"callout callout-details\">\n<summary class=\"callout-title\">{}</summary>\n", title),
+                "</details>\n",
+            )
+        } else {
+            (
+                format!("<div class=\"callout callout-{}\">\n<p class=\"callout-title\">{}</p>\n", kind, title),
+                "</div>\n",
+            )
+        };
+        // 用原样输出的 HTML 节点包住子节点，替换掉原容器
+        node.insert_before(arena.alloc(NodeValue::Raw(open).into()));
+        while let Some(child) = node.first_child() {
+            child.detach();
+            node.insert_before(child);
+        }
+        node.insert_before(arena.alloc(NodeValue::Raw(close.to_string()).into()));
+        node.detach();
+    }
+}
+
+fn alert_kind(alert: AlertType) -> &'static str {
+    match alert {
+        AlertType::Note => "note",
+        AlertType::Tip => "tip",
+        AlertType::Important => "important",
+        AlertType::Warning => "warning",
+        AlertType::Caution => "caution",
+    }
+}
@@ -0,0 +1,215 @@
+//! 内容校验模块
+//!
+//! 逐个解析 md_dir 下的 Markdown 文件，报告 front matter 语法错误（含行号）、
+//! 缺失标题、无法解析的 createTime、重复的 URL 以及专栏/书籍目录中指向不存在章节的条目。
+//! 供 `rustpress check` 在 CI 中使用。
+
+use crate::error::Result;
+use crate::front_matter::{self, FrontMatter, Layout};
+use crate::post::PostParser;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// 问题级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 单条校验问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckIssue {
+    pub path: PathBuf,
+    /// 文件中的行号（从 1 开始），无法定位时为 None
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for CheckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "错误",
+            Severity::Warning => "警告",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.path.display(), line, level, self.message),
+            None => write!(f, "{}: {}: {}", self.path.display(), level, self.message),
+        }
+    }
+}
+
+/// 校验报告
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub files: usize,
+    pub issues: Vec<CheckIssue>,
+}
+
+impl CheckReport {
+    pub fn error_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.severity == Severity::Error).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.severity == Severity::Warning).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    fn push(&mut self, path: &Path, line: Option<usize>, severity: Severity, message: String) {
+        self.issues.push(CheckIssue {
+            path: path.to_path_buf(),
+            line,
+            severity,
+            message,
+        });
+    }
+}
+
+/// createTime 是否可被识别（与解析器一致：兼容 `/`、`.` 分隔，时间部分可选）
+pub fn is_valid_create_time(value: &str) -> bool {
+    let normalized = value.trim().replace(['/', '.'], "-");
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .any(|fmt| chrono::NaiveDateTime::parse_from_str(&normalized, fmt).is_ok())
+        || chrono::NaiveDate::parse_from_str(&normalized, "%Y-%m-%d").is_ok()
+}
+
+/// 校验 md_dir 下的全部 Markdown 文件
+pub fn check_site<P: AsRef<Path>>(md_dir: P) -> Result<CheckReport> {
+    let md_dir = md_dir.as_ref();
+    let mut report = CheckReport::default();
+    // URL -> 生成该 URL 的文件（按路径排序，保证输出稳定）
+    let mut urls: BTreeMap<String, Vec<(PathBuf, Option<usize>)>> = BTreeMap::new();
+
+    let mut files: Vec<PathBuf> = WalkDir::new(md_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+
+    for path in &files {
+        report.files += 1;
+        let content = std::fs::read_to_string(path)?;
+
+        let raw = match front_matter::split(&content) {
+            Some(raw) => raw,
+            None => {
+                if content.starts_with("---") || content.starts_with("+++") {
+                    report.push(path, Some(1), Severity::Error, "front matter 缺少结束分隔符".to_string());
+                }
+                // 没有 front matter 的文件不会被当作文章，无需继续检查
+                continue;
+            }
+        };
+
+        let metadata = match raw.parse() {
+            Ok(v) => v,
+            Err(e) => {
+                report.push(
+                    path,
+                    e.line,
+                    Severity::Error,
+                    format!("front matter 解析失败，该文章将不会出现在站点中: {}", e.message),
+                );
+                continue;
+            }
+        };
+        let fm = FrontMatter::from_value(&metadata);
+
+        for diagnostic in front_matter::diagnose(&metadata) {
+            report.push(path, raw.key_line(&diagnostic.key), Severity::Warning, diagnostic.message);
+        }
+
+        // 短动态（tweet/short）本就没有标题
+        let untitled_layout = matches!(fm.layout, Some(Layout::Tweet) | Some(Layout::Short));
+        if !untitled_layout
+            && fm.title.is_none()
+            && PostParser::extract_title_from_markdown(raw.body).is_none()
+        {
+            report.push(path, Some(1), Severity::Error, "缺少标题（front matter 无 title 且正文无标题）".to_string());
+        }
+
+        match fm.create_time.as_deref() {
+            Some(t) if !is_valid_create_time(t) => {
+                let key = if metadata.get("createTime").is_some() { "createTime" } else { "date" };
+                report.push(
+                    path,
+                    raw.key_line(key),
+                    Severity::Error,
+                    format!("无法解析的时间 `{}`（支持 YYYY-MM-DD 或 YYYY-MM-DD HH:MM:SS）", t),
+                );
+            }
+            None if metadata.get("createTime").or_else(|| metadata.get("date")).is_some() => {
+                report.push(
+                    path,
+                    raw.key_line("createTime").or_else(|| raw.key_line("date")),
+                    Severity::Error,
+                    "createTime 不是字符串，将被忽略".to_string(),
+                );
+            }
+            _ => {}
+        }
+
+        // 目录条目必须指向 README 同级存在的章节文件
+        let is_readme = path.file_name().is_some_and(|n| n == "README.md");
+        if is_readme && matches!(fm.layout, Some(Layout::Columns) | Some(Layout::Doc)) {
+            let dir = path.parent().unwrap_or(md_dir);
+            for entry in &fm.catalog {
+                let name = entry.trim();
+                if name.is_empty() || dir.join(name).is_file() {
+                    continue;
+                }
+                report.push(
+                    path,
+                    raw.line_containing(name),
+                    Severity::Error,
+                    format!("目录条目 `{}` 指向的章节不存在", name),
+                );
+            }
+        }
+
+        // 根目录 README.md 是首页配置，草稿不会发布，二者都不参与 URL 冲突检查
+        if fm.draft || path == &md_dir.join("README.md") {
+            continue;
+        }
+        if let Ok(Some(post)) = PostParser::parse_file_content(&content, path.as_path(), md_dir) {
+            if let Some(url) = post.get("url").and_then(|v| v.as_str()) {
+                urls.entry(url.to_string())
+                    .or_default()
+                    .push((path.clone(), raw.key_line("slug")));
+            }
+        }
+    }
+
+    for (url, sources) in &urls {
+        if sources.len() < 2 {
+            continue;
+        }
+        for (path, line) in sources {
+            let others: Vec<String> = sources
+                .iter()
+                .filter(|(p, _)| p != path)
+                .map(|(p, _)| p.display().to_string())
+                .collect();
+            report.push(
+                path,
+                *line,
+                Severity::Error,
+                format!("URL `{}` 与 {} 重复", url, others.join(", ")),
+            );
+        }
+    }
+
+    report.issues.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    Ok(report)
+}
@@ -16,6 +16,10 @@ pub struct Cli {
     /// 指定配置文件（默认从 md_dir 下解析）
     #[arg(short, long, default_value = "config.toml")]
     pub config: String,
+
+    /// 解析与渲染的并行线程数（默认使用全部 CPU 核心，1 为串行）
+    #[arg(short, long, global = true)]
+    pub jobs: Option<usize>,
 }
 
 /// 可用的命令
@@ -39,9 +43,13 @@ pub enum Commands {
         #[arg(short, long, default_value = "public")]
         output_dir: String,
 
-        /// 开启增量编译（基于 build.toml 的 last_build_time）
+        /// 开启增量编译（基于输出目录中构建清单记录的内容哈希与依赖图）
         #[arg(long, default_value_t = false)]
         incremental: bool,
+
+        /// 严格模式：文章解析失败、页面模板错误或引用资源缺失时构建失败（默认仅输出汇总表）
+        #[arg(long, default_value_t = false)]
+        strict: bool,
     },
 
     /// 开发环境构建（包含 CSS 编译）
@@ -75,8 +83,53 @@ pub enum Commands {
         /// 关闭 hotreload（不监听模板文件变化）
         #[arg(long, default_value_t = false)]
         no_hotreload: bool,
+
+        /// 在内存中构建并直接提供预览，不写入输出目录（需开启 hotreload）
+        #[arg(long, default_value_t = false, conflicts_with = "no_hotreload")]
+        in_memory: bool,
     },
 
     /// 重新生成首页侧边栏数据到 build.toml
     BuildSidebar,
+
+    /// 校验全部 Markdown 的 front matter 与目录结构（存在错误时以非零状态退出，便于 CI）
+    Check,
+
+    /// 检查构建输出中的站内链接、资源与锚点（可选检查站外链接），存在失效链接时以非零状态退出
+    CheckLinks {
+        /// 指定输出目录
+        #[arg(short, long, default_value = "public")]
+        output_dir: String,
+
+        /// 检查前先执行一次全量构建
+        #[arg(long, default_value_t = false)]
+        build: bool,
+
+        /// 同时检查站外链接（覆盖 [link_check] external）
+        #[arg(long, default_value_t = false)]
+        external: bool,
+
+        /// 站外链接并发请求数（覆盖 [link_check] concurrency）
+        #[arg(long)]
+        concurrency: Option<usize>,
+
+        /// 站外链接超时秒数（覆盖 [link_check] timeout）
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+
+    /// 轮换付费专栏的内容密钥，增量重新生成受影响的页面并输出密钥清单
+    RotateKeys {
+        /// 专栏 doc_id
+        #[arg(long)]
+        doc: String,
+
+        /// 指定输出目录
+        #[arg(short, long, default_value = "public")]
+        output_dir: String,
+
+        /// 密钥清单输出路径（供换钥服务同步）；相对路径按项目根（source 的上级目录）解析
+        #[arg(long, default_value = "key_manifest.json")]
+        manifest: String,
+    },
 }
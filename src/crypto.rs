@@ -0,0 +1,452 @@
+//! 付费内容加密模块
+//!
+//! 构建期对 `is_free: false` 的专栏文章进行 AES-256-GCM 加密，
+//! 输出页面中只保留免费预览与密文容器（密文、IV、认证标签与 doc_id），
+//! 前端凭购买凭证（JWT）向换钥接口换取内容密钥，再通过 Web Crypto API 在本地解密。
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::post::Post;
+use crate::utils::escape_html;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine as _;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// 购买凭证签名密钥所在的环境变量
+pub const JWT_SECRET_ENV: &str = "JWT_SECRET";
+/// 内容密钥映射所在的环境变量（JSON：doc_id -> 密钥）
+pub const DOC_KEYS_ENV: &str = "DOC_KEYS";
+/// 默认密钥文件（与 DOC_KEYS 同结构的 JSON，由 rotate-keys 维护）
+pub const DEFAULT_KEYS_FILE: &str = "doc_keys.json";
+
+/// AES-GCM 认证标签长度（字节）
+const TAG_LEN: usize = 16;
+/// AES-GCM 初始化向量长度（字节）
+const IV_LEN: usize = 12;
+/// 默认免费预览字数
+const DEFAULT_PREVIEW_CHARS: usize = 200;
+
+/// 加密后的正文载荷（均为 Base64 编码，与前端 Web Crypto 解密参数一一对应）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedPayload {
+    pub ciphertext: String,
+    pub iv: String,
+    pub tag: String,
+}
+
+/// 将配置中的密钥字符串转换为 32 字节内容密钥
+///
+/// 合法的 Base64 32 字节密钥直接使用；其他字符串（如 `Key_123`）
+/// 以 doc_id 为盐经 HKDF-SHA256 派生，保证前后端得到同一把密钥。
+pub fn content_key_from_str(doc_id: &str, raw: &str) -> [u8; 32] {
+    let raw = raw.trim();
+    if let Ok(bytes) = BASE64.decode(raw) {
+        if let Ok(key) = <[u8; 32]>::try_from(bytes.as_slice()) {
+            return key;
+        }
+    }
+    let hk = Hkdf::<Sha256>::new(Some(doc_id.as_bytes()), raw.as_bytes());
+    let mut key = [0u8; 32];
+    // 输出长度固定为 32 字节，远小于 HKDF 上限，不会失败
+    hk.expand(b"rustpress-content-key", &mut key)
+        .expect("HKDF 输出长度合法");
+    key
+}
+
+/// 计算密钥标识（SHA-256 前 8 字节的十六进制），用于换钥与轮换时比对，不泄露密钥本身
+pub fn key_id(key: &[u8; 32]) -> String {
+    let digest = Sha256::digest(key);
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 内容密钥的 Base64 表示（换钥接口返回给前端的格式）
+pub fn encode_key(key: &[u8; 32]) -> String {
+    BASE64.encode(key)
+}
+
+/// 随机生成新的 32 字节内容密钥
+pub fn generate_content_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+/// 密钥文件路径（`[paywall] keys_file`，默认 doc_keys.json）；相对路径按项目根 `root` 解析，
+/// 因此 build、rotate-keys 与换钥接口无论在哪个目录运行都读写同一个文件
+pub fn keys_file_path(config: &Config, root: &Path) -> PathBuf {
+    let file = config
+        .data
+        .get("paywall")
+        .and_then(|v| v.get("keys_file"))
+        .and_then(|v| v.as_str())
+        .unwrap_or(DEFAULT_KEYS_FILE);
+    root.join(file)
+}
+
+/// 读取密钥文件，文件不存在时返回空映射
+pub fn read_keys_file<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| Error::Config(format!("密钥文件 {:?} 不是合法的 JSON 映射: {}", path, e)))
+}
+
+/// 写回密钥文件（按 doc_id 排序，便于直接粘贴为 DOC_KEYS）
+pub fn write_keys_file<P: AsRef<Path>>(path: P, keys: &HashMap<String, String>) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let sorted: std::collections::BTreeMap<&String, &String> = keys.iter().collect();
+    std::fs::write(path, serde_json::to_string_pretty(&sorted)?)?;
+    Ok(())
+}
+
+/// 环境变量 DOC_KEYS 中是否固定了该 doc_id 的密钥
+pub fn env_has_doc_key(doc_id: &str) -> bool {
+    std::env::var(DOC_KEYS_ENV)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<HashMap<String, String>>(&raw).ok())
+        .map(|m| m.contains_key(doc_id))
+        .unwrap_or(false)
+}
+
+/// 收集全部内容密钥字符串
+///
+/// 优先级由低到高：配置 `[paywall.keys]`、密钥文件（相对项目根 `root`）、环境变量 DOC_KEYS，高优先级覆盖同名项。
+pub fn load_doc_keys(config: &Config, root: &Path) -> Result<HashMap<String, String>> {
+    let mut keys = HashMap::new();
+
+    if let Some(table) = config
+        .data
+        .get("paywall")
+        .and_then(|v| v.get("keys"))
+        .and_then(|v| v.as_table())
+    {
+        for (doc_id, v) in table {
+            if let Some(s) = v.as_str() {
+                keys.insert(doc_id.clone(), s.to_string());
+            }
+        }
+    }
+
+    keys.extend(read_keys_file(keys_file_path(config, root))?);
+
+    if let Ok(raw) = std::env::var(DOC_KEYS_ENV) {
+        if !raw.trim().is_empty() {
+            let map: HashMap<String, String> = serde_json::from_str(&raw).map_err(|e| {
+                Error::Config(format!("环境变量 {} 不是合法的 JSON 映射: {}", DOC_KEYS_ENV, e))
+            })?;
+            keys.extend(map);
+        }
+    }
+
+    Ok(keys)
+}
+
+/// 解析指定 doc_id 当前的内容密钥
+pub fn resolve_content_key(config: &Config, root: &Path, doc_id: &str) -> Result<Option<[u8; 32]>> {
+    let keys = load_doc_keys(config, root)?;
+    Ok(keys.get(doc_id).map(|raw| content_key_from_str(doc_id, raw)))
+}
+
+/// 密钥轮换清单，供换钥服务同步新密钥并刷新受影响页面的缓存
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RotationManifest {
+    pub doc_id: String,
+    /// 轮换前的密钥标识（首次生成密钥时为空）
+    pub old_key_id: Option<String>,
+    pub new_key_id: String,
+    pub rotated_at: String,
+    /// 重新加密并写出的页面 URL
+    pub urls: Vec<String>,
+}
+
+/// 购买凭证载荷（JWT，HS256 签名，默认不设过期时间）
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PurchaseClaims {
+    pub email: String,
+    pub doc_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+}
+
+/// 读取购买凭证签名密钥：环境变量 JWT_SECRET 优先，其次 `[paywall] jwt_secret`
+pub fn jwt_secret(config: &Config) -> Option<String> {
+    std::env::var(JWT_SECRET_ENV)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            config
+                .data
+                .get("paywall")
+                .and_then(|v| v.get("jwt_secret"))
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        })
+}
+
+fn jwt_mac(secret: &str) -> Hmac<Sha256> {
+    // HMAC 接受任意长度密钥，不会失败
+    <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()).expect("HMAC 密钥长度合法")
+}
+
+/// 签发购买凭证
+pub fn sign_purchase_token(secret: &str, claims: &PurchaseClaims) -> Result<String> {
+    let header = BASE64_URL.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = BASE64_URL.encode(serde_json::to_vec(claims)?);
+    let signing_input = format!("{}.{}", header, payload);
+    let mut mac = jwt_mac(secret);
+    mac.update(signing_input.as_bytes());
+    let signature = BASE64_URL.encode(mac.finalize().into_bytes());
+    Ok(format!("{}.{}", signing_input, signature))
+}
+
+/// 校验购买凭证签名并返回载荷
+pub fn verify_purchase_token(secret: &str, token: &str) -> Result<PurchaseClaims> {
+    let invalid = |msg: &str| Error::Other(format!("购买凭证无效: {}", msg));
+
+    let mut parts = token.trim().split('.');
+    let (header, payload, signature) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(h), Some(p), Some(s), None) => (h, p, s),
+        _ => return Err(invalid("格式错误")),
+    };
+
+    let header_json: Value = BASE64_URL
+        .decode(header)
+        .ok()
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .ok_or_else(|| invalid("头部无法解析"))?;
+    if header_json.get("alg").and_then(|v| v.as_str()) != Some("HS256") {
+        return Err(invalid("仅支持 HS256 签名"));
+    }
+
+    let signature = BASE64_URL.decode(signature).map_err(|_| invalid("签名编码错误"))?;
+    let mut mac = jwt_mac(secret);
+    mac.update(format!("{}.{}", header, payload).as_bytes());
+    mac.verify_slice(&signature).map_err(|_| invalid("签名不匹配"))?;
+
+    let claims: PurchaseClaims = BASE64_URL
+        .decode(payload)
+        .ok()
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .ok_or_else(|| invalid("载荷缺少 email 或 doc_id"))?;
+    if let Some(exp) = claims.exp {
+        if chrono::Utc::now().timestamp() >= exp {
+            return Err(invalid("已过期"));
+        }
+    }
+    Ok(claims)
+}
+
+/// 使用 AES-256-GCM 加密 HTML 正文
+pub fn encrypt_html(key: &[u8; 32], html: &str) -> Result<EncryptedPayload> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let mut iv = [0u8; IV_LEN];
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let mut sealed = cipher
+        .encrypt(Nonce::from_slice(&iv), html.as_bytes())
+        .map_err(|e| Error::Other(format!("正文加密失败: {}", e)))?;
+    // aes-gcm 将认证标签追加在密文末尾，拆开以匹配前端参数
+    let tag = sealed.split_off(sealed.len() - TAG_LEN);
+
+    Ok(EncryptedPayload {
+        ciphertext: BASE64.encode(&sealed),
+        iv: BASE64.encode(iv),
+        tag: BASE64.encode(tag),
+    })
+}
+
+/// 解密 `encrypt_html` 的输出（供校验与密钥轮换使用）
+pub fn decrypt_html(key: &[u8; 32], payload: &EncryptedPayload) -> Result<String> {
+    let decode = |s: &str, name: &str| {
+        BASE64
+            .decode(s)
+            .map_err(|e| Error::Other(format!("{} 不是合法的 Base64: {}", name, e)))
+    };
+    let mut sealed = decode(&payload.ciphertext, "ciphertext")?;
+    let iv = decode(&payload.iv, "iv")?;
+    let tag = decode(&payload.tag, "tag")?;
+    if iv.len() != IV_LEN || tag.len() != TAG_LEN {
+        return Err(Error::Other("IV 或认证标签长度不正确".to_string()));
+    }
+    sealed.extend_from_slice(&tag);
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plain = cipher
+        .decrypt(Nonce::from_slice(&iv), sealed.as_slice())
+        .map_err(|_| Error::Other("正文解密失败，密钥可能已轮换".to_string()))?;
+    String::from_utf8(plain).map_err(|e| Error::Other(format!("解密结果不是合法 UTF-8: {}", e)))
+}
+
+/// 是否为专栏首页（README.md，layout: columns）
+fn is_column_index(post: &Post) -> bool {
+    post.slug() == Some("index") && post.categories().first().map(|c| c == "columns").unwrap_or(false)
+}
+
+/// 生成付费文章的最终正文：免费预览 + 密文容器
+pub fn render_locked_content(
+    doc_id: &str,
+    key_id: &str,
+    preview: &str,
+    payload: &EncryptedPayload,
+) -> String {
+    let mut html = String::new();
+    if !preview.is_empty() {
+        html.push_str(&format!(
+            "<div class=\"paywall-preview\"><p>{}</p></div>\n",
+            escape_html(preview)
+        ));
+    }
+    html.push_str(&format!(
+        "<div class=\"paywall-encrypted\" data-doc-id=\"{}\" data-key-id=\"{}\" data-ciphertext=\"{}\" data-iv=\"{}\" data-tag=\"{}\"></div>\n",
+        escape_html(doc_id),
+        key_id,
+        payload.ciphertext,
+        payload.iv,
+        payload.tag
+    ));
+    html
+}
+
+/// 拆出正文开头的 H1 标题（标题本就公开，保留在明文中以便模板照常剥离）
+fn split_leading_h1(html: &str) -> (&str, &str) {
+    let trimmed = html.trim_start();
+    if trimmed.starts_with("<h1") {
+        if let Some(end) = trimmed.find("</h1>") {
+            let split = html.len() - trimmed.len() + end + 5;
+            return (&html[..split], html[split..].trim_start());
+        }
+    }
+    ("", html)
+}
+
+/// 从正文 HTML 截取纯文本免费预览
+fn preview_text(html: &str, max_chars: usize) -> String {
+    if max_chars == 0 {
+        return String::new();
+    }
+    crate::excerpt::truncate_text(&crate::excerpt::html_to_text(html), max_chars)
+}
+
+/// 判断文章是否需要加密，返回其 doc_id
+///
+/// 文章自身的 `is_free` / `doc_id` 优先；未声明时继承所在专栏 README 的设置。
+/// 专栏首页本身作为目录页始终公开。
+pub fn paid_doc_id(post: &Post, column_index: Option<&Post>) -> Result<Option<String>> {
+    if is_column_index(post) {
+        return Ok(None);
+    }
+    let inherited = column_index.map(|c| &c.front_matter);
+    let is_free = post
+        .front_matter
+        .is_free
+        .or_else(|| inherited.and_then(|fm| fm.is_free))
+        .unwrap_or(true);
+    if is_free {
+        return Ok(None);
+    }
+    post.front_matter
+        .doc_id
+        .clone()
+        .or_else(|| inherited.and_then(|fm| fm.doc_id.clone()))
+        .map(Some)
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "付费文章缺少 doc_id（请在 front matter 或专栏 README 中声明）: {}",
+                post.source_path().unwrap_or("")
+            ))
+        })
+}
+
+/// 构建期加密全部付费文章，返回加密篇数
+///
+/// 找不到 doc_id 或内容密钥时直接报错，宁可构建失败也不发布明文。`root` 为项目根，用于定位密钥文件。
+pub fn encrypt_paid_posts(config: &Config, root: &Path, posts: &mut [Post]) -> Result<usize> {
+    encrypt_paid_posts_with_keys(config, &load_doc_keys(config, root)?, posts)
+}
+
+/// 用给定的内容密钥映射（doc_id -> 密钥）加密全部付费文章，返回加密篇数
+pub fn encrypt_paid_posts_with_keys(config: &Config, keys: &HashMap<String, String>, posts: &mut [Post]) -> Result<usize> {
+    let preview_chars = config
+        .data
+        .get("paywall")
+        .and_then(|v| v.get("preview_chars"))
+        .and_then(|v| v.as_integer())
+        .map(|n| n.max(0) as usize)
+        .unwrap_or(DEFAULT_PREVIEW_CHARS);
+
+    // 专栏目录路径 -> 专栏首页，用于继承 is_free / doc_id
+    let column_indexes: HashMap<Vec<String>, Post> = posts
+        .iter()
+        .filter(|p| is_column_index(p))
+        .map(|p| (p.categories(), p.clone()))
+        .collect();
+
+    let mut count = 0;
+
+    for post in posts.iter_mut() {
+        let doc_id = match paid_doc_id(post, column_indexes.get(&post.categories()))? {
+            Some(id) => id,
+            None => continue,
+        };
+        let raw = keys.get(&doc_id).ok_or_else(|| {
+            Error::Config(format!(
+                "未找到 doc_id `{}` 的内容密钥（请配置环境变量 {} 或 [paywall.keys]）: {}",
+                doc_id,
+                DOC_KEYS_ENV,
+                post.source_path().unwrap_or("")
+            ))
+        })?;
+        let key = content_key_from_str(&doc_id, raw);
+        let kid = key_id(&key);
+
+        let html = post.content().unwrap_or("").to_string();
+        let (heading, body) = split_leading_h1(&html);
+        let payload = encrypt_html(&key, body)?;
+        let preview = preview_text(body, preview_chars);
+        let locked = format!(
+            "{}{}",
+            heading,
+            render_locked_content(&doc_id, &kid, &preview, &payload)
+        );
+        // 自动摘要与 `<!-- more -->` 摘要取自付费正文，改用作者声明的摘要或公开的试读文本；
+        // 目录同样来自付费正文，一并清空
+        let declared_summary = ["summary", "description"]
+            .iter()
+            .filter_map(|k| post.data.get(*k).and_then(|v| v.as_str()))
+            .map(str::trim)
+            .find(|s| !s.is_empty())
+            .map(str::to_string);
+        let excerpt = crate::excerpt::from_text(declared_summary.unwrap_or(preview));
+
+        if let Some(obj) = post.data.as_object_mut() {
+            obj.insert("summary_html".to_string(), Value::String(excerpt.html));
+            obj.insert("summary_text".to_string(), Value::String(excerpt.text));
+            obj.insert("content".to_string(), Value::String(locked));
+            obj.insert("toc".to_string(), Value::Array(Vec::new()));
+            obj.insert("doc_id".to_string(), Value::String(doc_id));
+            obj.insert("encrypted".to_string(), Value::Bool(true));
+            obj.insert("key_id".to_string(), Value::String(kid));
+        }
+        count += 1;
+    }
+
+    if count > 0 {
+        println!("已加密付费文章 {} 篇", count);
+    }
+    Ok(count)
+}
@@ -23,6 +23,8 @@ pub enum Error {
     Toml(toml::de::Error),
     /// 网络服务器错误
     Server(String),
+    /// 构建失败（严格模式下汇总的问题）
+    Build(String),
     /// 通用错误
     Other(String),
 }
@@ -38,6 +40,7 @@ impl fmt::Display for Error {
             Error::Json(err) => write!(f, "JSON 序列化错误: {}", err),
             Error::Toml(err) => write!(f, "TOML 解析错误: {}", err),
             Error::Server(msg) => write!(f, "服务器错误: {}", msg),
+            Error::Build(msg) => write!(f, "构建失败: {}", msg),
             Error::Other(msg) => write!(f, "错误: {}", msg),
         }
     }
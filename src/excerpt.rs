@@ -0,0 +1,127 @@
+//! 摘要提取模块
+//!
+//! 摘要来源优先级：正文中的 `<!-- more -->` 标记之前的内容 > front matter 的 `summary` / `description`
+//! > 正文纯文本的前 N 个字符（中文按字截断，英文不截断单词）。
+
+use crate::error::Result;
+use crate::utils::strip_html_tags;
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// 未配置 `[excerpt] length` 时的摘要长度（字符数）
+pub const DEFAULT_EXCERPT_LENGTH: usize = 200;
+
+static RE_MORE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^\s*<!--\s*more\s*-->\s*$").unwrap());
+static RE_LEADING_H1: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)^\s*<h1[^>]*>.*?</h1>").unwrap());
+
+/// 文章摘要
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Excerpt {
+    /// 摘要 HTML（`<!-- more -->` 之前的渲染结果，其他来源为转义后的单段落）
+    pub html: String,
+    /// 摘要纯文本（用于列表页、RSS 与搜索索引）
+    pub text: String,
+}
+
+/// 返回 `<!-- more -->` 标记之前的 Markdown；标记须独占一行，代码块中的标记不算
+pub fn split_more(markdown: &str) -> Option<&str> {
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && RE_MORE.is_match(line) {
+            return Some(&markdown[..offset]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 提取摘要
+///
+/// `html` 为全文渲染结果，`render` 用于渲染 `<!-- more -->` 之前的 Markdown，渲染失败时返回其错误。
+pub fn extract<F: Fn(&str) -> Result<String>>(
+    markdown: &str,
+    html: &str,
+    metadata: &Value,
+    length: usize,
+    render: F,
+) -> Result<Excerpt> {
+    if let Some(before) = split_more(markdown) {
+        let html = strip_leading_h1(&render(before)?).trim().to_string();
+        if !html.is_empty() {
+            let text = html_to_text(&html);
+            return Ok(Excerpt { html, text });
+        }
+    }
+
+    let declared = ["summary", "description"]
+        .iter()
+        .filter_map(|key| metadata.get(*key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .find(|s| !s.is_empty());
+    if let Some(text) = declared {
+        return Ok(from_text(text.to_string()));
+    }
+
+    Ok(from_text(truncate_text(&html_to_text(strip_leading_h1(html)), length)))
+}
+
+/// 由纯文本构造摘要（HTML 为转义后的单段落）
+pub fn from_text(text: String) -> Excerpt {
+    if text.is_empty() {
+        return Excerpt::default();
+    }
+    let escaped = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    Excerpt {
+        html: format!("<p>{}</p>", escaped),
+        text,
+    }
+}
+
+/// HTML 转纯文本：去标签、合并空白并还原常见实体
+pub fn html_to_text(html: &str) -> String {
+    let text = strip_html_tags(&html.replace('<', " <").replace('>', "> "));
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 按字符数截断：中文逐字计数，英文单词不从中间截断，截断时追加省略号
+pub fn truncate_text(text: &str, max_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if max_chars == 0 {
+        return String::new();
+    }
+    if chars.len() <= max_chars {
+        return text.to_string();
+    }
+    let mut end = max_chars;
+    // 截断点落在英文单词中间时回退到单词边界（整段都是一个长单词时不回退）
+    if chars[end - 1].is_ascii_alphanumeric() && chars[end].is_ascii_alphanumeric() {
+        if let Some(boundary) = chars[..end].iter().rposition(|c| !c.is_ascii_alphanumeric()) {
+            end = boundary + 1;
+        }
+    }
+    let mut truncated: String = chars[..end].iter().collect::<String>().trim_end().to_string();
+    truncated.push('…');
+    truncated
+}
+
+/// 去掉开头的 H1（标题在列表页单独展示）
+fn strip_leading_h1(html: &str) -> &str {
+    match RE_LEADING_H1.find(html) {
+        Some(m) => &html[m.end()..],
+        None => html,
+    }
+}
@@ -0,0 +1,481 @@
+//! 订阅源模块
+//!
+//! 生成 RSS 2.0（`rss.xml`）、Atom 1.0（`atom.xml`）与 JSON Feed 1.1（`feed.json`）。
+//! 各格式分别由 `[features]` 中的 `rss`、`atom`、`json_feed` 开关控制；
+//! `[feed]` 中可设置条目数量与全文/摘要模式。
+//!
+//! 除全站订阅源外，每个标签、分类（含 `columns/N` 专栏）与专栏标签页旁也会生成同名订阅源，
+//! 如 `/tags/Rust/rss.xml`、`/columns/3/rss.xml`，可通过 `[feed] taxonomies = false` 关闭。
+
+use crate::config::Config;
+use crate::excerpt;
+use crate::front_matter::Layout;
+use crate::post::Post;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+
+/// 未配置 `[feed] limit` 时的条目数量
+pub const DEFAULT_LIMIT: usize = 20;
+
+static RE_ROOT_RELATIVE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(\s(?:href|src)=")/([^/"][^"]*)?""#).unwrap());
+
+/// 订阅源格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub const ALL: [FeedFormat; 3] = [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json];
+
+    /// 输出文件名
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss.xml",
+            FeedFormat::Atom => "atom.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    /// `[features]` 中的开关名
+    pub fn feature_key(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "atom",
+            FeedFormat::Json => "json_feed",
+        }
+    }
+
+    /// MIME 类型（用于 `<link rel="alternate">`）
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml",
+            FeedFormat::Atom => "application/atom+xml",
+            FeedFormat::Json => "application/feed+json",
+        }
+    }
+
+    /// 已启用的格式
+    pub fn enabled_formats(config: &Config) -> Vec<FeedFormat> {
+        FeedFormat::ALL.into_iter().filter(|f| f.enabled(config)).collect()
+    }
+
+    /// 该格式是否启用（未配置时默认启用）
+    pub fn enabled(&self, config: &Config) -> bool {
+        config
+            .data
+            .get("features")
+            .and_then(|v| v.get(self.feature_key()))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+    }
+}
+
+/// 订阅源作者（来自 `[author]`）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedAuthor {
+    pub name: String,
+    pub email: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// 订阅源元信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedMeta {
+    pub title: String,
+    pub description: String,
+    /// 站点根地址（无末尾斜杠）
+    pub base_url: String,
+    pub language: String,
+    pub author: FeedAuthor,
+    /// 输出全文（`[feed] content = "full"`），否则仅输出摘要
+    pub full_content: bool,
+    /// 最多输出的条目数
+    pub limit: usize,
+}
+
+impl FeedMeta {
+    /// 从站点配置读取订阅源元信息
+    pub fn from_config(config: &Config) -> Self {
+        let site = config.data.get("site");
+        let site_str = |key: &str| site.and_then(|v| v.get(key)).and_then(|v| v.as_str());
+        // 优先使用自定义域名字段，其次回退 base_url
+        let base_url = site_str("domain")
+            .or_else(|| site_str("base_url"))
+            .unwrap_or("")
+            .trim_end_matches('/')
+            .to_string();
+
+        let author = config.data.get("author");
+        let author_str = |key: &str| {
+            author
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let feed = config.data.get("feed");
+
+        FeedMeta {
+            title: site_str("name").unwrap_or("RustPress Blog").to_string(),
+            description: site_str("description").unwrap_or("A RustPress site").to_string(),
+            language: site_str("language").unwrap_or("zh-CN").to_string(),
+            author: FeedAuthor {
+                name: author_str("name").unwrap_or_default(),
+                email: author_str("email"),
+                url: author_str("website"),
+                avatar: author_str("avatar").map(|a| absolute_url(&base_url, &a)),
+            },
+            full_content: feed
+                .and_then(|v| v.get("content"))
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| s == "full"),
+            limit: feed
+                .and_then(|v| v.get("limit"))
+                .and_then(|v| v.as_integer())
+                .filter(|n| *n > 0)
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_LIMIT),
+            base_url,
+        }
+    }
+
+    /// 标签/分类/专栏订阅源的元信息：标题加上范围名称
+    pub fn scoped(&self, name: &str) -> FeedMeta {
+        FeedMeta {
+            title: format!("{} - {}", name, self.title),
+            description: format!("{}：{}", self.title, name),
+            ..self.clone()
+        }
+    }
+}
+
+/// 是否为标签、分类与专栏生成订阅源（`[feed] taxonomies`，默认开启）
+pub fn taxonomy_feeds_enabled(config: &Config) -> bool {
+    config
+        .data
+        .get("feed")
+        .and_then(|v| v.get("taxonomies"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+/// 标签/分类/专栏订阅源所在目录，如 `/tags/Rust/`、`/columns/3/`
+pub fn scoped_dir(segments: &[&str]) -> String {
+    format!("/{}/", segments.join("/"))
+}
+
+/// 页面 `<link rel="alternate">` 数据（模板上下文中的 `feed_links`）：
+/// `dir` 为订阅源所在目录，未启用分类法订阅源时为空
+pub fn alternate_links(config: &Config, dir: &str, name: &str) -> Vec<Value> {
+    if !taxonomy_feeds_enabled(config) {
+        return Vec::new();
+    }
+    let title = FeedMeta::from_config(config).scoped(name).title;
+    FeedFormat::enabled_formats(config)
+        .into_iter()
+        .map(|format| {
+            json!({
+                "type": format.mime_type(),
+                "href": format!("{}{}", dir, format.file_name()),
+                "title": title,
+            })
+        })
+        .collect()
+}
+
+/// 订阅源条目
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedItem {
+    pub title: String,
+    /// 绝对地址，同时作为条目 id
+    pub url: String,
+    pub summary_text: String,
+    pub summary_html: String,
+    /// 全文 HTML（站内相对链接已转换为绝对地址；加密文章为 None）
+    pub content_html: Option<String>,
+    pub published: DateTime<FixedOffset>,
+    pub updated: DateTime<FixedOffset>,
+    pub categories: Vec<String>,
+}
+
+impl FeedItem {
+    /// 由文章构建条目
+    pub fn from_post(post: &Post, meta: &FeedMeta) -> FeedItem {
+        let summary_text = post.summary_text().unwrap_or("").to_string();
+        // 动态（tweet/short）的标题只是文件名，改用摘要开头作为标题
+        let untitled = matches!(post.layout(), Some(Layout::Tweet) | Some(Layout::Short));
+        let title = post
+            .title()
+            .filter(|_| !untitled)
+            .map(str::to_string)
+            .unwrap_or_else(|| excerpt::truncate_text(&summary_text, 30));
+        let encrypted = post.data.get("encrypted").and_then(|v| v.as_bool()).unwrap_or(false);
+        let content_html = post
+            .content()
+            .filter(|_| !encrypted)
+            .map(|html| absolutize_links(html, &meta.base_url));
+        let published = published_at(post);
+        let updated = post
+            .modified_epoch()
+            .and_then(|epoch| Local.timestamp_opt(epoch, 0).single())
+            .map(|dt| dt.fixed_offset())
+            .filter(|dt| *dt > published)
+            .unwrap_or(published);
+
+        FeedItem {
+            title,
+            url: absolute_url(&meta.base_url, post.url().unwrap_or("/")),
+            summary_html: absolutize_links(post.summary_html().unwrap_or(""), &meta.base_url),
+            summary_text,
+            content_html,
+            published,
+            updated,
+            categories: post.tags(),
+        }
+    }
+
+    /// 按模式选择输出内容：全文模式且未加密时为全文，否则为摘要
+    fn body_html<'a>(&'a self, meta: &FeedMeta) -> &'a str {
+        match &self.content_html {
+            Some(html) if meta.full_content => html,
+            _ => &self.summary_html,
+        }
+    }
+}
+
+/// 文章发布时间：解析 `createTime`（本地时区），失败时回退 `date_ymd` 08:00
+pub fn published_at(post: &Post) -> DateTime<FixedOffset> {
+    let naive = post
+        .create_time()
+        .and_then(parse_naive_datetime)
+        .or_else(|| {
+            post.date()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .and_then(|d| d.and_hms_opt(8, 0, 0))
+        })
+        .unwrap_or_default();
+    local_datetime(naive)
+}
+
+/// 解析 front matter 中的时间字符串（本地时区）；仅有日期时取 08:00
+pub fn parse_local_datetime(s: &str) -> Option<DateTime<FixedOffset>> {
+    parse_naive_datetime(s)
+        .or_else(|| {
+            NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(8, 0, 0))
+        })
+        .map(local_datetime)
+}
+
+fn parse_naive_datetime(s: &str) -> Option<NaiveDateTime> {
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s.trim(), fmt).ok())
+}
+
+fn local_datetime(naive: NaiveDateTime) -> DateTime<FixedOffset> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+        .unwrap_or_else(|| naive.and_utc().fixed_offset())
+}
+
+/// 生成指定格式的订阅源；`path` 为订阅源自身在站点中的路径（如 `/rss.xml`）
+pub fn render<'a, I>(format: FeedFormat, meta: &FeedMeta, posts: I, path: &str) -> String
+where
+    I: IntoIterator<Item = &'a Post>,
+{
+    let items: Vec<FeedItem> = posts
+        .into_iter()
+        .take(meta.limit)
+        .map(|post| FeedItem::from_post(post, meta))
+        .collect();
+    let self_url = absolute_url(&meta.base_url, path);
+    match format {
+        FeedFormat::Rss => render_rss(meta, &items, &self_url),
+        FeedFormat::Atom => render_atom(meta, &items, &self_url),
+        FeedFormat::Json => render_json(meta, &items, &self_url),
+    }
+}
+
+fn last_updated(items: &[FeedItem]) -> DateTime<FixedOffset> {
+    items
+        .iter()
+        .map(|item| item.updated)
+        .max()
+        .unwrap_or_else(|| Local::now().fixed_offset())
+}
+
+fn render_rss(meta: &FeedMeta, items: &[FeedItem], self_url: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(&meta.title)));
+    xml.push_str(&format!("  <link>{}/</link>\n", escape_xml(&meta.base_url)));
+    xml.push_str(&format!("  <description>{}</description>\n", escape_xml(&meta.description)));
+    xml.push_str(&format!("  <language>{}</language>\n", escape_xml(&meta.language)));
+    xml.push_str(&format!("  <lastBuildDate>{}</lastBuildDate>\n", last_updated(items).to_rfc2822()));
+    xml.push_str(&format!(
+        "  <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape_xml(self_url)
+    ));
+    for item in items {
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&item.title)));
+        xml.push_str(&format!("    <link>{}</link>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("    <guid isPermaLink=\"true\">{}</guid>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("    <description>{}</description>\n", escape_xml(&item.summary_text)));
+        if meta.full_content {
+            if let Some(html) = &item.content_html {
+                xml.push_str(&format!("    <content:encoded>{}</content:encoded>\n", cdata(html)));
+            }
+        }
+        xml.push_str(&format!("    <pubDate>{}</pubDate>\n", item.published.to_rfc2822()));
+        if !meta.author.name.is_empty() {
+            xml.push_str(&format!("    <dc:creator>{}</dc:creator>\n", escape_xml(&meta.author.name)));
+        }
+        for category in &item.categories {
+            xml.push_str(&format!("    <category>{}</category>\n", escape_xml(category)));
+        }
+        xml.push_str("  </item>\n");
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn render_atom(meta: &FeedMeta, items: &[FeedItem], self_url: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"{}\">\n",
+        escape_xml(&meta.language)
+    ));
+    xml.push_str(&format!("  <id>{}/</id>\n", escape_xml(&meta.base_url)));
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(&meta.title)));
+    xml.push_str(&format!("  <subtitle>{}</subtitle>\n", escape_xml(&meta.description)));
+    xml.push_str(&format!("  <updated>{}</updated>\n", last_updated(items).to_rfc3339()));
+    xml.push_str(&format!("  <link rel=\"alternate\" type=\"text/html\" href=\"{}/\"/>\n", escape_xml(&meta.base_url)));
+    xml.push_str(&format!(
+        "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n",
+        escape_xml(self_url)
+    ));
+    if !meta.author.name.is_empty() {
+        xml.push_str("  <author>\n");
+        xml.push_str(&format!("    <name>{}</name>\n", escape_xml(&meta.author.name)));
+        if let Some(email) = &meta.author.email {
+            xml.push_str(&format!("    <email>{}</email>\n", escape_xml(email)));
+        }
+        if let Some(url) = &meta.author.url {
+            xml.push_str(&format!("    <uri>{}</uri>\n", escape_xml(url)));
+        }
+        xml.push_str("  </author>\n");
+    }
+    xml.push_str("  <generator uri=\"https://github.com/rixingyike/rustpress\">RustPress</generator>\n");
+    for item in items {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <id>{}</id>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&item.title)));
+        xml.push_str(&format!("    <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("    <published>{}</published>\n", item.published.to_rfc3339()));
+        xml.push_str(&format!("    <updated>{}</updated>\n", item.updated.to_rfc3339()));
+        if !item.summary_text.is_empty() {
+            xml.push_str(&format!("    <summary type=\"text\">{}</summary>\n", escape_xml(&item.summary_text)));
+        }
+        xml.push_str(&format!("    <content type=\"html\">{}</content>\n", escape_xml(item.body_html(meta))));
+        for category in &item.categories {
+            xml.push_str(&format!("    <category term=\"{}\"/>\n", escape_xml(category)));
+        }
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn render_json(meta: &FeedMeta, items: &[FeedItem], self_url: &str) -> String {
+    let mut authors = Vec::new();
+    if !meta.author.name.is_empty() {
+        let mut author = json!({ "name": meta.author.name });
+        if let Some(url) = &meta.author.url {
+            author["url"] = json!(url);
+        }
+        if let Some(avatar) = &meta.author.avatar {
+            author["avatar"] = json!(avatar);
+        }
+        authors.push(author);
+    }
+    let items: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            let mut value = json!({
+                "id": item.url,
+                "url": item.url,
+                "title": item.title,
+                "content_html": item.body_html(meta),
+                "date_published": item.published.to_rfc3339(),
+                "date_modified": item.updated.to_rfc3339(),
+            });
+            if !item.summary_text.is_empty() {
+                value["summary"] = json!(item.summary_text);
+            }
+            if !item.categories.is_empty() {
+                value["tags"] = json!(item.categories);
+            }
+            value
+        })
+        .collect();
+    let mut feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": meta.title,
+        "home_page_url": format!("{}/", meta.base_url),
+        "feed_url": self_url,
+        "description": meta.description,
+        "language": meta.language,
+        "items": items,
+    });
+    if !authors.is_empty() {
+        feed["authors"] = Value::Array(authors);
+    }
+    serde_json::to_string_pretty(&feed).unwrap_or_default()
+}
+
+/// 站内路径转为绝对地址
+pub(crate) fn absolute_url(base_url: &str, path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}/{}", base_url, path.trim_start_matches('/'))
+    }
+}
+
+/// 将 HTML 中以 `/` 开头的站内链接与图片地址转为绝对地址（阅读器中相对地址无法解析）
+fn absolutize_links(html: &str, base_url: &str) -> String {
+    if base_url.is_empty() {
+        return html.to_string();
+    }
+    RE_ROOT_RELATIVE
+        .replace_all(html, |c: &regex::Captures| {
+            format!("{}{}/{}\"", &c[1], base_url, c.get(2).map_or("", |m| m.as_str()))
+        })
+        .to_string()
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// CDATA 包裹（内容中的 `]]>` 需拆分）
+fn cdata(s: &str) -> String {
+    format!("<![CDATA[{}]]>", s.replace("]]>", "]]]]><![CDATA[>"))
+}
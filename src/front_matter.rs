@@ -0,0 +1,424 @@
+//! Front matter 模型模块
+//!
+//! 集中管理文章头部元数据：拆分与容错解析（YAML/TOML）、已知字段的强类型模型、
+//! 布局枚举，以及对疑似拼写错误的字段与未知布局给出诊断。
+//! 未识别的字段原样保留在 `extra` 中，模板依旧可以通过 `page.xxx` 访问。
+
+use crate::error::{Error, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// front matter 语法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// `---` 包裹的 YAML
+    Yaml,
+    /// `+++` 包裹的 TOML
+    Toml,
+}
+
+/// 从文件内容中拆分出的原始 front matter
+#[derive(Debug, Clone, Copy)]
+pub struct RawFrontMatter<'a> {
+    pub format: FrontMatterFormat,
+    /// 分隔符之间的原始文本
+    pub text: &'a str,
+    /// 正文（结束分隔符之后的内容）
+    pub body: &'a str,
+    /// 原始文本首行在文件中的行号（从 1 开始），用于定位解析错误
+    pub first_line: usize,
+}
+
+/// 拆分 front matter 与正文；没有 front matter 时返回 None
+pub fn split(content: &str) -> Option<RawFrontMatter<'_>> {
+    let (format, fm_marker, end_marker) = if content.starts_with("+++") {
+        (FrontMatterFormat::Toml, "+++", "+++\n")
+    } else if content.starts_with("---") {
+        (FrontMatterFormat::Yaml, "---", "---\n")
+    } else {
+        return None;
+    };
+
+    // 查找 front matter 结束位置
+    let start = fm_marker.len();
+    let end = if let Some(pos) = content[start..].find(end_marker) {
+        start + pos
+    } else if let Some(pos) = content[start..].find(fm_marker) {
+        start + pos
+    } else {
+        return None;
+    };
+
+    Some(RawFrontMatter {
+        format,
+        text: &content[start..end],
+        body: &content[end + fm_marker.len()..],
+        first_line: 1,
+    })
+}
+
+/// 缺少空格的键值对（如 `title:"xx"`）
+static RE_MISSING_SPACE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^([ \t]*[a-zA-Z0-9_-]+):([^\s].*)$").unwrap());
+/// 以中文冒号分隔的键（如 `title：xx`）
+static RE_CJK_COLON_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^([ \t]*[a-zA-Z0-9_-]+)[ \t]*：").unwrap());
+
+/// 针对 YAML 做鲁棒性处理：修复中文冒号与缺少空格的键值对
+pub fn fix_yaml(text: &str) -> String {
+    // 1. 修复键后的中文冒号为英文冒号（值中的中文冒号保持原样，如 `第一章：启程.md`）
+    let fixed = RE_CJK_COLON_KEY.replace_all(text, "${1}:");
+    // 2. 修复缺少空格的键值对 (e.g. "key:value" -> "key: value")
+    RE_MISSING_SPACE.replace_all(&fixed, "${1}: ${2}").to_string()
+}
+
+/// front matter 语法错误（行号为文件中的行号）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl RawFrontMatter<'_> {
+    /// 将原始文本中的行号换算为文件行号
+    fn file_line(&self, text_line: usize) -> usize {
+        self.first_line + text_line - 1
+    }
+
+    /// 查找某个字段在文件中的行号（只匹配顶层键）
+    pub fn key_line(&self, key: &str) -> Option<usize> {
+        self.text.lines().enumerate().find_map(|(i, line)| {
+            let rest = line.strip_prefix(key)?;
+            let rest = rest.trim_start();
+            (rest.starts_with(':') || rest.starts_with('：') || rest.starts_with('='))
+                .then(|| self.file_line(i + 1))
+        })
+    }
+
+    /// 查找包含指定文本的首行行号
+    pub fn line_containing(&self, needle: &str) -> Option<usize> {
+        self.text
+            .lines()
+            .position(|line| line.contains(needle))
+            .map(|i| self.file_line(i + 1))
+    }
+
+    /// 解析为 JSON 值，失败时给出出错行号
+    pub fn parse(&self) -> std::result::Result<Value, ParseFailure> {
+        let to_json = |e: serde_json::Error| ParseFailure {
+            line: None,
+            message: e.to_string(),
+        };
+        match self.format {
+            FrontMatterFormat::Toml => {
+                let metadata: toml::Value = toml::from_str(self.text).map_err(|e| ParseFailure {
+                    line: e.span().map(|span| {
+                        self.file_line(self.text[..span.start].matches('\n').count() + 1)
+                    }),
+                    message: e.message().to_string(),
+                })?;
+                serde_json::to_value(metadata).map_err(to_json)
+            }
+            FrontMatterFormat::Yaml => {
+                let metadata: serde_yaml::Value =
+                    serde_yaml::from_str(&fix_yaml(self.text)).map_err(|e| ParseFailure {
+                        // 容错修复只替换字符，不改变行数，行号可直接换算
+                        line: e.location().map(|loc| self.file_line(loc.line())),
+                        message: e.to_string(),
+                    })?;
+                serde_json::to_value(metadata).map_err(to_json)
+            }
+        }
+    }
+}
+
+/// 将原始 front matter 解析为 JSON 值
+pub fn parse_metadata(raw: &RawFrontMatter<'_>, path: &Path) -> Result<Value> {
+    raw.parse().map_err(|e| {
+        let kind = match raw.format {
+            FrontMatterFormat::Toml => "TOML",
+            FrontMatterFormat::Yaml => "YAML",
+        };
+        Error::Markdown(format!("解析{} front matter失败 {:?}: {}", kind, path, e.message))
+    })
+}
+
+/// 页面布局
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Layout {
+    Post,
+    Home,
+    Doc,
+    Docs,
+    Columns,
+    Project,
+    Projects,
+    About,
+    Friends,
+    Friend,
+    Works,
+    Work,
+    Tweet,
+    Short,
+    /// 主题自定义布局（需存在同名模板）
+    Other(String),
+}
+
+impl Layout {
+    /// 内置布局名称
+    pub const BUILTIN: [&'static str; 14] = [
+        "post", "home", "doc", "docs", "columns", "project", "projects", "about", "friends",
+        "friend", "works", "work", "tweet", "short",
+    ];
+
+    pub fn parse(s: &str) -> Layout {
+        match s.trim() {
+            "post" => Layout::Post,
+            "home" => Layout::Home,
+            "doc" => Layout::Doc,
+            "docs" => Layout::Docs,
+            "columns" => Layout::Columns,
+            "project" => Layout::Project,
+            "projects" => Layout::Projects,
+            "about" => Layout::About,
+            "friends" => Layout::Friends,
+            "friend" => Layout::Friend,
+            "works" => Layout::Works,
+            "work" => Layout::Work,
+            "tweet" => Layout::Tweet,
+            "short" => Layout::Short,
+            other => Layout::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Layout::Post => "post",
+            Layout::Home => "home",
+            Layout::Doc => "doc",
+            Layout::Docs => "docs",
+            Layout::Columns => "columns",
+            Layout::Project => "project",
+            Layout::Projects => "projects",
+            Layout::About => "about",
+            Layout::Friends => "friends",
+            Layout::Friend => "friend",
+            Layout::Works => "works",
+            Layout::Work => "work",
+            Layout::Tweet => "tweet",
+            Layout::Short => "short",
+            Layout::Other(s) => s,
+        }
+    }
+}
+
+impl std::fmt::Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 已知的 front matter 字段（含仅由模板使用的字段），用于拼写检查
+pub const KNOWN_KEYS: &[&str] = &[
+    "title", "slug", "layout", "description", "createTime", "date", "tags", "cover", "icon",
+    "catalog", "weight", "draft", "is_free", "doc_id", "images", "screenshots", "avatar",
+    "comments", "toc", "include_in_series", "paid", "price", "product_id", "sidebar", "link",
+    "domain", "year", "version", "url", "subtitle", "publisher", "downloads", "sync", "navbar",
+    "aside", "show_hero", "hero_title", "hero_subtitle", "hero_background", "summary",
+    "updated", "sitemap", "noindex", "markdown",
+];
+
+/// 解析阶段派生的字段，不属于 front matter
+const COMPUTED_KEYS: &[&str] = &[
+    "content", "categories", "source_path", "file_name", "modified_epoch", "column_title",
+    "date_ymd", "create_time_hm", "year_month", "buy_url", "encrypted", "key_id", "summary_html",
+    "summary_text", "word_count", "reading_time_minutes", "toc",
+];
+
+/// 强类型的 front matter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub slug: Option<String>,
+    pub layout: Option<Layout>,
+    pub description: Option<String>,
+    /// `createTime`（缺省时取 `date`）的原始值
+    pub create_time: Option<String>,
+    pub tags: Vec<String>,
+    pub cover: Option<String>,
+    pub icon: Option<String>,
+    /// 专栏/书籍 README 中的章节目录（保留缩进以表示层级）
+    pub catalog: Vec<String>,
+    pub weight: Option<i64>,
+    pub draft: bool,
+    pub is_free: Option<bool>,
+    pub doc_id: Option<String>,
+    /// 其余字段原样透传
+    pub extra: BTreeMap<String, Value>,
+}
+
+fn value_str(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn value_bool(v: &Value) -> Option<bool> {
+    match v {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" => Some(true),
+            "false" | "no" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn value_i64(v: &Value) -> Option<i64> {
+    match v {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_str_list(v: &Value) -> Vec<String> {
+    match v {
+        Value::Array(arr) => arr.iter().filter_map(value_str).collect(),
+        Value::String(s) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+    s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+impl FrontMatter {
+    /// 从 JSON 元数据构建（值类型宽松：数字/字符串/布尔字符串均可识别）
+    pub fn from_value(data: &Value) -> FrontMatter {
+        let obj = match data.as_object() {
+            Some(obj) => obj,
+            None => return FrontMatter::default(),
+        };
+        let get = |k: &str| obj.get(k);
+
+        let mut tags: Vec<String> = get("tags")
+            .map(value_str_list)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        // 去重（保持顺序）
+        let mut seen = std::collections::HashSet::new();
+        tags.retain(|t| seen.insert(t.clone()));
+
+        let typed = [
+            "title", "slug", "layout", "description", "createTime", "tags", "cover", "icon",
+            "catalog", "weight", "draft", "is_free", "doc_id",
+        ];
+        let extra = obj
+            .iter()
+            .filter(|(k, _)| !typed.contains(&k.as_str()) && !COMPUTED_KEYS.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        FrontMatter {
+            title: non_empty(get("title").and_then(value_str)),
+            slug: non_empty(get("slug").and_then(value_str)),
+            layout: non_empty(get("layout").and_then(value_str)).map(|s| Layout::parse(&s)),
+            description: get("description").and_then(value_str),
+            create_time: non_empty(get("createTime").or_else(|| get("date")).and_then(value_str)),
+            tags,
+            cover: non_empty(get("cover").and_then(value_str)),
+            icon: non_empty(get("icon").and_then(value_str)),
+            catalog: get("catalog").map(value_str_list).unwrap_or_default(),
+            weight: get("weight").and_then(value_i64),
+            draft: get("draft").and_then(value_bool).unwrap_or(false),
+            is_free: get("is_free").and_then(value_bool),
+            doc_id: non_empty(get("doc_id").and_then(value_str)),
+            extra,
+        }
+    }
+}
+
+/// front matter 诊断信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 相关字段名
+    pub key: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 字符级编辑距离
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// 在候选中查找与输入最接近的名称（短名称只容忍 1 处差异）
+fn closest<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let limit = if input.chars().count() <= 4 { 1 } else { 2 };
+    candidates
+        .iter()
+        .map(|c| (edit_distance(&input.to_lowercase(), &c.to_lowercase()), *c))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// 检查 front matter：疑似拼写错误的字段与未知布局
+pub fn diagnose(metadata: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let obj = match metadata.as_object() {
+        Some(obj) => obj,
+        None => return diagnostics,
+    };
+
+    for key in obj.keys() {
+        if KNOWN_KEYS.contains(&key.as_str()) || COMPUTED_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        if let Some(suggestion) = closest(key, KNOWN_KEYS) {
+            diagnostics.push(Diagnostic {
+                key: key.clone(),
+                message: format!("未知字段 `{}`，是否想写 `{}`？", key, suggestion),
+            });
+        }
+    }
+
+    if let Some(Layout::Other(name)) = FrontMatter::from_value(metadata).layout {
+        let message = match closest(&name, &Layout::BUILTIN) {
+            Some(suggestion) => format!("未知布局 `{}`，是否想写 `{}`？", name, suggestion),
+            None => format!("未知布局 `{}`，主题中需提供 {}.html 模板", name, name),
+        };
+        diagnostics.push(Diagnostic {
+            key: "layout".to_string(),
+            message,
+        });
+    }
+
+    diagnostics
+}
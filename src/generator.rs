@@ -2,22 +2,41 @@
 //!
 //! 负责生成静态网站文件
 
+use crate::cache::PostCache;
 use crate::config::Config;
 use crate::error::{Error, Result};
-use crate::post::{Post, PostParser};
+use crate::feed::{self, FeedFormat, FeedMeta};
+use crate::highlight::HighlightOptions;
+use crate::manifest::{self, BuildManifest, ManifestDiff, MANIFEST_FILE};
+use crate::post::{ParseOptions, Post, PostParser};
+use crate::report::{describe_error, missing_assets, BuildIssue, BuildReport, IssueKind};
+use crate::search::{self, SearchIndex};
+use crate::sitemap::{self, SitemapEntry, SitemapOptions};
 use crate::template::TemplateEngine;
-use crate::utils::{copy_dir_recursive, strip_html_tags};
-use chrono::TimeZone;
+use crate::utils::copy_dir_recursive;
 
+use rayon::prelude::*;
 use serde_json::Value;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+/// 内存文件系统：站点路径（如 `/blog/a.html`）-> 文件内容
+pub type MemFs = std::sync::Arc<std::sync::RwLock<std::collections::HashMap<String, Vec<u8>>>>;
 
 /// 静态文件生成器
 pub struct Generator {
     #[allow(dead_code)]
     config: Config,
     template_engine: TemplateEngine,
-    pub mem_fs: Option<std::sync::Arc<std::sync::RwLock<std::collections::HashMap<String, Vec<u8>>>>>,
+    pub mem_fs: Option<MemFs>,
+    /// 本次构建的输出目录（内存文件系统按相对它的路径存放文件）
+    output_root: std::sync::Mutex<std::path::PathBuf>,
+    /// 严格模式：存在解析、模板或资源问题时构建失败
+    strict: bool,
+    /// 本次构建收集到的问题
+    report: std::sync::Mutex<BuildReport>,
+    /// 覆盖同名 doc_id 的内容密钥（密钥轮换期间新密钥只保存在内存中）
+    key_overrides: std::sync::Mutex<HashMap<String, String>>,
 }
 
 impl Generator {
@@ -29,11 +48,21 @@ impl Generator {
             config,
             template_engine,
             mem_fs: None,
+            output_root: std::sync::Mutex::new(std::path::PathBuf::new()),
+            strict: false,
+            report: std::sync::Mutex::new(BuildReport::default()),
+            key_overrides: std::sync::Mutex::new(HashMap::new()),
         })
     }
 
-    /// 注入虚拟内存文件系统以供 Serve 阶段进行内存无落盘编译
-    pub fn with_mem_fs(mut self, mem_fs: std::sync::Arc<std::sync::RwLock<std::collections::HashMap<String, Vec<u8>>>>) -> Self {
+    /// 开启严格模式（`build --strict`）
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// 注入虚拟内存文件系统以供 Serve 阶段进行内存无落盘编译（`serve --in-memory`），输出目录不会被创建或改动
+    pub fn with_mem_fs(mut self, mem_fs: MemFs) -> Self {
         self.mem_fs = Some(mem_fs);
         self
     }
@@ -46,25 +75,123 @@ impl Generator {
         self.template_engine.render_post(post, all_posts)
     }
 
+    /// 列出全部文章，并对付费专栏文章做构建期加密（全量与增量构建共用）
+    ///
+    /// 启用 `[cache]` 时，内容与解析选项均未变的文章直接读取 `.rustpress-cache` 中的解析结果。
+    /// 正文中指向 `.md` 源文件的相对链接改写为生成后的 URL。解析失败的文章、缺失的引用资源、无法转换的公式
+    /// 与找不到目标的链接记入构建报告；资源检查需在静态资源拷贝到 output_dir 之后进行，
+    /// 且必须在加密之前（加密后正文中的图片与链接不可见）。付费文章按 `key_overrides` 覆盖后的内容密钥加密，
+    /// 其明文解析结果不写入缓存。
+    fn load_posts(&self, md_dir: &Path, output_dir: &Path) -> Result<Vec<Post>> {
+        let options = ParseOptions::from_config(&self.config);
+        let cache = crate::cache::enabled(&self.config)
+            .then(|| PostCache::open(crate::cache::cache_dir(md_dir), &options));
+        let (mut posts, issues) = PostParser::list_posts_cached(md_dir, &options, cache.as_ref())?;
+        self.record_issues(issues);
+        self.record_issues(crate::links::rewrite_md_links(&mut posts, md_dir));
+        self.write_highlight_css(output_dir, &options.highlight)?;
+        for post in &posts {
+            let source = post.source_path().unwrap_or_default();
+            self.record_issues(missing_assets(post, |site_path| self.output_exists(output_dir, site_path)).into_iter().map(|asset| {
+                BuildIssue::new(IssueKind::Asset, source, format!("引用的资源不存在: {}", asset))
+            }));
+            self.record_issues(crate::math::unsupported(post.content().unwrap_or_default()).into_iter().map(|message| {
+                BuildIssue::new(IssueKind::Math, source, format!("公式无法转换: {}", message))
+            }));
+        }
+        let mut keys = crate::crypto::load_doc_keys(&self.config, &crate::utils::project_root(md_dir))?;
+        keys.extend(self.key_overrides.lock().unwrap().clone());
+        crate::crypto::encrypt_paid_posts_with_keys(&self.config, &keys, &mut posts)?;
+        if let Some(cache) = &cache {
+            // 付费文章的解析结果是明文正文，不能落盘
+            for post in posts.iter().filter(|p| p.data.get("encrypted").and_then(|v| v.as_bool()) == Some(true)) {
+                if let Some(key) = crate::manifest::source_key(md_dir, Path::new(post.source_path().unwrap_or_default())) {
+                    cache.remove(&key);
+                }
+            }
+            cache.save()?;
+            println!("文章解析缓存：命中 {} 篇，共缓存 {} 篇", cache.hits(), cache.len());
+        }
+        Ok(posts)
+    }
+
+    /// 启用构建时代码高亮时写出配色样式表（由 base 模板按 `site.highlight.enable` 引用）
+    fn write_highlight_css(&self, output_dir: &Path, options: &HighlightOptions) -> Result<()> {
+        if !options.enable {
+            return Ok(());
+        }
+        let path = output_dir.join(crate::highlight::CSS_PATH);
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+        self.write_file(path, &crate::highlight::theme_css(options)?)
+    }
+
+    fn record_issues<I: IntoIterator<Item = BuildIssue>>(&self, issues: I) {
+        self.report.lock().unwrap().extend(issues);
+    }
+
+    /// 渲染单篇文章页面；失败时记入构建报告并返回 None，避免单篇文章中断整个构建
+    fn render_post_or_record(&self, post: &Post, all_posts: &[Post]) -> Option<String> {
+        match self.template_engine.render_post(post, all_posts) {
+            Ok(html) => Some(html),
+            Err(e) => {
+                let source = post.source_path().or(post.url()).unwrap_or_default();
+                self.record_issues([BuildIssue::new(IssueKind::Template, source, describe_error(&e))]);
+                None
+            }
+        }
+    }
+
+    /// 输出构建报告：宽松模式下打印汇总表，严格模式下存在问题即返回错误
+    ///
+    /// 报告保留到下次构建开始，供 [`issues`](Self::issues) 读取。
+    fn finish_report(&self) -> Result<()> {
+        let report = self.report.lock().unwrap();
+        if report.is_empty() {
+            return Ok(());
+        }
+        eprintln!("{}", report.summary_table());
+        if self.strict {
+            return Err(Error::Build(format!("严格模式下发现 {} 个问题", report.len())));
+        }
+        println!("警告: 以上 {} 个问题未阻断构建（使用 --strict 可使其构建失败）", report.len());
+        Ok(())
+    }
+
+    /// 最近一次构建收集到的问题（开发服务器据此在页面上提示构建错误）
+    pub fn issues(&self) -> Vec<BuildIssue> {
+        self.report.lock().unwrap().issues.clone()
+    }
+
+    /// 开始一次构建：清空上次的问题并记录输出目录
+    fn start_build(&self, output_dir: &Path) {
+        self.report.lock().unwrap().issues.clear();
+        *self.output_root.lock().unwrap() = output_dir.to_path_buf();
+    }
+
+    /// 输出文件在内存文件系统中的站点路径（相对输出目录，以 `/` 开头）
+    fn mem_key(&self, path: &Path) -> String {
+        let root = self.output_root.lock().unwrap();
+        let rel = path.strip_prefix(&*root).unwrap_or(path);
+        let segments: Vec<_> = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_string_lossy()),
+                _ => None,
+            })
+            .collect();
+        format!("/{}", segments.join("/"))
+    }
+
     fn write_file<P: AsRef<Path>>(&self, path: P, content: &str) -> Result<()> {
         self.write_file_bytes(path, content.as_bytes())
     }
 
     fn write_file_bytes<P: AsRef<Path>>(&self, path: P, content: &[u8]) -> Result<()> {
         if let Some(mem_fs) = &self.mem_fs {
-            let path = path.as_ref();
-            let path_str = path.to_string_lossy().replace("\\", "/");
-            let key = if let Some(idx) = path_str.find("/public/") {
-                path_str[idx + 7..].to_string()
-            } else if let Some(idx) = path_str.find("public/") {
-                format!("/{}", &path_str[idx + 7..])
-            } else {
-                format!("/{}", path.file_name().unwrap_or_default().to_string_lossy())
-            };
-
-            println!("[DEBUG] 写入虚拟内存文件: {} (key: {})", path.display(), key);
-            let mut map = mem_fs.write().unwrap();
-            map.insert(key, content.to_vec());
+            let key = self.mem_key(path.as_ref());
+            mem_fs.write().unwrap().insert(key, content.to_vec());
             Ok(())
         } else {
             std::fs::write(path.as_ref(), content)?;
@@ -72,15 +199,93 @@ impl Generator {
         }
     }
 
+    /// 创建输出子目录（内存文件系统模式下无需创建）
+    fn create_dir_all<P: AsRef<Path>>(&self, dir: P) -> std::io::Result<()> {
+        if self.mem_fs.is_some() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(dir)
+    }
+
+    /// 删除输出子目录及其中全部文件
+    fn remove_output_dir(&self, dir: &Path) {
+        if let Some(mem_fs) = &self.mem_fs {
+            let prefix = format!("{}/", self.mem_key(dir));
+            mem_fs.write().unwrap().retain(|key, _| !key.starts_with(&prefix));
+        } else if dir.exists() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    /// 输出目录中是否存在指定站点路径的文件
+    fn output_exists(&self, output_dir: &Path, site_path: &str) -> bool {
+        let path = output_dir.join(site_path.trim_start_matches('/'));
+        match &self.mem_fs {
+            Some(mem_fs) => mem_fs.read().unwrap().contains_key(&self.mem_key(&path)),
+            None => path.exists(),
+        }
+    }
+
+    /// 读取上次构建的清单（内存文件系统模式下从内存读取）
+    fn load_manifest(&self, output_dir: &Path) -> Option<BuildManifest> {
+        let path = output_dir.join(MANIFEST_FILE);
+        match &self.mem_fs {
+            Some(mem_fs) => BuildManifest::parse(std::str::from_utf8(mem_fs.read().unwrap().get(&self.mem_key(&path))?).ok()?),
+            None => BuildManifest::load(path),
+        }
+    }
+
+    fn save_manifest(&self, manifest: &BuildManifest, output_dir: &Path) -> Result<()> {
+        let path = output_dir.join(MANIFEST_FILE);
+        match &self.mem_fs {
+            Some(_) => self.write_file(path, &serde_json::to_string_pretty(manifest)?),
+            None => manifest.save(path),
+        }
+    }
+
+    /// 拷贝静态资源：二进制内置的主题静态资源、本地主题静态目录（覆盖内置）与源目录下的非 Markdown 文件
+    fn copy_static_assets(&self, md_dir: &Path, output_dir: &Path, theme_static_dir: &Path) -> Result<()> {
+        if self.mem_fs.is_none() {
+            crate::utils::write_embedded_theme_static(output_dir)?;
+            if theme_static_dir.exists() {
+                copy_dir_recursive(theme_static_dir, output_dir)?;
+            }
+            // 递归复制源目录下的所有非 Markdown 且非隐藏文件，保持相对路径（在主题资源之后，以免被主题同名文件覆盖）
+            return crate::utils::copy_non_md_recursive_preserve_paths(md_dir, output_dir);
+        }
+
+        for rel in crate::utils::ThemeStaticAssets::iter() {
+            if let Some(file) = crate::utils::ThemeStaticAssets::get(&rel) {
+                self.write_file_bytes(output_dir.join(rel.as_ref()), &file.data)?;
+            }
+        }
+        let local_files = walkdir::WalkDir::new(theme_static_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| (theme_static_dir, e.into_path()))
+            .chain(crate::utils::non_md_files(md_dir).into_iter().map(|path| (md_dir, path)));
+        for (root, path) in local_files {
+            let content = std::fs::read(&path)
+                .map_err(|e| Error::Other(format!("无法读取静态文件 {:?}: {}", path, e)))?;
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            self.write_file_bytes(output_dir.join(rel), &content)?;
+        }
+        Ok(())
+    }
+
     /// 构建网站
     pub fn build<P: AsRef<Path>, Q: AsRef<Path>>(&self, md_dir: P, output_dir: Q) -> Result<()> {
         let md_dir = md_dir.as_ref();
         let output_dir = output_dir.as_ref();
 
         println!("正在构建网站...");
+        self.start_build(output_dir);
 
         // 清理并重新创建输出目录
-        if output_dir.exists() {
+        if let Some(mem_fs) = &self.mem_fs {
+            mem_fs.write().unwrap().clear();
+        } else if output_dir.exists() {
             let mut retries = 5;
             loop {
                 match std::fs::remove_dir_all(output_dir) {
@@ -95,25 +300,17 @@ impl Generator {
                 }
             }
         }
-        std::fs::create_dir_all(output_dir)?;
+        self.create_dir_all(output_dir)?;
 
-        // 写出打包在二进制中的主题静态资源到输出目录（若存在本地静态目录，随后拷贝以覆盖）
-        crate::utils::write_embedded_theme_static(output_dir)?;
-        // 若本地存在主题静态目录，拷贝以覆盖（保留用户覆盖能力）
+        // 写出打包在二进制中的主题静态资源，再拷贝本地主题静态目录以覆盖（保留用户覆盖能力），最后复制源目录下的非 Markdown 文件
         let runtime_paths = crate::utils::RuntimePathsBuilder::new()
             .md_dir(md_dir)
             .theme_name(self.config.theme_name())
             .build();
-        let theme_static_dir = runtime_paths.theme_static_dir;
-        if theme_static_dir.exists() {
-            copy_dir_recursive(&theme_static_dir, output_dir)?;
-        }
-
-        // 递归复制源目录下的所有非 Markdown 且非隐藏文件，保持相对路径（覆盖原有顶层 assets 与根层非 md 的拷贝策略）
-        crate::utils::copy_non_md_recursive_preserve_paths(md_dir, output_dir)?;
+        self.copy_static_assets(md_dir, output_dir, &runtime_paths.theme_static_dir)?;
 
         // 列出所有文章
-        let posts = PostParser::list_posts(md_dir)?;
+        let posts = self.load_posts(md_dir, output_dir)?;
 
         // 首次构建时生成侧边栏数据（可手动编辑，写入优先项目根）
         crate::utils::ensure_sidebar_data(md_dir, &posts)?;
@@ -156,31 +353,13 @@ impl Generator {
         self.generate_index_pages(&posts, &all_tags, &all_categories, output_dir)?;
 
         // 渲染每篇文章
-        for post in &posts {
-            // 如果是站点首页对应的 README.md (categories为空且slug为index)，则跳过，因为首页由 render_home_page 单独渲染并写入 index.html
-            if post.categories().is_empty() && post.slug() == Some("index") {
-                continue;
-            }
-            let post_html = self.template_engine.render_post(post, &posts)?;
-            if let Some(url) = post.url() {
-                let rel_path = url.trim_start_matches('/');
-                let out_path = output_dir.join(rel_path);
-
-                if let Some(parent) = out_path.parent() {
-                    std::fs::create_dir_all(parent).map_err(|e| {
-                        Error::Other(format!("无法创建文章输出目录 {:?}: {}", parent, e))
-                    })?;
-                }
-
-                self.write_file(&out_path, &post_html)
-                    .map_err(|e| Error::Other(format!("无法写入文章文件 {:?}: {}", out_path, e)))?;
-            }
-        }
+        let targets: Vec<&Post> = posts.iter().collect();
+        self.render_posts(&targets, &posts, output_dir)?;
 
         // 渲染标签页（双路写入以兼容 /tags.html 与 /tags/）
         let tags_html = self.template_engine.render_tags(&posts, &all_tags)?;
         let tags_dir = output_dir.join("tags");
-        std::fs::create_dir_all(&tags_dir)?;
+        self.create_dir_all(&tags_dir)?;
         self.write_file(tags_dir.join("index.html"), &tags_html)
             .map_err(|e| Error::Other(format!("无法写入 tags/index.html 标签页: {}", e)))?;
         self.write_file(output_dir.join("tags.html"), &tags_html)
@@ -197,7 +376,7 @@ impl Generator {
             self.template_engine
                 .render_categories(&posts, &all_categories, &all_tags)?;
         let categories_dir = output_dir.join("categories");
-        std::fs::create_dir_all(&categories_dir)?;
+        self.create_dir_all(&categories_dir)?;
         self.write_file(categories_dir.join("index.html"), &categories_html)
             .map_err(|e| Error::Other(format!("无法写入分类页: {}", e)))?;
 
@@ -207,7 +386,7 @@ impl Generator {
         // 渲染归档页
         let archives_html = self.template_engine.render_archives(&posts, &all_years)?;
         let archives_dir = output_dir.join("archives");
-        std::fs::create_dir_all(&archives_dir)?;
+        self.create_dir_all(&archives_dir)?;
         self.write_file(archives_dir.join("index.html"), &archives_html)
             .map_err(|e| Error::Other(format!("无法写入归档页: {}", e)))?;
 
@@ -224,7 +403,7 @@ impl Generator {
         // 渲染友链页面
         let friends_html = self.template_engine.render_friends(&posts)?;
         let friends_dir = output_dir.join("friends");
-        std::fs::create_dir_all(&friends_dir)?;
+        self.create_dir_all(&friends_dir)?;
         self.write_file(friends_dir.join("index.html"), &friends_html)
             .map_err(|e| Error::Other(format!("无法写入友链页面: {}", e)))?;
 
@@ -243,17 +422,8 @@ impl Generator {
         // 生成搜索索引
         self.generate_search_index(&posts, output_dir)?;
 
-        // 生成 RSS（按开关）
-        let rss_enabled = self
-            .config
-            .data
-            .get("features")
-            .and_then(|v| v.get("rss"))
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
-        if rss_enabled {
-            self.generate_rss(&posts, output_dir)?;
-        }
+        // 生成订阅源（RSS / Atom / JSON Feed，按开关）
+        self.generate_feeds(&posts, output_dir)?;
 
         // 生成 Sitemap（按开关）
         let sitemap_enabled = self
@@ -263,13 +433,13 @@ impl Generator {
             .and_then(|v| v.get("sitemap"))
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
-        if sitemap_enabled {
-            self.generate_sitemap(&posts, output_dir)?;
-        }
-
-        println!("网站构建成功！静态文件已生成到 {:?} 目录。", output_dir);
+        let sitemap_file = if sitemap_enabled {
+            self.generate_sitemap(&posts, output_dir)?
+        } else {
+            sitemap::SITEMAP_FILE.to_string()
+        };
 
-        // 生成 robots.txt
+        // 生成 robots.txt（在汇总问题之前，宽松与严格模式写出相同的文件）
         let domain = self
             .config
             .site()
@@ -280,7 +450,7 @@ impl Generator {
             .to_string();
 
         if !domain.is_empty() {
-            let robots_txt = format!("User-agent: *\nAllow: /\n\nSitemap: {}/sitemap.xml", domain);
+            let robots_txt = format!("User-agent: *\nAllow: /\n\nSitemap: {}/{}", domain, sitemap_file);
             self.write_file(output_dir.join("robots.txt"), &robots_txt)
                 .map_err(|e| Error::Other(format!("无法写入 robots.txt: {}", e)))?;
             println!("robots.txt 已生成");
@@ -288,6 +458,14 @@ impl Generator {
             println!("警告: 未配置 site.domain，跳过生成 robots.txt");
         }
 
+        // 严格模式失败时不写清单，下次增量构建仍会重新生成出问题的页面
+        self.finish_report()?;
+
+        // 记录内容哈希与依赖图，供后续增量构建使用
+        let manifest = BuildManifest::collect(md_dir, &runtime_paths.theme_templates_dir, &self.config, &posts)?;
+        self.save_manifest(&manifest, output_dir)?;
+
+        println!("网站构建成功！静态文件已生成到 {:?} 目录。", output_dir);
         Ok(())
     }
 
@@ -321,8 +499,8 @@ impl Generator {
             }
         }
 
-        // 为每个标签生成分页页面
-        for tag_name in tag_set.into_iter() {
+        // 为每个标签生成分页页面（各标签并行渲染）
+        tag_set.into_par_iter().try_for_each(|tag_name| -> Result<()> {
             // 筛选并按日期降序排序该标签下的文章
             let mut tag_posts: Vec<&Post> = posts
                 .iter()
@@ -343,8 +521,9 @@ impl Generator {
 
             // 创建输出目录：public/tags/{tag_name}/
             let tag_dir = output_dir.join("tags").join(&tag_name);
-            std::fs::create_dir_all(&tag_dir)
+            self.create_dir_all(&tag_dir)
                 .map_err(|e| Error::Other(format!("无法创建标签目录 {:?}: {}", tag_dir, e)))?;
+            self.generate_scoped_feeds(&tag_posts, &feed::scoped_dir(&["tags", &tag_name]), &tag_name, output_dir)?;
 
             // 逐页渲染并输出：倒分页（最大页为最新，生成为 index.html）
             for page in 1..=total_pages {
@@ -372,7 +551,8 @@ impl Generator {
                     })?;
                 }
             }
-        }
+            Ok(())
+        })?;
 
         println!("已生成标签分页页面：路径模式 tags/<name>/index[.N].html");
         Ok(())
@@ -414,7 +594,7 @@ impl Generator {
 
         // 4. 创建专栏标签存储目录：public/columns/tags/
         let col_tags_dir = output_path.join("columns").join("tags");
-        std::fs::create_dir_all(&col_tags_dir)
+        self.create_dir_all(&col_tags_dir)
             .map_err(|e| Error::Other(format!("无法创建专栏标签目录 {:?}: {}", col_tags_dir, e)))?;
 
         // 5. 生成各个标签页面
@@ -432,9 +612,27 @@ impl Generator {
                 .cloned()
                 .collect();
 
+            // 专栏标签订阅源：收录这些专栏下的全部章节
+            let column_dirs: Vec<Vec<String>> = filtered_columns
+                .iter()
+                .filter_map(|col| col.get("categories").and_then(|v| v.as_array()))
+                .map(|cats| cats.iter().filter_map(|c| c.as_str().map(str::to_string)).collect())
+                .collect();
+            let mut tag_posts: Vec<&Post> = posts
+                .iter()
+                .filter(|p| {
+                    let cats = p.categories();
+                    column_dirs.iter().any(|dir| cats.len() >= dir.len() && cats[..dir.len()] == dir[..])
+                })
+                .collect();
+            tag_posts.sort_by(|a, b| b.date().unwrap_or("").cmp(a.date().unwrap_or("")));
+            let feed_dir = feed::scoped_dir(&["columns", "tags", tag]);
+            self.generate_scoped_feeds(&tag_posts, &feed_dir, tag, output_path)?;
+
             let mut context = self.template_engine.create_base_context();
             context.insert("columns", &filtered_columns);
             context.insert("active_tag", &tag);
+            context.insert("feed_links", &feed::alternate_links(&self.config, &feed_dir, tag));
             context.insert("columns_count", &columns_count);
 
             // 收集并排序所有唯一标签
@@ -508,8 +706,9 @@ impl Generator {
             };
 
             let tag_dir = output_dir.join("tags").join(tag_name);
-            std::fs::create_dir_all(&tag_dir)
+            self.create_dir_all(&tag_dir)
                 .map_err(|e| Error::Other(format!("无法创建标签目录 {:?}: {}", tag_dir, e)))?;
+            self.generate_scoped_feeds(&tag_posts, &feed::scoped_dir(&["tags", tag_name]), tag_name, output_dir)?;
 
             for page in 1..=total_pages {
                 let html = self.template_engine.render_tag_page(
@@ -518,17 +717,22 @@ impl Generator {
                     page,
                     posts_per_page,
                 )?;
-                let file_name = if page == total_pages {
-                    "index.html".to_string()
-                } else {
-                    format!("index{}.html", page)
-                };
-                let out_path = tag_dir.join(file_name);
-                self.write_file(&out_path, &html).map_err(|e| {
-                    Error::Other(format!("无法写入标签分页文件 {:?}: {}", out_path, e))
-                })?;
-                let rel = out_path.strip_prefix(output_dir).unwrap_or(&out_path);
-                rebuilt_paths.push(format!("/{}", rel.to_string_lossy()));
+                // 最新一页写为 index.html，多页时另写 indexN.html（与全量构建一致）
+                let mut file_names = Vec::new();
+                if page == total_pages {
+                    file_names.push("index.html".to_string());
+                }
+                if page != total_pages || total_pages > 1 {
+                    file_names.push(format!("index{}.html", page));
+                }
+                for file_name in file_names {
+                    let out_path = tag_dir.join(file_name);
+                    self.write_file(&out_path, &html).map_err(|e| {
+                        Error::Other(format!("无法写入标签分页文件 {:?}: {}", out_path, e))
+                    })?;
+                    let rel = out_path.strip_prefix(output_dir).unwrap_or(&out_path);
+                    rebuilt_paths.push(format!("/{}", rel.to_string_lossy()));
+                }
             }
             println!(
                 "标签重建: '{}' 共 {} 页 -> {}",
@@ -576,8 +780,8 @@ impl Generator {
             }
         }
 
-        // 为每个分类路径生成分页页面（倒分页）
-        for category_path in category_paths.into_iter() {
+        // 为每个分类路径生成分页页面（倒分页，各分类并行渲染）
+        category_paths.into_par_iter().try_for_each(|category_path| -> Result<()> {
             // 获取该分类路径下的所有文章，并按日期降序排序（最新在前）
             let mut category_posts: Vec<&Post> = posts
                 .iter()
@@ -595,8 +799,9 @@ impl Generator {
 
             // 构建分类目录路径
             let category_dir = output_dir.join(category_path.join("/"));
-            std::fs::create_dir_all(&category_dir)
+            self.create_dir_all(&category_dir)
                 .map_err(|e| Error::Other(format!("无法创建分类目录 {:?}: {}", category_dir, e)))?;
+            self.generate_category_feeds(&category_posts, &category_path, output_dir)?;
 
             // 计算总页数
             let total_posts = category_posts.len();
@@ -639,7 +844,8 @@ impl Generator {
                     })?;
                 }
             }
-        }
+            Ok(())
+        })?;
 
         println!("已生成分类分页页面：路径模式 <category-path>/index[.N].html");
         Ok(())
@@ -685,8 +891,9 @@ impl Generator {
             });
 
             let category_dir = output_dir.join(category_path.join("/"));
-            std::fs::create_dir_all(&category_dir)
+            self.create_dir_all(&category_dir)
                 .map_err(|e| Error::Other(format!("无法创建分类目录 {:?}: {}", category_dir, e)))?;
+            self.generate_category_feeds(&category_posts, category_path, output_dir)?;
 
             let total_posts = category_posts.len();
             let total_pages = if total_posts == 0 {
@@ -707,20 +914,22 @@ impl Generator {
                     page,
                     posts_per_page,
                 )?;
-                let file_name = if page == total_pages {
-                    if has_manual_index {
-                        continue;
-                    }
-                    "index.html".to_string()
-                } else {
-                    format!("index{}.html", page)
-                };
-                let out_path = category_dir.join(file_name);
-                self.write_file(&out_path, &html).map_err(|e| {
-                    Error::Other(format!("无法写入分类分页文件 {:?}: {}", out_path, e))
-                })?;
-                let rel = out_path.strip_prefix(output_dir).unwrap_or(&out_path);
-                rebuilt_paths.push(format!("/{}", rel.to_string_lossy()));
+                // 最新一页写为 index.html（已有手动 index 时跳过），多页时另写 indexN.html（与全量构建一致）
+                let mut file_names = Vec::new();
+                if page == total_pages && !has_manual_index {
+                    file_names.push("index.html".to_string());
+                }
+                if page != total_pages || total_pages > 1 {
+                    file_names.push(format!("index{}.html", page));
+                }
+                for file_name in file_names {
+                    let out_path = category_dir.join(file_name);
+                    self.write_file(&out_path, &html).map_err(|e| {
+                        Error::Other(format!("无法写入分类分页文件 {:?}: {}", out_path, e))
+                    })?;
+                    let rel = out_path.strip_prefix(output_dir).unwrap_or(&out_path);
+                    rebuilt_paths.push(format!("/{}", rel.to_string_lossy()));
+                }
             }
             println!(
                 "分类重建: '{}' 共 {} 页 -> {}",
@@ -760,8 +969,8 @@ impl Generator {
         }
 
         // 生成所有分页页面为根目录文件 index{n}.html
-        // 从第 1 页到 total_pages 页
-        for page in 1..=total_pages {
+        // 从第 1 页到 total_pages 页（并行渲染）
+        (1..=total_pages).into_par_iter().try_for_each(|page| {
             let page_html = self
                 .template_engine
                 .render_home_page(posts, all_tags, all_categories, page)
@@ -771,8 +980,8 @@ impl Generator {
             let page_file = output_dir.join(format!("index{}.html", page));
 
             self.write_file(&page_file, &page_html)
-                .map_err(|e| Error::Other(format!("无法写入分页文件 {:?}: {}", page_file, e)))?;
-        }
+                .map_err(|e| Error::Other(format!("无法写入分页文件 {:?}: {}", page_file, e)))
+        })?;
 
         println!(
             "已生成首页分页页面：共{}页，路径 index{{n}}.html（首页为 index.html）",
@@ -855,116 +1064,83 @@ impl Generator {
 
     /// 生成搜索索引
     fn generate_search_index<P: AsRef<Path>>(&self, posts: &[Post], output_dir: P) -> Result<()> {
-        let output_dir = output_dir.as_ref();
-        let mut search_data = Vec::new();
-
-        for (i, post) in posts.iter().enumerate() {
-            // 提取文章内容，移除HTML标签
-            let content = post.content().unwrap_or("");
-            let content_with_spaces = content
-                .replace("<", " <") // 在标签前添加空格
-                .replace(">", "> "); // 在标签后添加空格
-
-            let content_text = strip_html_tags(&content_with_spaces);
-
-            let slug = post.slug().unwrap_or("");
-            let url = post.url().unwrap_or("");
-
-            let search_item = serde_json::json!({
-                "id": i,
-                "title": post.title().unwrap_or(""),
-                "content": content_text,
-                "tags": post.tags(),
-                "categories": post.categories(),
-                "slug": slug,
-                "date": post.date().unwrap_or(""),
-                "url": url
-            });
-            search_data.push(search_item);
+        let index_dir = output_dir.as_ref().join(search::INDEX_DIR);
+        // 词项变化后旧分片不再被清单引用，整体重建以免残留
+        self.remove_output_dir(&index_dir);
+        self.create_dir_all(&index_dir)
+            .map_err(|e| Error::Other(format!("无法创建搜索索引目录 {:?}: {}", index_dir, e)))?;
+
+        let index = SearchIndex::build(posts);
+        for (name, content) in &index.files() {
+            self.write_file(index_dir.join(name), content)
+                .map_err(|e| Error::Other(format!("无法写入搜索索引文件 {}: {}", name, e)))?;
         }
 
-        let search_json = serde_json::to_string_pretty(&search_data)
-            .map_err(|e| Error::Other(format!("无法序列化搜索数据: {}", e)))?;
-
-        self.write_file(output_dir.join("search.json"), &search_json)
-            .map_err(|e| Error::Other(format!("无法写入搜索索引文件: {}", e)))?;
-
-        println!("搜索索引已生成：{:?}/search.json", output_dir);
+        println!(
+            "搜索索引已生成：{:?}（{} 篇文章，{} 个分片）",
+            index_dir,
+            index.docs.len(),
+            index.shards().len()
+        );
 
         Ok(())
     }
 
-    /// 生成 RSS (RSS 2.0 简版)
-    fn generate_rss<P: AsRef<Path>>(&self, posts: &[Post], output_dir: P) -> Result<()> {
+    /// 生成订阅源（RSS / Atom / JSON Feed，各格式按 `[features]` 开关）
+    fn generate_feeds<P: AsRef<Path>>(&self, posts: &[Post], output_dir: P) -> Result<()> {
         let output_dir = output_dir.as_ref();
-
-        // 站点信息
-        let site = self.config.data.get("site");
-        let site_name = site
-            .and_then(|v| v.get("name"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("RustPress Blog");
-        let site_desc = site
-            .and_then(|v| v.get("description"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("A RustPress site");
-        // 优先使用自定义域名字段，其次回退 base_url
-        let base_url = site
-            .and_then(|v| v.get("domain"))
-            .and_then(|v| v.as_str())
-            .or_else(|| {
-                site.and_then(|v| v.get("base_url"))
-                    .and_then(|v| v.as_str())
-            })
-            .unwrap_or("");
-        let base = base_url.trim_end_matches('/');
-
-        // 简单 XML 转义函数
-        fn escape_xml(s: &str) -> String {
-            s.replace('&', "&amp;")
-                .replace('<', "&lt;")
-                .replace('>', "&gt;")
-                .replace('"', "&quot;")
-                .replace('\'', "&apos;")
+        let meta = FeedMeta::from_config(&self.config);
+        // 与范围订阅源一致，分类与专栏首页（README）不是文章
+        let posts: Vec<&Post> = posts.iter().filter(|p| p.slug() != Some("index")).collect();
+        for file_name in self.write_feeds(&meta, &posts, "/", output_dir)? {
+            println!("订阅源已生成：{:?}/{}", output_dir, file_name);
         }
+        Ok(())
+    }
 
-        let mut items_xml = String::new();
-        for post in posts {
-            let title = escape_xml(post.title().unwrap_or(""));
-            let path = post.url().unwrap_or("");
-            let link = format!("{}{}", base, path);
-
-            let content = post.content().unwrap_or("");
-            let content_with_spaces = content.replace('<', " <").replace('>', "> ");
-            let text = strip_html_tags(&content_with_spaces);
-            let summary: String = text.chars().take(400).collect();
-            let description = escape_xml(summary.trim());
-
-            let pub_date = post.date().unwrap_or("");
-            let guid = &link;
-
-            items_xml.push_str(&format!(
-                "  <item>\n    <title>{}</title>\n    <link>{}</link>\n    <guid isPermaLink=\"true\">{}</guid>\n    <description>{}</description>\n    <pubDate>{}</pubDate>\n  </item>\n",
-                title, link, guid, description, pub_date
-            ));
+    /// 在标签/分类/专栏列表页旁写出该范围的订阅源（`dir` 如 `/tags/Rust/`，文章已按日期降序）
+    fn generate_scoped_feeds(&self, posts: &[&Post], dir: &str, name: &str, output_dir: &Path) -> Result<()> {
+        if !crate::feed::taxonomy_feeds_enabled(&self.config) {
+            return Ok(());
         }
+        let meta = FeedMeta::from_config(&self.config).scoped(name);
+        // 分类首页（README）不是文章，不进入订阅源
+        let posts: Vec<&Post> = posts.iter().copied().filter(|p| p.slug() != Some("index")).collect();
+        self.write_feeds(&meta, &posts, dir, output_dir)?;
+        Ok(())
+    }
 
-        let rss_xml = format!(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n  <title>{}</title>\n  <link>{}</link>\n  <description>{}</description>\n{}\n</channel>\n</rss>\n",
-            escape_xml(site_name),
-            escape_xml(base_url),
-            escape_xml(site_desc),
-            items_xml
-        );
+    /// 分类订阅源：`columns/N` 以专栏标题命名，其余以分类名命名
+    fn generate_category_feeds(&self, posts: &[&Post], category_path: &[String], output_dir: &Path) -> Result<()> {
+        let segments: Vec<&str> = category_path.iter().map(String::as_str).collect();
+        let column_title = posts
+            .iter()
+            .find(|p| p.slug() == Some("index") && p.categories() == category_path)
+            .and_then(|p| p.title());
+        let name = column_title.unwrap_or_else(|| segments.last().copied().unwrap_or_default());
+        self.generate_scoped_feeds(posts, &feed::scoped_dir(&segments), name, output_dir)
+    }
 
-        self.write_file(output_dir.join("rss.xml"), &rss_xml)
-            .map_err(|e| Error::Other(format!("无法写入RSS文件: {}", e)))?;
-        println!("RSS 已生成：{:?}/rss.xml", output_dir);
-        Ok(())
+    /// 将已启用格式的订阅源写入站内目录 `dir`，返回写出的文件名
+    fn write_feeds(&self, meta: &FeedMeta, posts: &[&Post], dir: &str, output_dir: &Path) -> Result<Vec<String>> {
+        let target_dir = output_dir.join(dir.trim_matches('/'));
+        self.create_dir_all(&target_dir)
+            .map_err(|e| Error::Other(format!("无法创建订阅源目录 {:?}: {}", target_dir, e)))?;
+        let mut written = Vec::new();
+        for format in FeedFormat::enabled_formats(&self.config) {
+            let path = format!("{}{}", dir, format.file_name());
+            let content = crate::feed::render(format, meta, posts.iter().copied(), &path);
+            let out_path = target_dir.join(format.file_name());
+            self.write_file(&out_path, &content)
+                .map_err(|e| Error::Other(format!("无法写入订阅源 {:?}: {}", out_path, e)))?;
+            written.push(format.file_name().to_string());
+        }
+        Ok(written)
     }
 
-    /// 生成 Sitemap (包含首页分页、文章、标签分页、分类分页、年份归档与主要静态页)
-    fn generate_sitemap<P: AsRef<Path>>(&self, posts: &[Post], output_dir: P) -> Result<()> {
+    /// 生成 Sitemap (包含首页分页、文章、标签分页、分类分页、年份归档与主要静态页)，
+    /// 返回入口文件名（`sitemap.xml` 或拆分后的 `sitemap_index.xml`）
+    fn generate_sitemap<P: AsRef<Path>>(&self, posts: &[Post], output_dir: P) -> Result<String> {
         let output_dir = output_dir.as_ref();
 
         // 基础 URL
@@ -984,7 +1160,7 @@ impl Generator {
             .unwrap_or("");
         let base = base_url.trim_end_matches('/');
 
-        let mut urls: Vec<String> = Vec::new();
+        let mut urls: Vec<SitemapEntry> = Vec::new();
 
         // 首页与分页
         let posts_per_page = self
@@ -995,15 +1171,15 @@ impl Generator {
             .and_then(|v| v.as_integer())
             .unwrap_or(10) as usize;
         let total_pages = (posts.len() + posts_per_page - 1) / posts_per_page;
-        urls.push(format!("{}/index.html", base));
+        urls.push(SitemapEntry::new(format!("{}/index.html", base)));
         for page in 1..=std::cmp::max(1, total_pages.saturating_sub(1)) {
-            urls.push(format!("{}/index{}.html", base, page));
+            urls.push(SitemapEntry::new(format!("{}/index{}.html", base, page)));
         }
 
-        // 文章页
-        for post in posts {
-            let path = post.url().unwrap_or("");
-            urls.push(format!("{}{}", base, path));
+        // 文章页（`sitemap: false` 或 `noindex: true` 的除外）
+        let options = SitemapOptions::from_config(&self.config);
+        for post in posts.iter().filter(|p| !sitemap::is_excluded(p)) {
+            urls.push(SitemapEntry::from_post(post, base, &options));
         }
 
         // 标签分页（倒分页）
@@ -1048,7 +1224,7 @@ impl Generator {
                 } else {
                     format!("index{}.html", page)
                 };
-                urls.push(format!("{}/{}/{}/{}", base, tags_dir, tag, file_name));
+                urls.push(SitemapEntry::new(format!("{}/{}/{}/{}", base, tags_dir, tag, file_name)));
             }
         }
 
@@ -1102,7 +1278,7 @@ impl Generator {
                 } else {
                     format!("index{}.html", page)
                 };
-                urls.push(format!("{}/{}/{}", base, path.join("/"), file_name));
+                urls.push(SitemapEntry::new(format!("{}/{}/{}", base, path.join("/"), file_name)));
             }
         }
 
@@ -1116,7 +1292,7 @@ impl Generator {
             }
         }
         for year in years.into_iter() {
-            urls.push(format!("{}/archives/{}/", base, year));
+            urls.push(SitemapEntry::new(format!("{}/archives/{}/", base, year)));
         }
 
         // 主要静态页面
@@ -1124,7 +1300,7 @@ impl Generator {
             "about.html",
             "search.html",
         ] {
-            urls.push(format!("{}/{}", base, static_page));
+            urls.push(SitemapEntry::new(format!("{}/{}", base, static_page)));
         }
         
         // 列表概览页目录式 URL
@@ -1134,21 +1310,18 @@ impl Generator {
             "archives/",
             "friends/",
         ] {
-            urls.push(format!("{}/{}", base, list_page));
+            urls.push(SitemapEntry::new(format!("{}/{}", base, list_page)));
         }
 
-        // 生成XML
-        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
-        xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
-        for u in urls {
-            xml.push_str(&format!("  <url><loc>{}</loc></url>\n", u));
+        // 生成 XML（超过协议上限时拆分并生成索引）
+        let files = sitemap::render(&urls, &options, base);
+        for file in &files {
+            self.write_file(output_dir.join(&file.name), &file.content)
+                .map_err(|e| Error::Other(format!("无法写入Sitemap文件 {}: {}", file.name, e)))?;
         }
-        xml.push_str("</urlset>\n");
-
-        self.write_file(output_dir.join("sitemap.xml"), &xml)
-            .map_err(|e| Error::Other(format!("无法写入Sitemap文件: {}", e)))?;
-        println!("Sitemap 已生成：{:?}/sitemap.xml", output_dir);
-        Ok(())
+        let entry = files[0].name.clone();
+        println!("Sitemap 已生成：{:?}/{}", output_dir, entry);
+        Ok(entry)
     }
 
     /// 生成年份/月份归档页面
@@ -1161,7 +1334,7 @@ impl Generator {
 
         // 创建archives目录
         let archives_dir = output_dir.join("archives");
-        std::fs::create_dir_all(&archives_dir)?;
+        self.create_dir_all(&archives_dir)?;
 
         // 按年份分组文章
         let mut year_posts: std::collections::HashMap<String, Vec<&Post>> =
@@ -1180,14 +1353,14 @@ impl Generator {
             }
         }
 
-        // 为每个年份和月份生成归档页
-        for (year, year_post_list) in year_posts {
+        // 为每个年份和月份生成归档页（各年份并行渲染）
+        year_posts.into_par_iter().try_for_each(|(year, year_post_list)| -> Result<()> {
             // 1. 生成年份概览页：/archives/{year}/index.html
             let year_archive_html = self
                 .template_engine
                 .render_year_archive(&year_post_list, &year, None)?;
             let year_dir = archives_dir.join(&year);
-            std::fs::create_dir_all(&year_dir)?;
+            self.create_dir_all(&year_dir)?;
             let year_file_path = year_dir.join("index.html");
 
             self.write_file(&year_file_path, &year_archive_html)
@@ -1212,7 +1385,7 @@ impl Generator {
                     .template_engine
                     .render_year_archive(&month_post_list, &year, Some(&month))?;
                 let month_dir = year_dir.join(&month);
-                std::fs::create_dir_all(&month_dir)?;
+                self.create_dir_all(&month_dir)?;
                 let month_file_path = month_dir.join("index.html");
 
                 self.write_file(&month_file_path, &month_archive_html)
@@ -1220,9 +1393,8 @@ impl Generator {
 
                 println!("月份归档页已生成：{:?}", month_file_path);
             }
-        }
-
-        Ok(())
+            Ok(())
+        })
     }
 
     /// 仅为指定年份集合生成归档页面，并输出详细路径日志
@@ -1235,7 +1407,7 @@ impl Generator {
         let output_dir = output_dir.as_ref();
 
         let archives_dir = output_dir.join("archives");
-        std::fs::create_dir_all(&archives_dir)?;
+        self.create_dir_all(&archives_dir)?;
 
         let mut year_posts: std::collections::HashMap<String, Vec<&Post>> =
             std::collections::HashMap::new();
@@ -1257,7 +1429,7 @@ impl Generator {
             // 1. 年份概览页
             let html = self.template_engine.render_year_archive(&list, &year, None)?;
             let year_dir = archives_dir.join(&year);
-            std::fs::create_dir_all(&year_dir)?;
+            self.create_dir_all(&year_dir)?;
             let file_path = year_dir.join("index.html");
             self.write_file(&file_path, &html)
                 .map_err(|e| Error::Other(format!("无法写入年份归档页 {}: {}", year, e)))?;
@@ -1281,7 +1453,7 @@ impl Generator {
                     .template_engine
                     .render_year_archive(&month_post_list, &year, Some(&month))?;
                 let month_dir = year_dir.join(&month);
-                std::fs::create_dir_all(&month_dir)?;
+                self.create_dir_all(&month_dir)?;
                 let month_file_path = month_dir.join("index.html");
                 self.write_file(&month_file_path, &month_html)
                     .map_err(|e| Error::Other(format!("无法写入月份归档页 {}-{}: {}", year, month, e)))?;
@@ -1293,7 +1465,8 @@ impl Generator {
         Ok(())
     }
 
-    /// 增量构建网站：仅渲染 last_build_time 之后修改的文章页面，派生页全量刷新
+    /// 增量构建网站：比较构建清单中的内容哈希与依赖图，只重新生成受影响的输出，并删除源文件已消失的输出；
+    /// 没有清单，或配置、模板、程序版本变化时执行全量构建
     pub fn build_incremental<P: AsRef<Path>, Q: AsRef<Path>>(
         &self,
         md_dir: P,
@@ -1302,129 +1475,69 @@ impl Generator {
         let md_dir = md_dir.as_ref();
         let output_dir = output_dir.as_ref();
 
-        println!("正在进行增量构建...");
-
-        // 确保输出目录存在（不清理）
-        if !output_dir.exists() {
-            std::fs::create_dir_all(output_dir)?;
-        }
-
-        // 写出二进制内置的主题静态资源（覆盖更新），再拷贝本地以覆盖
-        crate::utils::write_embedded_theme_static(output_dir)?;
+        self.start_build(output_dir);
         let runtime_paths = crate::utils::RuntimePathsBuilder::new()
             .md_dir(md_dir)
             .theme_name(self.config.theme_name())
             .build();
-        let theme_static_dir = runtime_paths.theme_static_dir;
-        if theme_static_dir.exists() {
-            copy_dir_recursive(&theme_static_dir, output_dir)?;
+        let Some(previous) = self.load_manifest(output_dir) else {
+            println!("未找到构建清单，执行全量构建");
+            return self.build(md_dir, output_dir);
+        };
+        if let Some(reason) = previous.full_rebuild_reason(&self.config, &runtime_paths.theme_templates_dir)? {
+            println!("{}，执行全量构建", reason);
+            return self.build(md_dir, output_dir);
         }
 
-        // 递归复制源目录下的所有非 Markdown 且非隐藏文件，保持相对路径（增量模式也执行，以便更新附件）
-        crate::utils::copy_non_md_recursive_preserve_paths(md_dir, output_dir)?;
+        println!("正在进行增量构建...");
+
+        // 静态资源覆盖更新（增量模式也复制源目录下的文件，以免被主题同名文件覆盖）
+        let theme_static_dir = runtime_paths.theme_static_dir;
+        self.copy_static_assets(md_dir, output_dir, &theme_static_dir)?;
 
         // 列出所有文章（用于派生页计算）
-        let posts = PostParser::list_posts(md_dir)?;
+        let posts = self.load_posts(md_dir, output_dir)?;
 
         // 首次构建时生成侧边栏数据（可手动编辑）
         crate::utils::ensure_sidebar_data(md_dir, &posts)?;
 
-        // 读取 last_build_time -> epoch（使用 NaiveDateTime 解析，并按本地时区转换）
-        let last_build_epoch: i64 = {
-            let build_path = crate::utils::resolve_build_toml_path_read(md_dir);
-            if !build_path.exists() {
-                0
-            } else if let Ok(content) = std::fs::read_to_string(&build_path) {
-                if let Ok(root) = content.parse::<toml::Value>() {
-                    if let Some(s) = root.get("last_build_time").and_then(|v| v.as_str()) {
-                        if let Ok(naive) =
-                            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
-                        {
-                            if let Some(dt) = naive.and_local_timezone(chrono::Local).single() {
-                                dt.timestamp()
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        }
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            } else {
-                0
-            }
-        };
-
-        // 过滤出修改过的文章
-        let changed_posts: Vec<&Post> = posts
-            .iter()
-            .filter(|p| p.modified_epoch().unwrap_or(0) > last_build_epoch)
-            .collect();
-
+        let manifest = BuildManifest::collect(md_dir, &runtime_paths.theme_templates_dir, &self.config, &posts)?;
+        let diff = manifest.diff(&previous);
         println!(
-            "检测到修改文章 {} 篇（自 {} 之后）",
-            changed_posts.len(),
-            if last_build_epoch == 0 {
-                "初始构建".to_string()
-            } else {
-                chrono::Local
-                    .timestamp_opt(last_build_epoch, 0)
-                    .single()
-                    .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
-                    .unwrap_or_else(|| last_build_epoch.to_string())
-            }
+            "检测到变化的源文件 {} 个、删除 {} 个，受影响输出 {} 个，待清理输出 {} 个",
+            diff.changed.len(),
+            diff.removed.len(),
+            diff.affected.len(),
+            diff.stale.len()
         );
 
-        // 渲染被修改的文章
-        for &post in &changed_posts {
-            // 如果是站点首页对应的 README.md (categories为空且slug为index)，则跳过
-            if post.categories().is_empty() && post.slug() == Some("index") {
-                continue;
-            }
-            let post_html = self.template_engine.render_post(post, &posts)?;
-
-            if let Some(url) = post.url() {
-                let rel_path = url.trim_start_matches('/');
-                let out_path = output_dir.join(rel_path);
-
-                if let Some(parent) = out_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
-                self.write_file(&out_path, &post_html)
-                    .map_err(|e| Error::Other(format!("无法写入文章文件 {:?}: {}", out_path, e)))?;
-            }
+        if !diff.is_empty() {
+            self.regenerate_affected(md_dir, output_dir, &posts, &diff)?;
+            self.remove_stale_outputs(output_dir, &theme_static_dir, &diff.stale)?;
         }
 
-        // 计算受影响集合
-        let mut changed_tags: std::collections::HashSet<String> = std::collections::HashSet::new();
-        let mut changed_categories: std::collections::HashSet<Vec<String>> =
-            std::collections::HashSet::new();
-        let mut changed_years: std::collections::HashSet<String> = std::collections::HashSet::new();
+        // 严格模式失败时保留上次的清单，下次增量构建仍会重新生成本次受影响的输出
+        self.finish_report()?;
+        self.save_manifest(&manifest, output_dir)?;
+        println!("增量构建完成！已更新变化的文章与受影响派生页。");
+        Ok(())
+    }
 
-        for p in &changed_posts {
-            for t in p.tags() {
-                changed_tags.insert(t.clone());
-            }
-            let cats = p.categories();
-            if !cats.is_empty() {
-                for i in 1..=cats.len() {
-                    changed_categories.insert(cats[0..i].to_vec());
-                }
-            }
-            if let Some(date) = p.date() {
-                if date.len() >= 4 {
-                    changed_years.insert(date[0..4].to_string());
-                }
-            }
-        }
+    /// 按差异重新生成：变化的文章页面，以及受影响输出所属的首页分页、标签、分类、归档与订阅源
+    fn regenerate_affected(&self, md_dir: &Path, output_dir: &Path, posts: &[Post], diff: &ManifestDiff) -> Result<()> {
+        use std::collections::HashSet;
 
-        // 统计全集用于首页与一些模板数据
-        let all_tags = PostParser::collect_tags(&posts);
-        let all_categories = PostParser::generate_hierarchical_categories(&posts);
+        // 内容变化的文章，以及依赖图中受其他文章影响的文章页（同书侧边栏与目录、列表页、站内链接）
+        let changed_posts: Vec<&Post> = posts
+            .iter()
+            .filter(|p| {
+                p.source_path()
+                    .and_then(|s| manifest::source_key(md_dir, Path::new(s)))
+                    .is_some_and(|key| diff.changed.contains(&key))
+                    || manifest::page_output(p).is_some_and(|page| diff.affected.contains(&page))
+            })
+            .collect();
+        self.render_posts(&changed_posts, posts, output_dir)?;
 
         let posts_per_page = self
             .config
@@ -1433,117 +1546,102 @@ impl Generator {
             .and_then(|v| v.get("posts_per_page"))
             .and_then(|v| v.as_integer())
             .unwrap_or(10) as usize;
-        let total_pages = (posts.len() + posts_per_page - 1) / posts_per_page;
-
-        // 仅重建受影响的首页分页页码
-        let mut posts_sorted: Vec<&Post> = posts.iter().collect();
-        posts_sorted.sort_by(|a, b| {
-            let da = a.date().unwrap_or("");
-            let db = b.date().unwrap_or("");
-            db.cmp(da)
-        });
-        let mut affected_pages: std::collections::HashSet<usize> = std::collections::HashSet::new();
-        for cp in &changed_posts {
-            let cs = cp.slug().unwrap_or("");
-            let cc = cp.categories();
-            if let Some(pos) = posts_sorted
-                .iter()
-                .position(|p| p.slug().unwrap_or("") == cs && p.categories() == cc)
-            {
-                let page = total_pages.saturating_sub(pos / posts_per_page).max(1);
-                affected_pages.insert(page);
-            } else {
-                affected_pages.insert(total_pages);
-            }
-        }
-        // 如果总页数增加，需要补充新增的 indexN.html 页
-        let mut existing_max_n: usize = 0;
-        if let Ok(rd) = std::fs::read_dir(output_dir) {
-            for entry in rd.flatten() {
-                let name_os = entry.file_name();
-                if let Ok(name) = name_os.into_string() {
-                    if let Some(rest) = name
-                        .strip_prefix("index")
-                        .and_then(|s| s.strip_suffix(".html"))
-                    {
-                        if let Ok(n) = rest.parse::<usize>() {
-                            existing_max_n = existing_max_n.max(n);
-                        }
+        let total_pages = posts.len().div_ceil(posts_per_page);
+        let existing_tags: HashSet<String> = posts.iter().flat_map(|p| p.tags()).collect();
+        let existing_years: HashSet<&str> = posts.iter().filter_map(|p| p.date()?.get(0..4)).collect();
+        let existing_categories: HashSet<Vec<String>> = posts
+            .iter()
+            .flat_map(|p| {
+                let cats = p.categories();
+                (1..=cats.len()).map(move |i| cats[..i].to_vec())
+            })
+            .collect();
+        let feed_files: Vec<&str> = FeedFormat::enabled_formats(&self.config).iter().map(|f| f.file_name()).collect();
+
+        // 受影响输出按所属页面分组
+        let mut pages: HashSet<usize> = HashSet::new();
+        let mut tags: HashSet<String> = HashSet::new();
+        let mut categories: HashSet<Vec<String>> = HashSet::new();
+        let mut years: HashSet<String> = HashSet::new();
+        let (mut tags_overview, mut categories_overview, mut archives_overview, mut feeds) = (false, false, false, false);
+        for output in &diff.affected {
+            let segments: Vec<&str> = output.split('/').collect();
+            let (file, dir) = segments.split_last().expect("split 至少返回一段");
+            match (dir, output.as_str()) {
+                (_, "tags/index.html" | "tags.html") => tags_overview = true,
+                (_, "categories/index.html") => categories_overview = true,
+                (_, "archives/index.html") => archives_overview = true,
+                (["archives", year, ..], _) => {
+                    if existing_years.contains(year) {
+                        years.insert(year.to_string());
+                    }
+                }
+                (["tags", tag], _) => {
+                    if existing_tags.contains(*tag) {
+                        tags.insert(tag.to_string());
+                    }
+                }
+                ([], _) if feed_files.contains(file) => feeds = true,
+                ([], _) => {
+                    if let Some(page) = file.strip_prefix("index").and_then(|s| s.strip_suffix(".html")) {
+                        pages.insert(if page.is_empty() { total_pages } else { page.parse().unwrap_or(total_pages) });
+                    }
+                }
+                _ => {
+                    let dir: Vec<String> = dir.iter().map(|s| s.to_string()).collect();
+                    if existing_categories.contains(&dir) {
+                        categories.insert(dir);
                     }
                 }
             }
         }
-        let expected_max_n = total_pages;
-        if expected_max_n > existing_max_n {
-            for n in (existing_max_n + 1)..=expected_max_n {
-                affected_pages.insert(n);
-            }
+
+        let all_tags = PostParser::collect_tags(posts);
+        let all_categories = PostParser::generate_hierarchical_categories(posts);
+        if !pages.is_empty() {
+            self.generate_index_pages_for(posts, &all_tags, &all_categories, &pages, output_dir)?;
         }
-        // 如果 index.html 不存在，也需要补充生成
-        if !output_dir.join("index.html").exists() {
-            affected_pages.insert(total_pages);
+        if tags_overview {
+            let tags_html = self.template_engine.render_tags(posts, &all_tags)?;
+            let tags_dir = output_dir.join("tags");
+            self.create_dir_all(&tags_dir)?;
+            self.write_file(tags_dir.join("index.html"), &tags_html)
+                .map_err(|e| Error::Other(format!("无法写入 tags/index.html 标签页: {}", e)))?;
+            self.write_file(output_dir.join("tags.html"), &tags_html)
+                .map_err(|e| Error::Other(format!("无法写入 tags.html 标签页: {}", e)))?;
         }
-        if !affected_pages.is_empty() {
-            self.generate_index_pages_for(
-                &posts,
-                &all_tags,
-                &all_categories,
-                &affected_pages,
-                output_dir,
-            )?;
+        if !tags.is_empty() {
+            self.generate_tag_pages_for(posts, &tags, output_dir)?;
         }
-
-        // 仅生成受影响标签分页
-        if !changed_tags.is_empty() {
-            // 当新标签首次出现时，更新标签总览页
-            let mut need_tags_overview = false;
-            for tag in &changed_tags {
-                let dir = output_dir.join("tags").join(tag);
-                if !dir.exists() {
-                    need_tags_overview = true;
-                    break;
-                }
-            }
-            if need_tags_overview {
-                let tags_html = self.template_engine.render_tags(&posts, &all_tags)?;
-                let tags_dir = output_dir.join("tags");
-                std::fs::create_dir_all(&tags_dir)?;
-                self.write_file(tags_dir.join("index.html"), &tags_html)
-                    .map_err(|e| Error::Other(format!("无法写入 tags/index.html 标签页: {}", e)))?;
-                self.write_file(output_dir.join("tags.html"), &tags_html)
-                    .map_err(|e| Error::Other(format!("无法写入 tags.html 标签页: {}", e)))?;
-            }
-            self.generate_tag_pages_for(&posts, &changed_tags, output_dir)?;
+        // 专栏标签页依赖各专栏的 README，整体刷新
+        self.generate_columns_tag_pages(posts, output_dir)?;
+        if categories_overview {
+            let categories_html = self.template_engine.render_categories(posts, &all_categories, &all_tags)?;
+            let categories_dir = output_dir.join("categories");
+            self.create_dir_all(&categories_dir)?;
+            self.write_file(categories_dir.join("index.html"), &categories_html)
+                .map_err(|e| Error::Other(format!("无法写入分类页: {}", e)))?;
         }
-
-        // 生成专栏标签分类页面
-        self.generate_columns_tag_pages(&posts, output_dir.as_ref())?;
-
-        // 仅生成受影响分类分页（含祖先路径）
-        if !changed_categories.is_empty() {
-            self.generate_category_pages_for(&posts, &changed_categories, output_dir)?;
+        if !categories.is_empty() {
+            self.generate_category_pages_for(posts, &categories, output_dir)?;
         }
-
-        // 仅生成受影响年份归档页
-        if !changed_years.is_empty() {
-            self.generate_year_archive_pages_for(&posts, &changed_years, output_dir)?;
+        if archives_overview {
+            let all_years = PostParser::collect_years(posts);
+            let archives_html = self.template_engine.render_archives(posts, &all_years)?;
+            let archives_dir = output_dir.join("archives");
+            self.create_dir_all(&archives_dir)?;
+            self.write_file(archives_dir.join("index.html"), &archives_html)
+                .map_err(|e| Error::Other(format!("无法写入归档页: {}", e)))?;
         }
-
-        // 静态导航页（about、friends、search、404）在增量模式下不变更时跳过重建
-
-        self.generate_search_index(&posts, output_dir)?;
-
-        let rss_enabled = self
-            .config
-            .data
-            .get("features")
-            .and_then(|v| v.get("rss"))
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
-        if rss_enabled {
-            self.generate_rss(&posts, output_dir)?;
+        if !years.is_empty() {
+            self.generate_year_archive_pages_for(posts, &years, output_dir)?;
+        }
+        if feeds {
+            self.generate_feeds(posts, output_dir)?;
         }
 
+        // 搜索索引与 sitemap 依赖全部文章，整体重建
+        self.generate_search_index(posts, output_dir)?;
         let sitemap_enabled = self
             .config
             .data
@@ -1552,127 +1650,147 @@ impl Generator {
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
         if sitemap_enabled {
-            self.generate_sitemap(&posts, output_dir)?;
+            self.generate_sitemap(posts, output_dir)?;
         }
+        Ok(())
+    }
 
-        // --- 增量重构核心增强：清理不再存在的文章页面 ---
-        self.clean_stale_outputs(output_dir, &posts)?;
-
-        // 更细致的集合日志
-        if !changed_tags.is_empty() {
-            let mut tags_list: Vec<&String> = changed_tags.iter().collect();
-            tags_list.sort();
-            let joined = tags_list.iter().fold(String::new(), |mut acc, s| {
-                if !acc.is_empty() {
-                    acc.push_str(", ");
+    /// 删除不再由任何源文件生成的输出（主题静态资源中的同名文件除外），并清理随之变空的目录
+    fn remove_stale_outputs(&self, output_dir: &Path, theme_static_dir: &Path, stale: &std::collections::BTreeSet<String>) -> Result<()> {
+        for rel in stale {
+            if crate::utils::ThemeStaticAssets::get(rel).is_some() || theme_static_dir.join(rel).is_file() {
+                continue;
+            }
+            let path = output_dir.join(rel);
+            if let Some(mem_fs) = &self.mem_fs {
+                if mem_fs.write().unwrap().remove(&self.mem_key(&path)).is_some() {
+                    println!("已删除过时输出: {}", rel);
                 }
-                acc.push_str(s);
-                acc
-            });
-            println!("受影响标签 {} 个：{}", tags_list.len(), joined);
-        }
-        if !changed_categories.is_empty() {
-            let mut cat_list: Vec<String> =
-                changed_categories.iter().map(|p| p.join("/")).collect();
-            cat_list.sort();
-            println!(
-                "受影响分类路径 {} 个：{}",
-                cat_list.len(),
-                cat_list.join(", ")
-            );
-        }
-        if !changed_years.is_empty() {
-            let mut years_list: Vec<&String> = changed_years.iter().collect();
-            years_list.sort();
-            let joined = years_list.iter().fold(String::new(), |mut acc, s| {
-                if !acc.is_empty() {
-                    acc.push_str(", ");
+                continue;
+            }
+            if !path.is_file() {
+                continue;
+            }
+            std::fs::remove_file(&path)
+                .map_err(|e| Error::Other(format!("无法删除过时输出 {:?}: {}", path, e)))?;
+            println!("已删除过时输出: {}", rel);
+            let mut dir = path.parent();
+            while let Some(d) = dir.filter(|d| *d != output_dir && d.starts_with(output_dir)) {
+                if std::fs::remove_dir(d).is_err() {
+                    break;
                 }
-                acc.push_str(s);
-                acc
-            });
-            println!("受影响年份 {} 个：{}", years_list.len(), joined);
+                dir = d.parent();
+            }
         }
-        println!("增量构建完成！已更新文章与受影响派生页。");
         Ok(())
     }
 
-    /// 清理输出目录中的陈旧 HTML 文件（不再属于当前文章集合的文章页面）
-    fn clean_stale_outputs<P: AsRef<Path>>(&self, output_dir: P, posts: &[Post]) -> Result<()> {
-        let output_dir = output_dir.as_ref();
-
-        // 1. 收集所有当前活跃文章的预期相对路径
-        let mut active_article_paths = std::collections::HashSet::new();
-        for post in posts {
-            if let Some(url) = post.url() {
-                let rel_path = url.trim_start_matches('/').to_string();
-                active_article_paths.insert(rel_path);
-            }
-        }
-
-        // 2. 定义系统级保留页面关键词与完全匹配项
-        let system_pages = [
-            "index.html",
-            "tags/index.html",
-            "categories/index.html",
-            "archives/index.html",
-            "about.html",
-            "friends/index.html",
-            "search.html",
-            "404.html",
-            "robots.txt",
-            "sitemap.xml",
-            "search.json",
-            "rss.xml",
-            "atom.xml",
-        ];
-
-        // 3. 递归扫描输出目录
-        for entry in walkdir::WalkDir::new(output_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if !entry.file_type().is_file() {
-                continue;
+    /// 并行渲染并写出指定文章页面（全量构建、增量构建与密钥轮换共用）
+    fn render_posts(&self, targets: &[&Post], all_posts: &[Post], output_dir: &Path) -> Result<()> {
+        targets.par_iter().try_for_each(|&post| {
+            // 如果是站点首页对应的 README.md (categories为空且slug为index)，则跳过，因为首页由 render_home_page 单独渲染并写入 index.html
+            if post.categories().is_empty() && post.slug() == Some("index") {
+                return Ok(());
             }
+            let Some(post_html) = self.render_post_or_record(post, all_posts) else {
+                return Ok(());
+            };
 
-            let path = entry.path();
-            let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            if let Some(url) = post.url() {
+                let rel_path = url.trim_start_matches('/');
+                let out_path = output_dir.join(rel_path);
 
-            // 仅对 .html 文件执行文章清除比对
-            if !file_name.ends_with(".html") {
-                continue;
+                if let Some(parent) = out_path.parent() {
+                    self.create_dir_all(parent).map_err(|e| {
+                        Error::Other(format!("无法创建文章输出目录 {:?}: {}", parent, e))
+                    })?;
+                }
+                self.write_file(&out_path, &post_html)
+                    .map_err(|e| Error::Other(format!("无法写入文章文件 {:?}: {}", out_path, e)))?;
             }
+            Ok(())
+        })
+    }
 
-            // 获取相对于 output_dir 的路径字符串
-            let rel_path_os = path.strip_prefix(output_dir).unwrap_or(path);
-            let rel_path = rel_path_os.to_string_lossy().to_string();
-            // 调试日志
-            println!("正在检查文件: {}, 相对路径: {}", file_name, rel_path);
+    /// 轮换指定专栏的内容密钥：新密钥只保存在内存中，经增量构建重新生成受影响的页面，全部成功后才写入密钥文件
+    ///
+    /// 清单记录了每篇付费文章的 key_id，密钥变化后依赖图中这些文章的全部输出（文章页、专栏目录、标签与分类页、
+    /// 订阅源等）都会重新生成，清单随之更新。doc_id 没有付费页面、构建失败或有页面渲染失败时密钥文件保持不变，
+    /// 换钥服务仍提供旧密钥；之后再次构建会按旧密钥恢复已写出的页面。
+    pub fn rotate_keys<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        md_dir: P,
+        output_dir: Q,
+        doc_id: &str,
+    ) -> Result<crate::crypto::RotationManifest> {
+        use crate::crypto;
 
-            // 系统级保留页直接跳过
-            if system_pages.contains(&rel_path.as_str()) {
-                continue;
-            }
+        let md_dir = md_dir.as_ref();
+        let output_dir = output_dir.as_ref();
 
-            // 分页页面 (如 index2.html, index3.html) 及主题内置分页模式保留
-            if file_name.starts_with("index") {
-                continue;
-            }
+        // 环境变量优先级最高，若其中固定了该 doc_id，写入密钥文件也不会生效
+        if crypto::env_has_doc_key(doc_id) {
+            return Err(Error::Config(format!(
+                "环境变量 {} 中固定了 `{}` 的密钥，请先移除该项再轮换",
+                crypto::DOC_KEYS_ENV,
+                doc_id
+            )));
+        }
 
-            // 检查目录结构中的特殊模式（如 tags/xxx/index.html 或 categories/xxx/index.html）
-            // 这些由 generate_tag_pages 等生成，虽包含 index 但不在根目录，一并保留
-            if file_name == "index.html" {
-                continue;
-            }
+        let root = crate::utils::project_root(md_dir);
+        let old_key_id = crypto::resolve_content_key(&self.config, &root, doc_id)?.map(|k| crypto::key_id(&k));
+
+        let new_key = crypto::generate_content_key();
+        let new_key_id = crypto::key_id(&new_key);
+        let encoded_key = crypto::encode_key(&new_key);
+
+        *self.key_overrides.lock().unwrap() = HashMap::from([(doc_id.to_string(), encoded_key.clone())]);
+        let rebuilt = self.rebuild_rotated(md_dir, output_dir, doc_id);
+        self.key_overrides.lock().unwrap().clear();
+        let urls = rebuilt?;
+
+        // 全部页面写出成功后才保存新密钥
+        let keys_path = crypto::keys_file_path(&self.config, &root);
+        let mut file_keys = crypto::read_keys_file(&keys_path)?;
+        file_keys.insert(doc_id.to_string(), encoded_key);
+        crypto::write_keys_file(&keys_path, &file_keys)?;
+        println!("已写入新密钥 {} 到 {:?}", new_key_id, keys_path);
+        println!("已重新加密 `{}` 的页面 {} 个", doc_id, urls.len());
+
+        Ok(crypto::RotationManifest {
+            doc_id: doc_id.to_string(),
+            old_key_id,
+            new_key_id,
+            rotated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            urls,
+        })
+    }
 
-            // 如果不在活跃文章列表中，则是源文件已删除的陈旧残留，执行物理回收
-            if !active_article_paths.contains(&rel_path) {
-                println!("检测到过时内容，正在清理残留 HTML: {}", rel_path);
-                let _ = std::fs::remove_file(path);
-            }
+    /// 用内存中的新密钥增量构建，返回该 doc_id 付费页面的 URL
+    fn rebuild_rotated(&self, md_dir: &Path, output_dir: &Path, doc_id: &str) -> Result<Vec<String>> {
+        // 先确认该 doc_id 有付费页面，再写出任何页面
+        self.start_build(output_dir);
+        let posts = self.load_posts(md_dir, output_dir)?;
+        let urls: Vec<String> = posts
+            .iter()
+            .filter(|p| p.data.get("doc_id").and_then(|v| v.as_str()) == Some(doc_id))
+            .filter(|p| p.data.get("encrypted").and_then(|v| v.as_bool()) == Some(true))
+            .filter_map(|p| p.url().map(|u| u.to_string()))
+            .collect();
+        if urls.is_empty() {
+            return Err(Error::Config(format!("`{}` 没有需要加密的付费页面，请检查 --doc 是否正确", doc_id)));
         }
 
-        Ok(())
+        self.build_incremental(md_dir, output_dir)
+            .map_err(|e| Error::Build(format!("重新生成页面失败，未写入新密钥: {}", e)))?;
+        // 部分页面可能已用新密钥写出；密钥文件未变，修复后重新构建即恢复为旧密钥加密的页面
+        let failed = self.issues().iter().filter(|issue| issue.kind == IssueKind::Template).count();
+        if failed > 0 {
+            return Err(Error::Build(format!(
+                "{} 个页面渲染失败，未写入新密钥；修复后请重新构建再轮换",
+                failed
+            )));
+        }
+        Ok(urls)
     }
 }
@@ -0,0 +1,487 @@
+//! 构建时代码高亮模块
+//!
+//! 基于 syntect 在渲染 Markdown 时为围栏代码块着色，页面无需前端 JS 即可正确显示，
+//! 加密正文与 RSS 中的代码块同样有效。围栏信息串支持以下属性：
+//!
+//! ```text
+//! ```rust title="main.rs" {3-5,8} linenos
+//! ```diff-rust                    // 或 ```rust diff：以 + / - 开头的行标记为增删
+//! ```
+//!
+//! 配置见 `[highlight]`：`enable`、`theme`、`mode`（class / inline）与 `line_numbers`。
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::utils::escape_html;
+use comrak::adapters::SyntaxHighlighterAdapter;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::{LazyLock, Mutex};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{
+    css_for_theme_with_class_style, styled_line_to_highlighted_html, ClassStyle, ClassedHTMLGenerator,
+    IncludeBackground,
+};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// 未配置 `[highlight] theme` 时使用的主题
+pub const DEFAULT_THEME: &str = "InspiredGitHub";
+
+/// 高亮 CSS 在输出目录中的位置
+pub const CSS_PATH: &str = "static/css/highlight.css";
+
+/// 语法高亮类名前缀，避免与站点样式冲突
+const CLASS_PREFIX: &str = "hl-";
+/// 行、行号与 diff 等版式类名前缀（与语法作用域类名区分，如 `comment.line` 会生成 `hl-line`）
+const LAYOUT_PREFIX: &str = "code-";
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: CLASS_PREFIX };
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// 高亮输出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightMode {
+    /// 输出 CSS 类名，配色由生成的 highlight.css 提供
+    Class,
+    /// 输出内联样式（适合 RSS 等无法引用样式表的场景）
+    Inline,
+}
+
+/// 代码高亮选项（`[highlight]`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightOptions {
+    /// 是否启用构建时高亮
+    pub enable: bool,
+    /// syntect 内置主题名
+    pub theme: String,
+    /// 输出方式
+    pub mode: HighlightMode,
+    /// 是否默认显示行号（可被代码块的 `linenos` / `nolinenos` 覆盖）
+    pub line_numbers: bool,
+}
+
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        HighlightOptions {
+            enable: false,
+            theme: DEFAULT_THEME.to_string(),
+            mode: HighlightMode::Class,
+            line_numbers: false,
+        }
+    }
+}
+
+impl HighlightOptions {
+    /// 从站点配置读取高亮选项；主题不存在时回退默认主题并给出警告
+    pub fn from_config(config: &Config) -> Self {
+        let mut options = HighlightOptions::default();
+        let section = match config.data.get("highlight") {
+            Some(section) => section,
+            None => return options,
+        };
+        if let Some(enable) = section.get("enable").and_then(|v| v.as_bool()) {
+            options.enable = enable;
+        }
+        if let Some(theme) = section.get("theme").and_then(|v| v.as_str()) {
+            if THEME_SET.themes.contains_key(theme) {
+                options.theme = theme.to_string();
+            } else {
+                eprintln!(
+                    "警告: 未知的代码高亮主题 `{}`，使用默认主题 {}（可选: {}）",
+                    theme,
+                    DEFAULT_THEME,
+                    theme_names().join(", ")
+                );
+            }
+        }
+        if let Some(mode) = section.get("mode").and_then(|v| v.as_str()) {
+            options.mode = match mode {
+                "inline" => HighlightMode::Inline,
+                _ => HighlightMode::Class,
+            };
+        }
+        if let Some(line_numbers) = section.get("line_numbers").and_then(|v| v.as_bool()) {
+            options.line_numbers = line_numbers;
+        }
+        options
+    }
+
+    fn theme(&self) -> &'static Theme {
+        THEME_SET
+            .themes
+            .get(&self.theme)
+            .unwrap_or_else(|| &THEME_SET.themes[DEFAULT_THEME])
+    }
+}
+
+/// 可用的内置主题名
+pub fn theme_names() -> Vec<&'static str> {
+    THEME_SET.themes.keys().map(String::as_str).collect()
+}
+
+/// 代码块围栏属性
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FenceAttrs {
+    /// 语言（`diff-` 前缀已去除）
+    pub lang: String,
+    /// 标题/文件名（`title="main.rs"`）
+    pub title: Option<String>,
+    /// 是否显示行号（`linenos` / `nolinenos`，未指定时跟随全局配置）
+    pub line_numbers: Option<bool>,
+    /// 需要强调的行（`{3-5,8}`，从 1 开始）
+    pub highlight_lines: Vec<RangeInclusive<usize>>,
+    /// diff 模式（`diff` 属性或 `diff-<lang>` 语言）
+    pub diff: bool,
+}
+
+impl FenceAttrs {
+    /// 解析围栏信息串：`lang` 为首个单词，`meta` 为其余部分
+    pub fn parse(lang: &str, meta: &str) -> FenceAttrs {
+        let mut attrs = FenceAttrs::default();
+        match lang.strip_prefix("diff-") {
+            Some(rest) if !rest.is_empty() => {
+                attrs.lang = rest.to_string();
+                attrs.diff = true;
+            }
+            _ => attrs.lang = lang.to_string(),
+        }
+
+        let mut rest = meta.trim();
+        while !rest.is_empty() {
+            if let Some(inner) = rest.strip_prefix('{') {
+                let end = inner.find('}').unwrap_or(inner.len());
+                attrs.highlight_lines.extend(parse_line_ranges(&inner[..end]));
+                rest = inner.get(end + 1..).unwrap_or("");
+            } else {
+                let end = rest.find(|c: char| c.is_whitespace() || c == '{').unwrap_or(rest.len());
+                let (word, tail) = rest.split_at(end);
+                rest = tail;
+                if let Some(value) = word.strip_prefix("title=") {
+                    let (title, tail) = read_value(value, rest);
+                    attrs.title = Some(title).filter(|t| !t.is_empty());
+                    rest = tail;
+                } else {
+                    match word {
+                        "linenos" | "showLineNumbers" | "line-numbers" => attrs.line_numbers = Some(true),
+                        "nolinenos" => attrs.line_numbers = Some(false),
+                        "diff" => attrs.diff = true,
+                        _ => {}
+                    }
+                }
+            }
+            rest = rest.trim_start();
+        }
+        attrs
+    }
+
+    /// 第 `line` 行（从 1 开始）是否需要强调
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight_lines.iter().any(|r| r.contains(&line))
+    }
+}
+
+/// 解析 `3-5,8` 形式的行号范围，非法片段忽略
+fn parse_line_ranges(spec: &str) -> Vec<RangeInclusive<usize>> {
+    spec.split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            match part.split_once('-') {
+                Some((a, b)) => {
+                    let (a, b) = (a.trim().parse().ok()?, b.trim().parse().ok()?);
+                    (a <= b).then_some(a..=b)
+                }
+                None => part.parse().ok().map(|n| n..=n),
+            }
+        })
+        .collect()
+}
+
+/// 读取属性值：支持带引号（可含空格）与不带引号两种写法，返回 (值, 剩余部分)
+fn read_value<'a>(value: &'a str, rest: &'a str) -> (String, &'a str) {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote) {
+            if let Some(end) = inner.find(quote) {
+                return (inner[..end].to_string(), rest);
+            }
+            // 引号内含空格：值延续到剩余部分中的闭合引号
+            let mut title = inner.to_string();
+            return match rest.find(quote) {
+                Some(end) => {
+                    title.push_str(&rest[..end]);
+                    (title, &rest[end + 1..])
+                }
+                None => {
+                    title.push_str(rest);
+                    (title, "")
+                }
+            };
+        }
+    }
+    (value.to_string(), rest)
+}
+
+/// 高亮一段代码，返回逐行的 HTML（已转义，不含换行符）
+pub fn highlight_lines(code: &str, lang: &str, options: &HighlightOptions) -> Result<Vec<String>> {
+    let syntax = find_syntax(lang);
+    let lines = match options.mode {
+        HighlightMode::Class => {
+            let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, CLASS_STYLE);
+            for line in LinesWithEndings::from(code) {
+                generator
+                    .parse_html_for_line_which_includes_newline(line)
+                    .map_err(|e| Error::Other(format!("代码高亮失败: {}", e)))?;
+            }
+            split_balanced_lines(&generator.finalize())
+        }
+        HighlightMode::Inline => {
+            let mut highlighter = HighlightLines::new(syntax, options.theme());
+            let mut lines = Vec::new();
+            for line in LinesWithEndings::from(code) {
+                let regions = highlighter
+                    .highlight_line(line, &SYNTAX_SET)
+                    .map_err(|e| Error::Other(format!("代码高亮失败: {}", e)))?;
+                let html = styled_line_to_highlighted_html(&regions, IncludeBackground::No)
+                    .map_err(|e| Error::Other(format!("代码高亮失败: {}", e)))?;
+                lines.push(html.replace('\n', ""));
+            }
+            lines
+        }
+    };
+    Ok(lines)
+}
+
+fn find_syntax(lang: &str) -> &'static SyntaxReference {
+    let lang = lang.trim();
+    if lang.is_empty() {
+        return SYNTAX_SET.find_syntax_plain_text();
+    }
+    SYNTAX_SET
+        .find_syntax_by_token(lang)
+        .or_else(|| SYNTAX_SET.find_syntax_by_token(&lang.to_lowercase()))
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text())
+}
+
+/// 将带 `<span>` 嵌套的高亮 HTML 按换行拆分为逐行片段：
+/// 行尾关闭仍打开的 span，下一行开头重新打开，保证每行 HTML 自身闭合
+fn split_balanced_lines(html: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut current = String::new();
+    // 当前行是否已有文本（代码以换行结尾时，最后只剩重新打开的空 span，不再追加空行）
+    let mut has_text = false;
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix("</span>") {
+            open.pop();
+            current.push_str("</span>");
+            rest = tail;
+        } else if rest.starts_with("<span") {
+            let end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+            open.push(&rest[..end]);
+            current.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if let Some(tail) = rest.strip_prefix('\n') {
+            current.push_str(&"</span>".repeat(open.len()));
+            lines.push(std::mem::take(&mut current));
+            current = open.concat();
+            has_text = false;
+            rest = tail;
+        } else {
+            let first = rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            let end = rest[first..].find(['<', '\n']).map(|i| i + first).unwrap_or(rest.len());
+            current.push_str(&rest[..end]);
+            has_text = true;
+            rest = &rest[end..];
+        }
+    }
+    if has_text {
+        current.push_str(&"</span>".repeat(open.len()));
+        lines.push(current);
+    }
+    lines
+}
+
+fn write_pre_open(output: &mut dyn fmt::Write, attrs: &FenceAttrs, options: &HighlightOptions) -> fmt::Result {
+    let mut classes = vec![format!("{}code", CLASS_PREFIX)];
+    if attrs.line_numbers.unwrap_or(options.line_numbers) {
+        classes.push(format!("{}linenos", LAYOUT_PREFIX));
+    }
+    if attrs.diff {
+        classes.push(format!("{}diff", LAYOUT_PREFIX));
+    }
+    write!(output, "<pre class=\"{}\"", classes.join(" "))?;
+    if !attrs.lang.is_empty() {
+        write!(output, " data-lang=\"{}\"", escape_html(&attrs.lang))?;
+    }
+    if let Some(title) = &attrs.title {
+        write!(output, " data-title=\"{}\"", escape_html(title))?;
+    }
+    if options.mode == HighlightMode::Inline {
+        let settings = &options.theme().settings;
+        let mut style = String::new();
+        if let Some(c) = settings.background {
+            style.push_str(&format!("background-color:#{:02x}{:02x}{:02x};", c.r, c.g, c.b));
+        }
+        if let Some(c) = settings.foreground {
+            style.push_str(&format!("color:#{:02x}{:02x}{:02x};", c.r, c.g, c.b));
+        }
+        if !style.is_empty() {
+            write!(output, " style=\"{}\"", style)?;
+        }
+    }
+    write!(output, ">")
+}
+
+fn code_open_tag(lang: &str) -> String {
+    if lang.is_empty() {
+        "<code>".to_string()
+    } else {
+        format!("<code class=\"language-{}\">", escape_html(lang))
+    }
+}
+
+/// 逐行输出：`<span class="hl-line" data-line="N">…</span>`，按需附加强调与增删标记
+fn render_lines(code: &str, attrs: &FenceAttrs, options: &HighlightOptions) -> Result<String> {
+    let code = code.trim_end_matches('\n');
+    // diff 模式：去掉行首的 +/- 标记再高亮，标记改由 CSS 显示（复制代码时不会带上）
+    let mut markers = Vec::new();
+    let source = if attrs.diff {
+        let mut stripped = String::with_capacity(code.len());
+        for line in code.split('\n') {
+            let marker = match line.chars().next() {
+                Some(c @ ('+' | '-')) => Some(c),
+                _ => None,
+            };
+            markers.push(marker);
+            stripped.push_str(if marker.is_some() { &line[1..] } else { line.strip_prefix(' ').unwrap_or(line) });
+            stripped.push('\n');
+        }
+        stripped
+    } else {
+        format!("{}\n", code)
+    };
+
+    let lines = highlight_lines(&source, &attrs.lang, options)?;
+    let mut html = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let number = i + 1;
+        let mut classes = format!("{}line", LAYOUT_PREFIX);
+        let mut style = "";
+        if attrs.is_highlighted(number) {
+            classes.push_str(&format!(" {}mark", LAYOUT_PREFIX));
+            style = "background-color:rgba(255,213,79,0.25);";
+        }
+        match markers.get(i).copied().flatten() {
+            Some('+') => {
+                classes.push_str(&format!(" {}diff-add", LAYOUT_PREFIX));
+                style = "background-color:rgba(46,160,67,0.15);";
+            }
+            Some(_) => {
+                classes.push_str(&format!(" {}diff-remove", LAYOUT_PREFIX));
+                style = "background-color:rgba(248,81,73,0.15);";
+            }
+            None => {}
+        }
+        html.push_str(&format!("<span class=\"{}\" data-line=\"{}\"", classes, number));
+        if options.mode == HighlightMode::Inline && !style.is_empty() {
+            html.push_str(&format!(" style=\"{}\"", style));
+        }
+        html.push('>');
+        html.push_str(line);
+        html.push_str("</span>\n");
+    }
+    Ok(html)
+}
+
+/// 生成高亮样式表：主题配色（class 模式）+ 行号、强调行、diff 与标题的版式
+pub fn theme_css(options: &HighlightOptions) -> Result<String> {
+    let mut css = String::new();
+    if options.mode == HighlightMode::Class {
+        css.push_str(
+            &css_for_theme_with_class_style(options.theme(), CLASS_STYLE)
+                .map_err(|e| Error::Other(format!("生成代码高亮样式失败: {}", e)))?,
+        );
+    }
+    css.push_str(LAYOUT_CSS);
+    Ok(css)
+}
+
+const LAYOUT_CSS: &str = r#"
+/* rustpress: 代码块版式 */
+pre.hl-code { padding-left: 0; padding-right: 0; }
+pre.hl-code > code { display: block; min-width: 100%; }
+pre.hl-code .code-line { display: inline-block; min-width: 100%; padding: 0 1rem; box-sizing: border-box; }
+pre.hl-code .code-mark { background-color: rgba(255, 213, 79, 0.25); }
+pre.code-linenos .code-line::before {
+  content: attr(data-line);
+  display: inline-block;
+  width: 2.5em;
+  margin-right: 1em;
+  text-align: right;
+  opacity: 0.45;
+  user-select: none;
+}
+pre.code-diff .code-line { padding-left: 1.75rem; position: relative; }
+pre.code-diff .code-diff-add { background-color: rgba(46, 160, 67, 0.15); }
+pre.code-diff .code-diff-remove { background-color: rgba(248, 81, 73, 0.15); }
+pre.code-diff .code-diff-add::after, pre.code-diff .code-diff-remove::after {
+  position: absolute;
+  left: 0.6rem;
+  user-select: none;
+}
+pre.code-diff .code-diff-add::after { content: "+"; color: #2ea043; }
+pre.code-diff .code-diff-remove::after { content: "-"; color: #f85149; }
+pre.hl-code[data-title]::before {
+  content: attr(data-title);
+  display: block;
+  margin: -0.5rem 0 0.75rem;
+  padding: 0 1rem 0.5rem;
+  font-size: 0.8em;
+  opacity: 0.7;
+  border-bottom: 1px solid rgba(127, 127, 127, 0.25);
+}
+"#;
+
+/// comrak 高亮插件：`write_pre_tag` 取得围栏属性后暂存，供随后的 `write_highlighted` 使用。
+/// 需开启 `github_pre_lang` 与 `full_info_string`，语言与属性才会传给 `write_pre_tag`。
+pub(crate) struct CodeHighlighter<'a> {
+    options: &'a HighlightOptions,
+    current: Mutex<FenceAttrs>,
+}
+
+impl<'a> CodeHighlighter<'a> {
+    pub(crate) fn new(options: &'a HighlightOptions) -> Self {
+        CodeHighlighter { options, current: Mutex::new(FenceAttrs::default()) }
+    }
+
+    fn current(&self) -> FenceAttrs {
+        self.current.lock().map(|attrs| attrs.clone()).unwrap_or_default()
+    }
+}
+
+impl SyntaxHighlighterAdapter for CodeHighlighter<'_> {
+    fn write_highlighted(&self, output: &mut dyn fmt::Write, _lang: Option<&str>, code: &str) -> fmt::Result {
+        let html = render_lines(code, &self.current(), self.options).map_err(|_| fmt::Error)?;
+        output.write_str(&html)
+    }
+
+    fn write_pre_tag(&self, output: &mut dyn fmt::Write, attributes: HashMap<&'static str, Cow<'_, str>>) -> fmt::Result {
+        let lang = attributes.get("lang").map(|s| s.as_ref()).unwrap_or("");
+        let meta = attributes.get("data-meta").map(|s| s.as_ref()).unwrap_or("");
+        let attrs = FenceAttrs::parse(lang, meta);
+        write_pre_open(output, &attrs, self.options)?;
+        if let Ok(mut current) = self.current.lock() {
+            *current = attrs;
+        }
+        Ok(())
+    }
+
+    fn write_code_tag(&self, output: &mut dyn fmt::Write, _attributes: HashMap<&'static str, Cow<'_, str>>) -> fmt::Result {
+        output.write_str(&code_open_tag(&self.current().lang))
+    }
+}
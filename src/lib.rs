@@ -6,20 +6,40 @@
 //! - 静态文件生成
 //! - 开发服务器
 
+pub mod cache;
+pub mod callout;
+pub mod check;
 pub mod cli;
 pub mod config;
+pub mod crypto;
 pub mod error;
+pub mod excerpt;
+pub mod feed;
+pub mod front_matter;
 pub mod generator;
+pub mod highlight;
+pub mod linkcheck;
+pub mod links;
+pub mod livereload;
+pub mod manifest;
+pub mod markdown;
+pub mod math;
 pub mod plugins;
 pub mod post;
+pub mod reading;
+pub mod report;
+pub mod search;
 pub mod server;
+pub mod sitemap;
 pub mod template;
+pub mod toc;
 pub mod utils;
 
 // 重新导出主要的公共类型和函数
 pub use cli::{Cli, Commands};
 pub use config::Config;
 pub use error::{Error, Result};
+pub use front_matter::{FrontMatter, Layout};
 pub use generator::Generator;
 pub use post::{Post, PostParser};
 pub use server::DevServer;
@@ -0,0 +1,564 @@
+//! 链接检查模块
+//!
+//! 扫描构建输出目录中的全部 HTML 页面，检查站内 `href` / `src`（分页 `indexN.html`、标签与分类页、
+//! `/static/…` 等）是否指向存在的文件，`#锚点` 是否存在于目标页面，并按源 Markdown 文件分组输出报告。
+//! 可选检查站外链接：并发数与超时可配置，可访问的结果缓存到磁盘，有效期内不重复请求。
+//! 供 `rustpress check-links` 使用。
+
+use crate::check::Severity;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::links::normalize;
+use crate::post::{ParseOptions, PostParser};
+use crate::report::percent_decode;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// 内联脚本内容（其中拼接的地址不是真实链接）
+static RE_SCRIPT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)(<script\b[^>]*>).*?</script>").unwrap());
+static RE_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+static RE_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<([a-zA-Z][a-zA-Z0-9-]*)\b([^>]*)>").unwrap());
+static RE_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(?:^|\s)(href|src|rel|id|name)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap()
+});
+static RE_NUMERIC_ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&#(?:[xX]([0-9a-fA-F]+)|([0-9]+));").unwrap());
+
+/// 默认站外链接并发请求数
+const DEFAULT_CONCURRENCY: usize = 8;
+/// 默认站外链接超时（秒）
+const DEFAULT_TIMEOUT: u64 = 10;
+/// 默认缓存有效期（小时）
+const DEFAULT_CACHE_HOURS: u64 = 24;
+/// 默认缓存文件
+const DEFAULT_CACHE_FILE: &str = ".rustpress-links.json";
+
+/// 链接检查选项（`[link_check]`）
+#[derive(Debug, Clone)]
+pub struct LinkCheckOptions {
+    /// 是否检查站外链接
+    pub external: bool,
+    /// 站外链接并发请求数
+    pub concurrency: usize,
+    /// 单个站外链接的超时
+    pub timeout: Duration,
+    /// 站外链接结果缓存文件
+    pub cache_file: PathBuf,
+    /// 缓存有效期
+    pub cache_ttl: Duration,
+    /// 跳过以这些前缀开头的链接（站内路径或完整 URL）
+    pub ignore: Vec<String>,
+}
+
+impl Default for LinkCheckOptions {
+    fn default() -> Self {
+        LinkCheckOptions {
+            external: false,
+            concurrency: DEFAULT_CONCURRENCY,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            cache_file: PathBuf::from(DEFAULT_CACHE_FILE),
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_HOURS * 3600),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl LinkCheckOptions {
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = LinkCheckOptions::default();
+        let Some(section) = config.data.get("link_check") else {
+            return defaults;
+        };
+        let positive = |key: &str| section.get(key).and_then(|v| v.as_integer()).filter(|n| *n > 0).map(|n| n as u64);
+        LinkCheckOptions {
+            external: section.get("external").and_then(|v| v.as_bool()).unwrap_or(defaults.external),
+            concurrency: positive("concurrency").map_or(defaults.concurrency, |n| n as usize),
+            timeout: positive("timeout").map_or(defaults.timeout, Duration::from_secs),
+            cache_file: section
+                .get("cache_file")
+                .and_then(|v| v.as_str())
+                .map_or(defaults.cache_file, PathBuf::from),
+            cache_ttl: positive("cache_hours").map_or(defaults.cache_ttl, |h| Duration::from_secs(h * 3600)),
+            ignore: section
+                .get("ignore")
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 单条失效链接
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LinkIssue {
+    /// 所在页面（相对输出目录）
+    pub page: String,
+    /// 页面中的原始链接
+    pub link: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for LinkIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "错误",
+            Severity::Warning => "警告",
+        };
+        write!(f, "{}: {}: {} -> {}", self.page, level, self.link, self.message)
+    }
+}
+
+/// 链接检查报告
+#[derive(Debug, Default)]
+pub struct LinkReport {
+    /// 扫描的页面数
+    pub pages: usize,
+    /// 检查的站内链接数
+    pub internal: usize,
+    /// 检查的站外链接数（去重后）
+    pub external: usize,
+    /// 源 Markdown 文件（无源文件的生成页面以页面路径代替）-> 问题
+    pub groups: BTreeMap<String, Vec<LinkIssue>>,
+}
+
+impl LinkReport {
+    pub fn issues(&self) -> impl Iterator<Item = &LinkIssue> {
+        self.groups.values().flatten()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.issues().filter(|i| i.severity == Severity::Error).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.issues().filter(|i| i.severity == Severity::Warning).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    fn push(&mut self, group: &str, issue: LinkIssue) {
+        self.groups.entry(group.to_string()).or_default().push(issue);
+    }
+}
+
+impl fmt::Display for LinkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (group, issues) in &self.groups {
+            writeln!(f, "{}", group)?;
+            for issue in issues {
+                writeln!(f, "  {}", issue)?;
+            }
+        }
+        write!(
+            f,
+            "链接检查完成：共 {} 个页面，{} 个站内链接，{} 个站外链接；{} 个错误，{} 个警告",
+            self.pages,
+            self.internal,
+            self.external,
+            self.error_count(),
+            self.warning_count()
+        )
+    }
+}
+
+/// 页面中提取出的链接与锚点
+#[derive(Default)]
+struct Page {
+    links: Vec<String>,
+    ids: HashSet<String>,
+}
+
+/// 链接的分类
+enum Target {
+    /// 不需要检查（mailto:、javascript:、模板占位符等）
+    Skip,
+    /// 站外链接（已去掉锚点）
+    External(String),
+    /// 站内路径（可能为空，表示当前页面）与锚点
+    Internal { path: String, fragment: Option<String> },
+}
+
+/// 检查 output_dir 中的全部页面；md_dir 用于把页面对应回源 Markdown 文件
+pub fn check_links(output_dir: &Path, md_dir: &Path, config: &Config, options: &LinkCheckOptions) -> Result<LinkReport> {
+    if !output_dir.is_dir() {
+        return Err(Error::Other(format!(
+            "输出目录 {} 不存在，请先运行 rustpress build",
+            output_dir.display()
+        )));
+    }
+
+    let mut pages: BTreeMap<String, Page> = BTreeMap::new();
+    for entry in WalkDir::new(output_dir).into_iter().filter_map(|e| e.ok()) {
+        let is_html = entry.path().extension().is_some_and(|ext| ext == "html" || ext == "htm");
+        if !entry.file_type().is_file() || !is_html {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(output_dir) else {
+            continue;
+        };
+        let html = std::fs::read_to_string(entry.path())?;
+        pages.insert(rel_key(rel), scan_page(&html));
+    }
+
+    let sources = page_sources(md_dir, config);
+    let site_prefixes = site_prefixes(config);
+    let mut report = LinkReport {
+        pages: pages.len(),
+        ..Default::default()
+    };
+    // 站外 URL -> 引用它的（分组，页面，原始链接）
+    let mut external: BTreeMap<String, Vec<(String, String, String)>> = BTreeMap::new();
+
+    for (page, content) in &pages {
+        let group = sources
+            .get(page)
+            .map(|source| source.display().to_string())
+            .unwrap_or_else(|| format!("{}（生成页面）", page));
+        let mut seen = HashSet::new();
+        for link in &content.links {
+            if !seen.insert(link.as_str()) || options.ignore.iter().any(|prefix| link.starts_with(prefix.as_str())) {
+                continue;
+            }
+            match classify(link, &site_prefixes) {
+                Target::Skip => {}
+                Target::External(url) => {
+                    if options.external {
+                        external.entry(url).or_default().push((group.clone(), page.clone(), link.clone()));
+                    }
+                }
+                Target::Internal { path, fragment } => {
+                    report.internal += 1;
+                    if let Err(message) = check_internal(output_dir, &pages, page, &path, fragment.as_deref()) {
+                        let issue = LinkIssue {
+                            page: page.clone(),
+                            link: link.clone(),
+                            severity: Severity::Error,
+                            message,
+                        };
+                        report.push(&group, issue);
+                    }
+                }
+            }
+        }
+    }
+
+    if options.external && !external.is_empty() {
+        report.external = external.len();
+        let urls: Vec<String> = external.keys().cloned().collect();
+        let results = check_external(urls, options)?;
+        for (url, referrers) in &external {
+            let Some(Err(message)) = results.get(url) else {
+                continue;
+            };
+            for (group, page, link) in referrers {
+                let issue = LinkIssue {
+                    page: page.clone(),
+                    link: link.clone(),
+                    severity: Severity::Warning,
+                    message: message.clone(),
+                };
+                report.push(group, issue);
+            }
+        }
+    }
+
+    for issues in report.groups.values_mut() {
+        issues.sort();
+    }
+    Ok(report)
+}
+
+/// 提取页面中的链接（跳过内联脚本与注释）与全部 id / name 锚点
+fn scan_page(html: &str) -> Page {
+    let html = RE_COMMENT.replace_all(html, "");
+    let html = RE_SCRIPT.replace_all(&html, "$1");
+    let mut page = Page::default();
+    for tag in RE_TAG.captures_iter(&html) {
+        let mut link = None;
+        let mut hint_only = false;
+        for attr in RE_ATTR.captures_iter(&tag[2]) {
+            let value = unescape_attr(attr.get(2).or_else(|| attr.get(3)).map_or("", |m| m.as_str()));
+            match attr[1].to_ascii_lowercase().as_str() {
+                "href" | "src" => link = Some(value),
+                // preconnect / dns-prefetch 只给出源站，不是可访问的页面
+                "rel" => hint_only = value.split_whitespace().any(|r| r == "preconnect" || r == "dns-prefetch"),
+                "name" if tag[1].eq_ignore_ascii_case("a") => {
+                    page.ids.insert(value);
+                }
+                "id" => {
+                    page.ids.insert(value);
+                }
+                _ => {}
+            }
+        }
+        if let Some(link) = link.filter(|_| !hint_only) {
+            page.links.push(link);
+        }
+    }
+    page
+}
+
+fn classify(link: &str, site_prefixes: &[String]) -> Target {
+    let link = link.trim();
+    if link.is_empty() || link.contains("{{") || link.contains("${") {
+        return Target::Skip;
+    }
+    let lower = link.to_ascii_lowercase();
+    let internal = |rest: &str| {
+        let (path, fragment) = match rest.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment.to_string())),
+            None => (rest, None),
+        };
+        let path = path.split('?').next().unwrap_or_default().to_string();
+        Target::Internal { path, fragment }
+    };
+
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        // 指向本站域名的绝对地址按站内链接检查
+        for prefix in site_prefixes {
+            if let Some(rest) = lower.strip_prefix(prefix.as_str()) {
+                if rest.is_empty() || rest.starts_with(['/', '?', '#']) {
+                    let rest = &link[prefix.len()..];
+                    return internal(if rest.is_empty() { "/" } else { rest });
+                }
+            }
+        }
+        return Target::External(link.split('#').next().unwrap_or(link).to_string());
+    }
+    if let Some(rest) = link.strip_prefix("//") {
+        return Target::External(format!("https://{}", rest.split('#').next().unwrap_or(rest)));
+    }
+    let has_scheme = lower
+        .split_once(':')
+        .is_some_and(|(scheme, _)| scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c)));
+    if has_scheme {
+        return Target::Skip;
+    }
+    internal(link)
+}
+
+/// 检查站内链接，返回问题说明
+fn check_internal(
+    output_dir: &Path,
+    pages: &BTreeMap<String, Page>,
+    page: &str,
+    path: &str,
+    fragment: Option<&str>,
+) -> std::result::Result<(), String> {
+    let target = if path.is_empty() {
+        page.to_string()
+    } else {
+        let decoded = percent_decode(path);
+        let joined = match decoded.strip_prefix('/') {
+            Some(rooted) => PathBuf::from(rooted),
+            None => Path::new(page).parent().unwrap_or(Path::new("")).join(&decoded),
+        };
+        let normalized = normalize(&joined);
+        if normalized.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Err("指向输出目录之外".to_string());
+        }
+        // 依次尝试：文件本身、目录下的 index.html、补全 .html 扩展名（与静态服务器一致）
+        let mut candidates = Vec::new();
+        if !path.ends_with('/') {
+            candidates.push(normalized.clone());
+        }
+        candidates.push(normalized.join("index.html"));
+        if !path.ends_with('/') && normalized.extension().is_none() {
+            candidates.push(normalized.with_extension("html"));
+        }
+        match candidates.into_iter().find(|p| output_dir.join(p).is_file()) {
+            Some(found) => rel_key(&found),
+            None => return Err("目标文件不存在".to_string()),
+        }
+    };
+
+    let fragment = fragment.filter(|f| !f.is_empty() && *f != "top" && !f.starts_with(":~:"));
+    if let (Some(fragment), Some(target_page)) = (fragment, pages.get(&target)) {
+        if !target_page.ids.contains(fragment) && !target_page.ids.contains(&percent_decode(fragment)) {
+            return Err(format!("锚点 #{} 在 {} 中不存在", percent_decode(fragment), target));
+        }
+    }
+    Ok(())
+}
+
+/// 页面路径（相对输出目录，`/` 分隔）-> 源 Markdown 文件
+fn page_sources(md_dir: &Path, config: &Config) -> HashMap<String, PathBuf> {
+    let mut sources = HashMap::new();
+    if md_dir.join("README.md").is_file() {
+        sources.insert("index.html".to_string(), md_dir.join("README.md"));
+    }
+    let options = ParseOptions::from_config(config);
+    let Ok((posts, _)) = PostParser::list_posts_with_errors(md_dir, &options) else {
+        return sources;
+    };
+    for post in &posts {
+        let (Some(url), Some(source)) = (post.url(), post.source_path()) else {
+            continue;
+        };
+        let url = percent_decode(url.trim_start_matches('/'));
+        let page = if url.is_empty() || url.ends_with('/') {
+            format!("{}index.html", url)
+        } else {
+            url
+        };
+        sources.insert(page, PathBuf::from(source));
+    }
+    sources
+}
+
+/// 视为本站的绝对地址前缀（`site.domain` 与 `site.base_url`）
+fn site_prefixes(config: &Config) -> Vec<String> {
+    ["domain", "base_url"]
+        .iter()
+        .filter_map(|key| config.data.get("site")?.get(*key)?.as_str())
+        .map(|url| url.trim().trim_end_matches('/').to_ascii_lowercase())
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .collect()
+}
+
+fn rel_key(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 还原属性值中的实体（tera 会把 `/` 转义为 `&#x2F;`）
+fn unescape_attr(value: &str) -> String {
+    if !value.contains('&') {
+        return value.to_string();
+    }
+    let value = RE_NUMERIC_ENTITY.replace_all(value, |c: &regex::Captures| {
+        let code = match (c.get(1), c.get(2)) {
+            (Some(hex), _) => u32::from_str_radix(hex.as_str(), 16).ok(),
+            (_, Some(dec)) => dec.as_str().parse().ok(),
+            _ => None,
+        };
+        code.and_then(char::from_u32).map_or_else(|| c[0].to_string(), String::from)
+    });
+    value
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// 站外链接缓存条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// 检查时间（Unix 秒）
+    checked_at: u64,
+    ok: bool,
+    message: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// 检查站外链接：有效期内可访问的结果直接取缓存，其余并发请求后写回缓存
+fn check_external(
+    urls: Vec<String>,
+    options: &LinkCheckOptions,
+) -> Result<HashMap<String, std::result::Result<(), String>>> {
+    let mut cache: BTreeMap<String, CacheEntry> = std::fs::read_to_string(&options.cache_file)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let now = now_secs();
+    let fresh = |entry: &CacheEntry| entry.ok && now.saturating_sub(entry.checked_at) < options.cache_ttl.as_secs();
+
+    let mut results = HashMap::new();
+    let mut pending = Vec::new();
+    for url in urls {
+        match cache.get(&url) {
+            Some(entry) if fresh(entry) => {
+                results.insert(url, Ok(()));
+            }
+            _ => pending.push(url),
+        }
+    }
+
+    if !pending.is_empty() {
+        println!("正在检查 {} 个站外链接（并发 {}）...", pending.len(), options.concurrency);
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Other(format!("无法创建异步运行时: {}", e)))?;
+        let checked = rt.block_on(fetch_all(pending, options))?;
+        for (url, result) in checked {
+            let entry = CacheEntry {
+                checked_at: now,
+                ok: result.is_ok(),
+                message: result.as_ref().err().cloned().unwrap_or_default(),
+            };
+            cache.insert(url.clone(), entry);
+            results.insert(url, result);
+        }
+    }
+
+    // 过期条目不再保留
+    cache.retain(|_, entry| now.saturating_sub(entry.checked_at) < options.cache_ttl.as_secs());
+    if let Some(parent) = options.cache_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&options.cache_file, serde_json::to_string_pretty(&cache)?)?;
+    Ok(results)
+}
+
+async fn fetch_all(
+    urls: Vec<String>,
+    options: &LinkCheckOptions,
+) -> Result<Vec<(String, std::result::Result<(), String>)>> {
+    let client = reqwest::Client::builder()
+        .timeout(options.timeout)
+        .user_agent(concat!("rustpress-link-checker/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| Error::Other(format!("无法创建 HTTP 客户端: {}", e)))?;
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
+    let mut tasks = tokio::task::JoinSet::new();
+    for url in urls {
+        let client = client.clone();
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = fetch(&client, &url).await;
+            (url, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.map_err(|e| Error::Other(format!("链接检查任务失败: {}", e)))?);
+    }
+    Ok(results)
+}
+
+/// 先发 HEAD，不支持 HEAD 的站点再退回 GET；2xx / 3xx 视为可访问
+async fn fetch(client: &reqwest::Client, url: &str) -> std::result::Result<(), String> {
+    let describe = |e: reqwest::Error| {
+        if e.is_timeout() {
+            "请求超时".to_string()
+        } else {
+            format!("请求失败: {}", e)
+        }
+    };
+    let response = match client.head(url).send().await {
+        Ok(response) if ![403, 405, 501].contains(&response.status().as_u16()) => response,
+        _ => client.get(url).send().await.map_err(describe)?,
+    };
+    let status = response.status();
+    if status.is_success() || status.is_redirection() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", status.as_u16()))
+    }
+}
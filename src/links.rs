@@ -0,0 +1,132 @@
+//! 站内链接模块
+//!
+//! 作者常在 Markdown 中用相对路径互相引用（`[下一章](./2.md)`、`../docs/intro/README.md`），
+//! 编辑器与 GitHub 中可以跳转，生成站点后却会 404。这里在渲染后的 HTML 上把指向 `.md` 源文件的链接
+//! 改写为生成后的 URL（与 `PostParser::get_url_from_path` 的规则一致，含 works 与 README → index），
+//! 保留 `#锚点` 与查询串；找不到目标的链接保持原样并记入构建报告。
+
+use crate::post::Post;
+use crate::report::{percent_decode, BuildIssue, IssueKind};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+/// 正文中的链接地址
+static RE_HREF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(<a\b[^>]*?\bhref=")([^"]*)(")"#).unwrap());
+
+/// 改写全部文章中指向 `.md` 源文件的链接，返回无法解析的链接
+pub fn rewrite_md_links(posts: &mut [Post], md_dir: &Path) -> Vec<BuildIssue> {
+    let urls: HashMap<PathBuf, String> = posts
+        .iter()
+        .filter_map(|post| Some((normalize(Path::new(post.source_path()?)), post.url()?.to_string())))
+        .collect();
+
+    let mut issues = Vec::new();
+    for post in posts.iter_mut() {
+        let Some(source) = post.source_path().map(PathBuf::from) else {
+            continue;
+        };
+        for key in ["content", "summary_html"] {
+            let Some(html) = post.data.get(key).and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if !html.to_ascii_lowercase().contains(".md") {
+                continue;
+            }
+            let mut broken = Vec::new();
+            let rewritten = RE_HREF.replace_all(html, |c: &Captures| match resolve(&c[2], &source, md_dir, &urls) {
+                Some(Ok(url)) => format!("{}{}{}", &c[1], url, &c[3]),
+                Some(Err(message)) => {
+                    broken.push(message);
+                    c[0].to_string()
+                }
+                None => c[0].to_string(),
+            });
+            let rewritten = rewritten.into_owned();
+            // 摘要中的链接与正文重复，只按正文报告
+            if key == "content" {
+                issues.extend(broken.into_iter().map(|message| BuildIssue::new(IssueKind::Link, &source, message)));
+            }
+            if let Some(obj) = post.data.as_object_mut() {
+                obj.insert(key.to_string(), rewritten.into());
+            }
+        }
+    }
+    issues
+}
+
+/// 正文中的站内文章链接：`(被链接文章下标, 链接所在文章下标)`，供构建清单在目标文章变化时重新生成链接所在页面
+pub fn backlinks(posts: &[Post]) -> Vec<(usize, usize)> {
+    let index: HashMap<&str, usize> = posts.iter().enumerate().filter_map(|(i, post)| Some((post.url()?, i))).collect();
+    let mut links = Vec::new();
+    for (i, post) in posts.iter().enumerate() {
+        let Some(html) = post.content() else {
+            continue;
+        };
+        for c in RE_HREF.captures_iter(html) {
+            let href = &c[2];
+            let path = &href[..href.find(['#', '?']).unwrap_or(href.len())];
+            if let Some(&target) = index.get(path).filter(|&&target| target != i) {
+                links.push((target, i));
+            }
+        }
+    }
+    links.sort_unstable();
+    links.dedup();
+    links
+}
+
+/// 解析单个链接：不是指向 `.md` 的站内链接时返回 None；
+/// 找到目标文章返回改写后的地址，否则返回问题说明
+fn resolve(href: &str, source: &Path, md_dir: &Path, urls: &HashMap<PathBuf, String>) -> Option<Result<String, String>> {
+    let lower = href.trim().to_ascii_lowercase();
+    let external = lower.starts_with("//")
+        || lower.starts_with('#')
+        || lower
+            .split_once(':')
+            .is_some_and(|(scheme, _)| !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c)));
+    if external {
+        return None;
+    }
+
+    let href = href.trim();
+    let split = href.find(['#', '?']).unwrap_or(href.len());
+    let (path, suffix) = href.split_at(split);
+    if !path.to_ascii_lowercase().ends_with(".md") {
+        return None;
+    }
+
+    let decoded = percent_decode(path);
+    let target = match decoded.strip_prefix('/') {
+        Some(rooted) => md_dir.join(rooted),
+        None => source.parent().unwrap_or(Path::new("")).join(&decoded),
+    };
+    let target = normalize(&target);
+    Some(match urls.get(&target) {
+        Some(url) => Ok(format!("{}{}", url, suffix)),
+        // 根目录的 README.md 是主页本身，不在文章列表中
+        None if target == normalize(&md_dir.join("README.md")) => Ok(format!("/{}", suffix)),
+        None if target.exists() => Err(format!("链接指向未发布的文章（草稿或解析失败）: {}", href)),
+        None => Err(format!("链接的文件不存在: {}", href)),
+    })
+}
+
+/// 按字面归一化路径中的 `.` 与 `..`（不访问文件系统）
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(out.components().next_back(), Some(Component::Normal(_))) {
+                    out.pop();
+                } else {
+                    out.push("..");
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
@@ -0,0 +1,259 @@
+//! 浏览器实时刷新（serve 模式）
+//!
+//! 开发服务器在 `/__rustpress/livereload` 提供 SSE 事件流，并向 HTML 响应注入一段客户端脚本：
+//! 重新构建成功后刷新页面；只有 CSS 变化时仅替换对应样式表、不刷新页面；
+//! 构建失败时在页面上以浮层显示出错的文件与信息，修复后自动刷新。`rustpress build` 的输出不含该脚本。
+
+use crate::report::{BuildIssue, IssueKind};
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// SSE 事件流地址
+pub const EVENTS_PATH: &str = "/__rustpress/livereload";
+/// 客户端脚本地址
+pub const SCRIPT_PATH: &str = "/__rustpress/livereload.js";
+
+/// 客户端脚本：监听事件流，刷新页面、热替换样式表或显示错误浮层
+const CLIENT_SCRIPT: &str = r#"(function () {
+  if (!window.EventSource) return;
+  var source = new EventSource("/__rustpress/livereload");
+  var overlayId = "rustpress-error-overlay";
+
+  function removeOverlay() {
+    var old = document.getElementById(overlayId);
+    if (old) old.remove();
+  }
+
+  function showOverlay(errors) {
+    removeOverlay();
+    var overlay = document.createElement("div");
+    overlay.id = overlayId;
+    overlay.style.cssText = "position:fixed;inset:0;z-index:2147483647;overflow:auto;padding:32px;" +
+      "background:rgba(24,24,27,.92);color:#fafafa;font:14px/1.6 ui-monospace,SFMono-Regular,Menlo,monospace";
+    var title = document.createElement("h2");
+    title.textContent = "构建失败";
+    title.style.cssText = "margin:0 0 16px;color:#f87171;font-size:20px";
+    overlay.appendChild(title);
+    errors.forEach(function (error) {
+      var item = document.createElement("div");
+      item.style.cssText = "margin:0 0 16px;padding:12px 16px;border-left:4px solid #f87171;background:rgba(255,255,255,.06)";
+      if (error.file) {
+        var file = document.createElement("div");
+        file.textContent = error.file;
+        file.style.cssText = "color:#fbbf24;margin-bottom:4px";
+        item.appendChild(file);
+      }
+      var message = document.createElement("pre");
+      message.textContent = error.message;
+      message.style.cssText = "margin:0;white-space:pre-wrap";
+      item.appendChild(message);
+      overlay.appendChild(item);
+    });
+    var hint = document.createElement("p");
+    hint.textContent = "修复后保存文件，页面会自动刷新（点击浮层关闭）";
+    hint.style.cssText = "color:#a1a1aa";
+    overlay.appendChild(hint);
+    overlay.addEventListener("click", removeOverlay);
+    document.body.appendChild(overlay);
+  }
+
+  function swapCss(paths) {
+    var links = document.querySelectorAll('link[rel="stylesheet"]');
+    var swapped = 0;
+    Array.prototype.forEach.call(links, function (link) {
+      var url = new URL(link.href, location.href);
+      if (url.origin !== location.origin || paths.indexOf(url.pathname) < 0) return;
+      url.searchParams.set("livereload", Date.now());
+      var next = link.cloneNode();
+      next.href = url.toString();
+      next.onload = function () { link.remove(); };
+      link.after(next);
+      swapped++;
+    });
+    // 页面未引用变化的样式表（如 @import 引入）时整体刷新
+    if (!swapped) location.reload();
+  }
+
+  source.addEventListener("reload", function () { location.reload(); });
+  source.addEventListener("css", function (e) { removeOverlay(); swapCss(JSON.parse(e.data).paths); });
+  source.addEventListener("build-error", function (e) { showOverlay(JSON.parse(e.data).errors); });
+})();
+"#;
+
+/// 推送给浏览器的事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReloadEvent {
+    /// 刷新页面
+    Reload,
+    /// 仅替换样式表（站点路径，如 `/static/css/main.css`）
+    Css { paths: Vec<String> },
+    /// 构建失败
+    Error { errors: Vec<ErrorEntry> },
+}
+
+impl ReloadEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ReloadEvent::Reload => "reload",
+            ReloadEvent::Css { .. } => "css",
+            ReloadEvent::Error { .. } => "build-error",
+        }
+    }
+}
+
+/// 错误浮层中的一条
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorEntry {
+    /// 出错的文件（未知时为空）
+    pub file: String,
+    pub message: String,
+}
+
+impl ErrorEntry {
+    pub fn new<F: Into<String>, M: Into<String>>(file: F, message: M) -> Self {
+        ErrorEntry { file: file.into(), message: message.into() }
+    }
+}
+
+/// 实时刷新通道：构建循环发送事件，每个打开的页面各自订阅
+#[derive(Debug, Clone)]
+pub struct LiveReload {
+    tx: broadcast::Sender<ReloadEvent>,
+    /// 尚未修复的构建错误，新打开（或刷新后）的页面连接时立即收到
+    last_error: Arc<Mutex<Option<ReloadEvent>>>,
+}
+
+impl Default for LiveReload {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveReload {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(16);
+        LiveReload { tx, last_error: Arc::new(Mutex::new(None)) }
+    }
+
+    /// 向所有页面推送事件；成功的事件会清除记录的构建错误
+    pub fn send(&self, event: ReloadEvent) {
+        *self.last_error.lock().unwrap() = matches!(event, ReloadEvent::Error { .. }).then(|| event.clone());
+        // 没有打开的页面时发送失败，忽略即可
+        let _ = self.tx.send(event);
+    }
+
+    /// 订阅事件：先返回尚未修复的构建错误，再依次返回新事件
+    pub fn subscribe(&self) -> impl Stream<Item = ReloadEvent> + Send + use<> {
+        let pending = self.last_error.lock().unwrap().clone();
+        let updates = stream::unfold(self.tx.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+        stream::iter(pending).chain(updates)
+    }
+
+    /// 事件流与客户端脚本路由
+    pub fn routes(&self) -> Router {
+        let live = self.clone();
+        Router::new()
+            .route(
+                EVENTS_PATH,
+                get(move || {
+                    let events = live.subscribe().map(|event| {
+                        let data = serde_json::to_string(&event).unwrap_or_default();
+                        Ok::<_, Infallible>(Event::default().event(event.name()).data(data))
+                    });
+                    async move { Sse::new(events).keep_alive(KeepAlive::default()) }
+                }),
+            )
+            .route(
+                SCRIPT_PATH,
+                get(|| async { ([(header::CONTENT_TYPE, "text/javascript; charset=utf-8")], CLIENT_SCRIPT) }),
+            )
+    }
+}
+
+/// 中间件：向成功的 HTML 响应注入客户端脚本
+pub async fn inject_client(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if response.status() != StatusCode::OK || !is_html {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    if bytes.is_empty() {
+        return Response::from_parts(parts, Body::empty());
+    }
+    let html = inject_script(&String::from_utf8_lossy(&bytes));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+    Response::from_parts(parts, Body::from(html))
+}
+
+/// 在最后一个 `</body>` 之前插入客户端脚本（没有时追加到末尾）
+pub fn inject_script(html: &str) -> String {
+    let tag = format!(r#"<script src="{}" defer></script>"#, SCRIPT_PATH);
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(pos) => format!("{}{}{}", &html[..pos], tag, &html[pos..]),
+        None => format!("{}{}", html, tag),
+    }
+}
+
+/// 变化的文件全部是 CSS 时，返回它们在站点中的路径（按所在根目录换算），否则返回 None（需刷新页面）
+///
+/// `roots` 为复制到输出目录根的源目录，如主题静态目录与 md_dir。
+pub fn css_only_changes(changed: &[PathBuf], roots: &[&Path]) -> Option<Vec<String>> {
+    if changed.is_empty() {
+        return None;
+    }
+    let mut paths: Vec<String> = changed
+        .iter()
+        .map(|path| {
+            if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("css")) {
+                return None;
+            }
+            let rel = roots.iter().find_map(|root| path.strip_prefix(root).ok())?;
+            let segments: Vec<_> = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+            Some(format!("/{}", segments.join("/")))
+        })
+        .collect::<Option<_>>()?;
+    paths.sort();
+    paths.dedup();
+    Some(paths)
+}
+
+/// 构建问题中会导致页面缺失或出错的部分（解析失败、模板渲染失败），用于错误浮层
+pub fn blocking_issues(issues: &[BuildIssue]) -> Vec<ErrorEntry> {
+    issues
+        .iter()
+        .filter(|issue| matches!(issue.kind, IssueKind::Parse | IssueKind::Template))
+        .map(|issue| ErrorEntry::new(issue.path.display().to_string(), issue.message.clone()))
+        .collect()
+}
@@ -16,6 +16,7 @@ use std::process::exit;
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    configure_jobs(cli.jobs)?;
 
     match &cli.command {
         Commands::New { name, force } => new_project(name, *force),
@@ -25,7 +26,8 @@ fn main() -> Result<()> {
         Commands::Build {
             output_dir,
             incremental,
-        } => build_site(&cli.md_dir, output_dir, &cli.config, *incremental),
+            strict,
+        } => build_site(&cli.md_dir, output_dir, &cli.config, *incremental, *strict),
         Commands::BuildDev {
             output_dir,
             incremental,
@@ -45,9 +47,10 @@ fn main() -> Result<()> {
             output_dir,
             incremental: _,
             no_hotreload,
+            in_memory,
         } => {
             if !*no_hotreload {
-                dev_site_hotreload(*port, &cli.md_dir, output_dir, &cli.config, false)
+                dev_site_hotreload(*port, &cli.md_dir, output_dir, &cli.config, false, *in_memory)
             } else {
                 // 即使不显式开启 hotreload，当前 serve 也默认调用同步预览（单次构建后 serve）
                 println!("以静态模式启动预览服务器...");
@@ -57,14 +60,38 @@ fn main() -> Result<()> {
                     &cli.config,
                 );
                 let config = Config::from_file(&config_path)?;
-                build_site(&cli.md_dir, output_dir, &cli.config, false)?;
-                DevServer::serve_sync(*port, output_dir, Some(&config))
+                build_site(&cli.md_dir, output_dir, &cli.config, false, false)?;
+                DevServer::serve_sync(*port, output_dir, Some(&config), Some(std::path::PathBuf::from(&cli.md_dir)))
             }
         }
         Commands::BuildSidebar => build_sidebar(&cli.md_dir, &cli.config),
+        Commands::Check => check_site(&cli.md_dir),
+        Commands::CheckLinks {
+            output_dir,
+            build,
+            external,
+            concurrency,
+            timeout,
+        } => check_links(&cli.md_dir, output_dir, &cli.config, *build, *external, *concurrency, *timeout),
+        Commands::RotateKeys {
+            doc,
+            output_dir,
+            manifest,
+        } => rotate_keys(&cli.md_dir, output_dir, &cli.config, doc, manifest),
     }
 }
 
+/// 设置解析与渲染线程池的线程数（`--jobs`），未指定或为 0 时使用全部 CPU 核心
+fn configure_jobs(jobs: Option<usize>) -> Result<()> {
+    let Some(jobs) = jobs.filter(|n| *n > 0) else {
+        return Ok(());
+    };
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build_global()
+        .map_err(|e| rustpress::error::Error::Other(format!("无法创建线程池: {}", e)))
+}
+
 /// 创建新的博客项目
 fn new_project(name: &str, force: bool) -> Result<()> {
     let project_path = Path::new(name);
@@ -150,7 +177,13 @@ tags: ["Rust", "博客"]
 }
 
 /// 构建博客网站
-fn build_site(md_dir: &str, output_dir: &str, config_file: &str, incremental: bool) -> Result<()> {
+fn build_site(
+    md_dir: &str,
+    output_dir: &str,
+    config_file: &str,
+    incremental: bool,
+    strict: bool,
+) -> Result<()> {
     use std::path::Path;
     // 启动时初始化（themes/config.toml/build.toml 及示例页）
     ensure_initial_setup(Path::new(md_dir), config_file)?;
@@ -159,7 +192,7 @@ fn build_site(md_dir: &str, output_dir: &str, config_file: &str, incremental: bo
         rustpress::utils::resolve_config_toml_path_read(Path::new(md_dir), config_file);
 
     let config = Config::from_file(&config_path)?;
-    let generator = Generator::new(config, Path::new(md_dir))?;
+    let generator = Generator::new(config, Path::new(md_dir))?.with_strict(strict);
 
     // 根据 build.toml 的编译模式决定默认行为；命令行 --incremental 显式开启则覆盖为增量
     let file_mode = rustpress::utils::read_build_mode(std::path::Path::new(md_dir));
@@ -206,22 +239,26 @@ fn build_dev_site(
 
     // 先构建 CSS
     println!("正在构建主题 CSS...");
-    // 加载配置以确定主题名称
-    // 启动时初始化（themes/config.toml/build.toml 及示例页）
-    ensure_initial_setup(std::path::Path::new(md_dir), config_file)?;
-    let config_path =
-        rustpress::utils::resolve_config_toml_path_read(std::path::Path::new(md_dir), config_file);
-    let config = Config::from_file(&config_path)?;
-    build_theme_css(md_dir, &config)?;
+    // 启动时初始化（themes/config.toml/build.toml 及示例页），加载配置以确定主题名称
+    prepare_dev_site(md_dir, config_file)?;
 
     // 再构建网站
     println!("正在构建网站...");
-    build_site(md_dir, output_dir, config_file, incremental)?;
+    build_site(md_dir, output_dir, config_file, incremental, false)?;
 
     println!("开发环境构建完成！");
     Ok(())
 }
 
+/// 初始化项目并构建主题 CSS（开发环境构建的第一步；内存预览模式只执行这一步）
+fn prepare_dev_site(md_dir: &str, config_file: &str) -> Result<()> {
+    ensure_initial_setup(std::path::Path::new(md_dir), config_file)?;
+    let config_path =
+        rustpress::utils::resolve_config_toml_path_read(std::path::Path::new(md_dir), config_file);
+    let config = Config::from_file(&config_path)?;
+    build_theme_css(md_dir, &config)
+}
+
 /// 开发模式（hotreload）：构建并启动服务器，同时监听模板与内容变化自动重建
 fn dev_site_hotreload(
     port: u16,
@@ -229,11 +266,17 @@ fn dev_site_hotreload(
     output_dir: &str,
     config_file: &str,
     _incremental: bool,
+    in_memory: bool,
 ) -> Result<()> {
     println!("开发模式（hotreload）启动中...");
 
-    // 先全量构建生成聚合页（归档/分类/标签/RSS等），同时初始化静态资源与 CSS
-    build_dev_site(md_dir, output_dir, config_file, false)?;
+    if in_memory {
+        // 内存预览：只初始化项目与主题 CSS，站点由预览服务器构建到内存中，不写入输出目录
+        prepare_dev_site(md_dir, config_file)?;
+    } else {
+        // 先全量构建生成聚合页（归档/分类/标签/RSS等），同时初始化静态资源与 CSS
+        build_dev_site(md_dir, output_dir, config_file, false)?;
+    }
 
     let rt = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -241,14 +284,25 @@ fn dev_site_hotreload(
         .map_err(|e| rustpress::error::Error::Server(format!("无法创建异步运行时: {}", e)))?;
 
     rt.block_on(async {
-        DevServer::serve_live(
-            port,
-            Path::new(md_dir),
-            Path::new(output_dir),
-            config_file,
-            true, // 自动打开浏览器
-            std::future::pending(),
-        ).await
+        if in_memory {
+            DevServer::serve_live_in_memory(
+                port,
+                Path::new(md_dir),
+                Path::new(output_dir),
+                config_file,
+                true, // 自动打开浏览器
+                std::future::pending(),
+            ).await
+        } else {
+            DevServer::serve_live(
+                port,
+                Path::new(md_dir),
+                Path::new(output_dir),
+                config_file,
+                true, // 自动打开浏览器
+                std::future::pending(),
+            ).await
+        }
     })
 }
 
@@ -260,3 +314,79 @@ fn build_sidebar(md_dir: &str, _config_file: &str) -> Result<()> {
     println!("已根据当前内容重新生成 build.toml 的侧边栏数据");
     Ok(())
 }
+
+/// 校验内容，存在错误时以状态码 1 退出
+fn check_site(md_dir: &str) -> Result<()> {
+    let report = rustpress::check::check_site(md_dir)?;
+    for issue in &report.issues {
+        println!("{}", issue);
+    }
+    println!(
+        "检查完成：共 {} 个文件，{} 个错误，{} 个警告",
+        report.files,
+        report.error_count(),
+        report.warning_count()
+    );
+    if report.has_errors() {
+        exit(1);
+    }
+    Ok(())
+}
+
+/// 检查构建输出中的链接，存在失效的站内链接时以状态码 1 退出（站外链接失效仅作警告）
+fn check_links(
+    md_dir: &str,
+    output_dir: &str,
+    config_file: &str,
+    build: bool,
+    external: bool,
+    concurrency: Option<usize>,
+    timeout: Option<u64>,
+) -> Result<()> {
+    let config_path = rustpress::utils::resolve_config_toml_path_read(Path::new(md_dir), config_file);
+    let config = Config::from_file(&config_path)?;
+    if build {
+        build_site(md_dir, output_dir, config_file, false, false)?;
+    }
+
+    let mut options = rustpress::linkcheck::LinkCheckOptions::from_config(&config);
+    options.external |= external;
+    if let Some(concurrency) = concurrency.filter(|n| *n > 0) {
+        options.concurrency = concurrency;
+    }
+    if let Some(timeout) = timeout.filter(|n| *n > 0) {
+        options.timeout = std::time::Duration::from_secs(timeout);
+    }
+
+    let report = rustpress::linkcheck::check_links(Path::new(output_dir), Path::new(md_dir), &config, &options)?;
+    println!("{}", report);
+    if report.has_errors() {
+        exit(1);
+    }
+    Ok(())
+}
+
+/// 轮换指定专栏的内容密钥并写出密钥清单
+fn rotate_keys(
+    md_dir: &str,
+    output_dir: &str,
+    config_file: &str,
+    doc_id: &str,
+    manifest_path: &str,
+) -> Result<()> {
+    let config_path = rustpress::utils::resolve_config_toml_path_read(Path::new(md_dir), config_file);
+    let config = Config::from_file(&config_path)?;
+    let generator = Generator::new(config, Path::new(md_dir))?;
+
+    let manifest = generator.rotate_keys(md_dir, output_dir, doc_id)?;
+    // 与密钥文件一致，相对路径按项目根解析
+    let manifest_path = rustpress::utils::project_root(Path::new(md_dir)).join(manifest_path);
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    println!(
+        "密钥轮换完成：{} -> {}，清单已写入 {}",
+        manifest.old_key_id.as_deref().unwrap_or("(无)"),
+        manifest.new_key_id,
+        manifest_path.display()
+    );
+    Ok(())
+}
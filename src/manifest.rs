@@ -0,0 +1,426 @@
+//! 构建清单模块
+//!
+//! 增量构建原先比较文件修改时间与 build.toml 的 `last_build_time`，`git clone` / 切换分支后所有 mtime
+//! 都会变化，模板与配置的修改也无法察觉。构建清单（输出目录下的 `.rustpress-manifest.json`）改为记录
+//! 每个源文件、模板与配置的内容哈希，以及依赖图：每个源文件参与生成了哪些输出文件
+//! （文章页、首页分页、标签/分类/归档页、订阅源，以及同书章节、列表页与链接到它的文章页）。增量构建据此只重新生成受影响的输出，
+//! 并删除源文件已消失的输出。搜索索引与 sitemap 依赖全部文章，有任何变化时整体重建，不记入依赖图。
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::feed::{self, FeedFormat};
+use crate::front_matter::Layout;
+use crate::post::Post;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// 清单文件名（位于输出目录，随输出目录一同清理）
+pub const MANIFEST_FILE: &str = ".rustpress-manifest.json";
+/// 清单格式版本，格式变化时递增
+pub const MANIFEST_VERSION: u32 = 3;
+/// 生成规则版本：页面结构、依赖规则等生成逻辑变化时手动递增，使已有输出全量重建
+/// （开发期间代码变化不会改变 rustpress 版本号）
+pub const OUTPUT_FORMAT: u32 = 1;
+
+/// 构建清单
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub version: u32,
+    /// 生成清单的 rustpress 版本（内置模板随版本变化）
+    pub generator: String,
+    /// 生成规则版本，见 [`OUTPUT_FORMAT`]
+    pub format: u32,
+    /// 站点配置的内容哈希
+    pub config: String,
+    /// 主题模板（相对模板目录）-> 内容哈希；使用内置模板时为空
+    pub templates: BTreeMap<String, String>,
+    /// 源文件（相对 md_dir，`/` 分隔）-> 哈希与输出
+    pub sources: BTreeMap<String, SourceEntry>,
+}
+
+/// 单个源文件的记录
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceEntry {
+    pub hash: String,
+    /// 付费文章加密所用内容密钥的 key_id（`crypto::key_id`）；密钥来自 DOC_KEYS 或项目根的密钥文件，
+    /// 不在源文件哈希中，轮换后据此重新生成
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+    /// 该源文件参与生成的输出文件（相对输出目录，`/` 分隔）
+    pub outputs: BTreeSet<String>,
+}
+
+/// 两次构建清单的差异
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    /// 新增、内容变化或内容密钥变化的源文件
+    pub changed: BTreeSet<String>,
+    /// 已删除的源文件
+    pub removed: BTreeSet<String>,
+    /// 需要重新生成的输出
+    pub affected: BTreeSet<String>,
+    /// 不再由任何源文件生成、应删除的输出
+    pub stale: BTreeSet<String>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty() && self.affected.is_empty() && self.stale.is_empty()
+    }
+}
+
+impl BuildManifest {
+    /// 读取清单；不存在、无法解析或版本不符时返回 None（需全量构建）
+    pub fn load<P: AsRef<Path>>(path: P) -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(path).ok()?)
+    }
+
+    /// 从 JSON 文本解析清单（内存文件系统模式下清单不落盘）；无法解析或版本不符时返回 None
+    pub fn parse(content: &str) -> Option<Self> {
+        let manifest: BuildManifest = serde_json::from_str(content).ok()?;
+        (manifest.version == MANIFEST_VERSION).then_some(manifest)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| Error::Other(format!("无法写入构建清单 {:?}: {}", path, e)))
+    }
+
+    /// 收集本次构建的清单：md_dir 下全部非隐藏文件的哈希，文章的输出取自依赖图，密钥取自已加密文章
+    pub fn collect(md_dir: &Path, templates_dir: &Path, config: &Config, posts: &[Post]) -> Result<Self> {
+        let graph = dependency_graph(config, posts);
+        let key_ids: BTreeMap<String, String> = posts
+            .iter()
+            .filter_map(|post| {
+                let key_id = post.data.get("key_id")?.as_str()?;
+                Some((normalize_source(Path::new(post.source_path()?)), key_id.to_string()))
+            })
+            .collect();
+        let mut sources = BTreeMap::new();
+        for entry in WalkDir::new(md_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() || entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(rel) = entry.path().strip_prefix(md_dir) else {
+                continue;
+            };
+            let rel = rel_key(rel);
+            let key_id = key_ids.get(&normalize_source(entry.path())).cloned();
+            let outputs = if entry.path().extension().is_some_and(|ext| ext == "md") {
+                match graph.get(&normalize_source(entry.path())) {
+                    Some(outputs) => outputs.clone(),
+                    // 根目录 README.md 是首页本身；其余（草稿、解析失败）没有输出
+                    None if rel == "README.md" => BTreeSet::from(["index.html".to_string()]),
+                    None => BTreeSet::new(),
+                }
+            } else {
+                // 附件按原相对路径复制
+                BTreeSet::from([rel.clone()])
+            };
+            sources.insert(rel, SourceEntry { hash: hash_file(entry.path())?, key_id, outputs });
+        }
+
+        Ok(BuildManifest {
+            version: MANIFEST_VERSION,
+            generator: env!("CARGO_PKG_VERSION").to_string(),
+            format: OUTPUT_FORMAT,
+            config: hash_config(config),
+            templates: hash_templates(templates_dir)?,
+            sources,
+        })
+    }
+
+    /// 配置、模板或程序版本相对本清单有变化时返回原因（需全量构建）
+    pub fn full_rebuild_reason(&self, config: &Config, templates_dir: &Path) -> Result<Option<&'static str>> {
+        Ok(if self.generator != env!("CARGO_PKG_VERSION") {
+            Some("rustpress 版本变化")
+        } else if self.format != OUTPUT_FORMAT {
+            Some("生成规则变化")
+        } else if self.config != hash_config(config) {
+            Some("配置变化")
+        } else if self.templates != hash_templates(templates_dir)? {
+            Some("模板变化")
+        } else {
+            None
+        })
+    }
+
+    /// 与上次清单比较：内容或内容密钥变化的源文件影响其新旧输出的并集；
+    /// 内容未变但输出集合变化（如分页位置移动）时影响两者的差集
+    pub fn diff(&self, previous: &BuildManifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+        for (source, entry) in &self.sources {
+            match previous.sources.get(source) {
+                Some(old) if old.hash == entry.hash && old.key_id == entry.key_id => {
+                    diff.affected.extend(old.outputs.symmetric_difference(&entry.outputs).cloned());
+                }
+                Some(old) => {
+                    diff.changed.insert(source.clone());
+                    diff.affected.extend(old.outputs.union(&entry.outputs).cloned());
+                }
+                None => {
+                    diff.changed.insert(source.clone());
+                    diff.affected.extend(entry.outputs.iter().cloned());
+                }
+            }
+        }
+        for (source, old) in &previous.sources {
+            if !self.sources.contains_key(source) {
+                diff.removed.insert(source.clone());
+                diff.affected.extend(old.outputs.iter().cloned());
+            }
+        }
+
+        let current: BTreeSet<&String> = self.sources.values().flat_map(|e| &e.outputs).collect();
+        diff.stale = previous
+            .sources
+            .values()
+            .flat_map(|e| &e.outputs)
+            .filter(|output| !current.contains(output))
+            .cloned()
+            .collect();
+        diff.affected.retain(|output| !diff.stale.contains(output));
+        diff
+    }
+}
+
+/// 文章页的输出文件（相对输出目录）；根目录 README 即首页，由首页分页生成，返回 None
+pub fn page_output(post: &Post) -> Option<String> {
+    let page = post.url()?.trim_start_matches('/');
+    if post.categories().is_empty() && post.slug() == Some("index") {
+        return None;
+    }
+    Some(if page.is_empty() || page.ends_with('/') { format!("{}index.html", page) } else { page.to_string() })
+}
+
+/// 文章所属的书（与 doc 布局的侧边栏一致：`docs/<id>` 与 `columns/<id>` 下同名目录视为同一本书）
+fn book_id(post: &Post) -> Option<String> {
+    let mut cats = post.categories();
+    (cats.len() >= 2 && (cats[0] == "docs" || cats[0] == "columns")).then(|| cats.swap_remove(1))
+}
+
+/// 是否为汇总其他文章的列表布局（docs、专栏总览、projects、works、friends、about）
+fn is_listing(post: &Post) -> bool {
+    let layout = post.data.get("layout").and_then(|v| v.as_str());
+    match layout {
+        Some("docs" | "projects" | "works" | "friends" | "about") => true,
+        Some("columns") => {
+            let cats = post.categories();
+            cats.is_empty() || cats == ["columns"]
+        }
+        _ => false,
+    }
+}
+
+/// `post` 是否出现在列表页 `listing` 中（与 `TemplateEngine::render_post` 的筛选规则一致）
+fn listed_in(listing: &Post, post: &Post) -> bool {
+    let cats = post.categories();
+    let book_readme = cats.len() == 2 && post.slug() == Some("index");
+    match listing.data.get("layout").and_then(|v| v.as_str()) {
+        Some("docs") => book_readme && cats[0] == "docs" && post.has_layout(Layout::Doc),
+        Some("columns") => book_readme && cats[0] == "columns" && post.has_layout(Layout::Columns),
+        Some("projects") => post.has_layout(Layout::Project),
+        Some("works") => cats == ["works"] && post.has_layout(Layout::Work),
+        Some("friends") => post.has_layout(Layout::Friend),
+        // about 页汇总全站统计
+        Some("about") => true,
+        _ => false,
+    }
+}
+
+/// 依赖图：文章源文件路径（与 `Post::source_path` 一致）-> 该文章参与生成的输出文件
+///
+/// 分页规则与 `Generator` 一致：按日期降序，倒分页（最新一页为 `index.html`，页数大于 1 时另写 `indexN.html`）。
+pub fn dependency_graph(config: &Config, posts: &[Post]) -> BTreeMap<String, BTreeSet<String>> {
+    let per_page = |section: &str, fallback: i64| {
+        config
+            .data
+            .get(section)
+            .and_then(|v| v.get("posts_per_page"))
+            .and_then(|v| v.as_integer())
+            .or_else(|| config.data.get("homepage")?.get("posts_per_page")?.as_integer())
+            .unwrap_or(fallback)
+            .max(1) as usize
+    };
+    let home_per_page = per_page("homepage", 10);
+    let tags_per_page = per_page("tags", 8);
+    let categories_per_page = per_page("categories", 8);
+    let feed_files: Vec<&str> = FeedFormat::enabled_formats(config).iter().map(|f| f.file_name()).collect();
+    let scoped_feeds = feed::taxonomy_feeds_enabled(config);
+
+    let mut graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut add = |post: &Post, output: String| {
+        if let Some(source) = post.source_path() {
+            graph.entry(normalize_source(Path::new(source))).or_default().insert(output);
+        }
+    };
+
+    let all: Vec<&Post> = posts.iter().collect();
+    for (post, files) in paginate(&all, home_per_page, "", true) {
+        files.into_iter().for_each(|file| add(post, file));
+    }
+
+    let mut tags: BTreeMap<String, Vec<&Post>> = BTreeMap::new();
+    let mut categories: BTreeMap<Vec<String>, Vec<&Post>> = BTreeMap::new();
+    for post in posts {
+        if let Some(page) = page_output(post) {
+            add(post, page);
+        }
+        for file in &feed_files {
+            add(post, file.to_string());
+        }
+        for tag in post.tags().into_iter().collect::<BTreeSet<_>>() {
+            tags.entry(tag).or_default().push(post);
+        }
+        if !post.tags().is_empty() {
+            add(post, "tags/index.html".to_string());
+            add(post, "tags.html".to_string());
+        }
+        let cats = post.categories();
+        for i in 1..=cats.len() {
+            categories.entry(cats[..i].to_vec()).or_default().push(post);
+        }
+        if !cats.is_empty() {
+            add(post, "categories/index.html".to_string());
+        }
+        if let Some(date) = post.date().filter(|d| d.len() >= 4) {
+            add(post, "archives/index.html".to_string());
+            add(post, format!("archives/{}/index.html", &date[..4]));
+            if let Some(month) = date.get(5..7) {
+                add(post, format!("archives/{}/{}/index.html", &date[..4], month));
+            }
+        }
+    }
+
+    // 文章页还依赖其他文章：同一本书（docs/columns 下同名目录，含 README）的侧边栏、上下篇与目录，
+    // 列表布局汇总的文章，以及正文中改写后指向该文章的站内链接
+    let pages: Vec<Option<String>> = posts.iter().map(page_output).collect();
+    let mut books: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, post) in posts.iter().enumerate() {
+        if let Some(book) = book_id(post) {
+            books.entry(book).or_default().push(i);
+        }
+    }
+    for members in books.values() {
+        for &i in members {
+            for &j in members.iter().filter(|&&j| j != i) {
+                if let Some(page) = &pages[j] {
+                    add(&posts[i], page.clone());
+                }
+            }
+        }
+    }
+    for (j, listing) in posts.iter().enumerate().filter(|(_, p)| is_listing(p)) {
+        if let Some(page) = &pages[j] {
+            posts.iter().filter(|p| listed_in(listing, p)).for_each(|post| add(post, page.clone()));
+        }
+    }
+    for (target, source) in crate::links::backlinks(posts) {
+        if let Some(page) = &pages[source] {
+            add(&posts[target], page.clone());
+        }
+    }
+
+    for (tag, list) in tags {
+        let dir = format!("tags/{}/", tag);
+        for (post, files) in paginate(&list, tags_per_page, &dir, true) {
+            files.into_iter().for_each(|file| add(post, file));
+            if scoped_feeds {
+                feed_files.iter().for_each(|file| add(post, format!("{}{}", dir, file)));
+            }
+        }
+    }
+    for (path, list) in categories {
+        let dir = format!("{}/", path.join("/"));
+        // 分类下已有 README（slug 为 index）时，最新一页不写 index.html
+        let manual_index = list.iter().any(|p| p.categories() == path && p.slug() == Some("index"));
+        for (post, files) in paginate(&list, categories_per_page, &dir, !manual_index) {
+            files.into_iter().for_each(|file| add(post, file));
+            if scoped_feeds {
+                feed_files.iter().for_each(|file| add(post, format!("{}{}", dir, file)));
+            }
+        }
+    }
+    graph
+}
+
+/// 按日期降序倒分页，返回每篇文章所在的分页文件（`dir` 为目录前缀，如 `tags/Rust/`）
+fn paginate<'a>(posts: &[&'a Post], per_page: usize, dir: &str, write_index: bool) -> Vec<(&'a Post, Vec<String>)> {
+    let mut sorted: Vec<&Post> = posts.to_vec();
+    sorted.sort_by(|a, b| b.date().unwrap_or("").cmp(a.date().unwrap_or("")));
+    let total = sorted.len().div_ceil(per_page);
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, post)| {
+            let page = (sorted.len() - 1 - i) / per_page + 1;
+            let mut files = Vec::new();
+            if page == total {
+                if write_index {
+                    files.push(format!("{}index.html", dir));
+                }
+                if total > 1 {
+                    files.push(format!("{}index{}.html", dir, total));
+                }
+            } else {
+                files.push(format!("{}index{}.html", dir, page));
+            }
+            (*post, files)
+        })
+        .collect()
+}
+
+/// 内容哈希（SHA-256 十六进制）
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn hash_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| Error::Other(format!("无法读取文件 {:?}: {}", path, e)))?;
+    Ok(hash_bytes(&bytes))
+}
+
+/// 配置哈希取自解析后的配置（只改注释或空白不会触发全量构建）
+pub fn hash_config(config: &Config) -> String {
+    hash_bytes(toml::to_string(&config.data).unwrap_or_default().as_bytes())
+}
+
+/// 模板目录下全部文件的哈希；目录不存在（使用内置模板）时为空
+pub fn hash_templates(templates_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut templates = BTreeMap::new();
+    if !templates_dir.is_dir() {
+        return Ok(templates);
+    }
+    for entry in WalkDir::new(templates_dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Ok(rel) = entry.path().strip_prefix(templates_dir) {
+            templates.insert(rel_key(rel), hash_file(entry.path())?);
+        }
+    }
+    Ok(templates)
+}
+
+/// 相对路径统一为 `/` 分隔
+fn rel_key(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 源文件在清单中的键（相对 md_dir）
+pub fn source_key(md_dir: &Path, path: &Path) -> Option<String> {
+    let md_dir = crate::links::normalize(md_dir);
+    crate::links::normalize(path).strip_prefix(&md_dir).ok().map(rel_key)
+}
+
+fn normalize_source(path: &Path) -> String {
+    rel_key(&crate::links::normalize(path))
+}
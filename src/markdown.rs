@@ -0,0 +1,201 @@
+//! Markdown 扩展选项模块
+//!
+//! 站点配置 `[markdown]` 决定 comrak 启用哪些扩展，文章可通过 front matter 的
+//! `markdown:` 表按篇覆盖（只需写出要改动的键）：
+//!
+//! ```yaml
+//! markdown:
+//!   unsafe_html: true
+//!   math: true
+//! ```
+//!
+//! 默认开启表格、删除线、脚注、任务列表、`==高亮==`、标题锚点与提示块，其余关闭。
+//! 提示块的默认标题可在 `[markdown.callouts]` 中按类型修改（如 `tip = "小贴士"`）。
+
+use crate::callout;
+use crate::config::Config;
+use comrak::Options;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// `[markdown]` 支持的键
+pub const KEYS: &[&str] = &[
+    "table", "strikethrough", "footnotes", "tasklist", "highlight", "unsafe_html", "autolink",
+    "superscript", "description_lists", "front_matter_delimiter", "smart_punctuation",
+    "hard_breaks", "header_ids", "header_id_prefix", "alerts", "callouts", "wikilinks", "math",
+];
+
+/// Markdown 扩展选项（`[markdown]` / front matter `markdown:`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// 表格
+    pub table: bool,
+    /// `~~删除线~~`
+    pub strikethrough: bool,
+    /// 脚注
+    pub footnotes: bool,
+    /// 任务列表 `- [x]`
+    pub tasklist: bool,
+    /// `==高亮==` → `<mark>`
+    pub highlight: bool,
+    /// 原样输出 Markdown 中的 HTML（关闭时 HTML 会被替换为注释）
+    pub unsafe_html: bool,
+    /// 自动识别裸链接（`https://…`、`www.…`、邮箱）
+    pub autolink: bool,
+    /// `^上标^`
+    pub superscript: bool,
+    /// 定义列表（`术语` 换行 `: 释义`）
+    pub description_lists: bool,
+    /// 正文内额外的 front matter 分隔符（如 `---`），匹配的块会被跳过
+    pub front_matter_delimiter: Option<String>,
+    /// 智能标点：直引号转弯引号，`--` / `---` 转破折号
+    pub smart_punctuation: bool,
+    /// 段落内的换行直接输出为 `<br>`
+    pub hard_breaks: bool,
+    /// 是否为标题生成锚点 id（关闭后文章目录为空）
+    pub header_ids: bool,
+    /// 锚点 id 前缀
+    pub header_id_prefix: String,
+    /// 提示块：GitHub 风格 `> [!NOTE]` 与 VuePress 风格 `:::tip 标题`
+    pub alerts: bool,
+    /// 提示块默认标题（类型 -> 标题）
+    pub callout_titles: BTreeMap<String, String>,
+    /// `[[页面]]` / `[[页面|标题]]` 维基链接
+    pub wikilinks: bool,
+    /// `$行内$`、`$$块级$$` 与 `` $`代码式`$ `` 数学公式
+    pub math: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            table: true,
+            strikethrough: true,
+            footnotes: true,
+            tasklist: true,
+            highlight: true,
+            unsafe_html: false,
+            autolink: false,
+            superscript: false,
+            description_lists: false,
+            front_matter_delimiter: None,
+            smart_punctuation: false,
+            hard_breaks: false,
+            header_ids: true,
+            header_id_prefix: String::new(),
+            alerts: true,
+            callout_titles: callout::default_titles(),
+            wikilinks: false,
+            math: false,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// 从站点配置读取；未知的键给出警告
+    pub fn from_config(config: &Config) -> Self {
+        let mut options = MarkdownOptions::default();
+        let Some(section) = config.data.get("markdown") else {
+            return options;
+        };
+        let section = serde_json::to_value(section).unwrap_or(Value::Null);
+        if let Some(table) = section.as_object() {
+            for key in table.keys().filter(|k| !KEYS.contains(&k.as_str())) {
+                eprintln!("警告: [markdown] 中未知的选项 `{}`（可选: {}）", key, KEYS.join(", "));
+            }
+        }
+        options.apply(&section);
+        options
+    }
+
+    /// 叠加文章 front matter 中的 `markdown:` 覆盖；没有覆盖时返回 None
+    pub fn with_overrides(&self, overrides: Option<&Value>) -> Option<Self> {
+        let overrides = overrides.filter(|v| v.as_object().is_some_and(|o| !o.is_empty()))?;
+        let mut options = self.clone();
+        options.apply(overrides);
+        Some(options)
+    }
+
+    /// 按键覆盖（布尔值兼容 `"true"` / `"off"` 等字符串写法）
+    fn apply(&mut self, table: &Value) {
+        let flag = |key: &str| table.get(key).and_then(as_flag);
+        let flags: [(&str, &mut bool); 15] = [
+            ("table", &mut self.table),
+            ("strikethrough", &mut self.strikethrough),
+            ("footnotes", &mut self.footnotes),
+            ("tasklist", &mut self.tasklist),
+            ("highlight", &mut self.highlight),
+            ("unsafe_html", &mut self.unsafe_html),
+            ("autolink", &mut self.autolink),
+            ("superscript", &mut self.superscript),
+            ("description_lists", &mut self.description_lists),
+            ("smart_punctuation", &mut self.smart_punctuation),
+            ("hard_breaks", &mut self.hard_breaks),
+            ("header_ids", &mut self.header_ids),
+            ("alerts", &mut self.alerts),
+            ("wikilinks", &mut self.wikilinks),
+            ("math", &mut self.math),
+        ];
+        for (key, field) in flags {
+            if let Some(value) = flag(key) {
+                *field = value;
+            }
+        }
+        if let Some(delimiter) = table.get("front_matter_delimiter") {
+            self.front_matter_delimiter = match delimiter {
+                Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+                Value::String(_) | Value::Bool(false) | Value::Null => None,
+                _ => self.front_matter_delimiter.take(),
+            };
+        }
+        if let Some(prefix) = table.get("header_id_prefix").and_then(|v| v.as_str()) {
+            self.header_id_prefix = prefix.to_string();
+        }
+        if let Some(titles) = table.get("callouts").and_then(|v| v.as_object()) {
+            for (kind, title) in titles {
+                let kind = kind.to_lowercase();
+                match title.as_str() {
+                    Some(title) if self.callout_titles.contains_key(&kind) => {
+                        self.callout_titles.insert(kind, title.to_string());
+                    }
+                    _ => eprintln!("警告: [markdown.callouts] 中未知的提示块类型 `{}`", kind),
+                }
+            }
+        }
+    }
+
+    /// 转换为 comrak 选项（标题锚点由 toc 插件处理，不在此设置）
+    pub fn comrak_options(&self) -> Options<'static> {
+        let mut options = Options::default();
+        options.extension.table = self.table;
+        options.extension.strikethrough = self.strikethrough;
+        options.extension.footnotes = self.footnotes;
+        options.extension.tasklist = self.tasklist;
+        options.extension.highlight = self.highlight;
+        options.extension.autolink = self.autolink;
+        options.extension.superscript = self.superscript;
+        options.extension.description_lists = self.description_lists;
+        options.extension.front_matter_delimiter = self.front_matter_delimiter.clone();
+        options.extension.alerts = self.alerts;
+        options.extension.block_directive = self.alerts;
+        options.extension.wikilinks_title_after_pipe = self.wikilinks;
+        options.extension.math_dollars = self.math;
+        options.extension.math_code = self.math;
+        options.parse.smart = self.smart_punctuation;
+        options.render.hardbreaks = self.hard_breaks;
+        options.render.r#unsafe = self.unsafe_html;
+        options
+    }
+}
+
+fn as_flag(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,1034 @@
+//! 数学公式模块：构建时将 LaTeX 转换为 MathML
+//!
+//! 启用 `[markdown] math`（或文章 front matter `markdown: { math: true }`）后，comrak 会把
+//! `$行内$`、`$$块级$$` 与 `` $`代码式`$ `` 渲染为带 `data-math-style` 的占位元素，
+//! 这里再将其中的 LaTeX 转换为 MathML。浏览器原生渲染，无需从 CDN 加载 KaTeX，
+//! RSS 与付费加密正文中的公式同样可见。
+//!
+//! 支持常用子集：上下标、分式、根式、希腊字母与常用符号、函数名、重音、字体（`\mathbb` 等）、
+//! `\left…\right` 伸缩括号、`\text`、间距，以及 matrix / pmatrix / cases / aligned / array 等环境。
+//! 不支持的命令渲染为 `<merror>` 并保留原文，构建时按文件给出警告（见 [`unsupported`]），不会中断构建。
+
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// comrak 输出的公式占位：```math 代码块（启用代码高亮时属性位于 `<pre>` 上），
+/// 或 `$…$` / `$$…$$` 对应的 span / code
+static RE_MATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"<pre[^>]*?(?:data-math-style="display"[^>]*><code[^>]*|><code[^>]*?data-math-style="display"[^>]*)>([^<]*)</code></pre>|<(?:span|code) data-math-style="(inline|display)">([^<]*)</(?:span|code)>"#,
+    )
+    .unwrap()
+});
+
+/// 转换失败的位置（`<merror data-error="…">`）
+static RE_MERROR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<merror data-error="([^"]*)""#).unwrap());
+
+/// 将 HTML 中的公式占位替换为 MathML
+pub fn render_math(html: &str) -> String {
+    if !html.contains("data-math-style") {
+        return html.to_string();
+    }
+    RE_MATH
+        .replace_all(html, |c: &Captures| match c.get(1) {
+            Some(block) => to_mathml(&unescape_html(block.as_str()), true),
+            None => to_mathml(&unescape_html(&c[3]), &c[2] == "display"),
+        })
+        .into_owned()
+}
+
+/// 收集 HTML 中未能转换的公式片段说明（去重，保持顺序）
+pub fn unsupported(html: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for c in RE_MERROR.captures_iter(html) {
+        let message = unescape_html(&c[1]);
+        if !found.contains(&message) {
+            found.push(message);
+        }
+    }
+    found
+}
+
+/// 将一段 LaTeX 转换为 `<math>` 元素（附带 TeX 原文注解，便于复制与无障碍读屏）
+pub fn to_mathml(latex: &str, display: bool) -> String {
+    let mut parser = Parser::new(latex, display);
+    let (items, _) = parser.parse_row(Ctx::default());
+    let body = if parser.too_deep {
+        // 超过嵌套上限时整段公式显示原文
+        error(&format!("嵌套超过 {} 层", MAX_DEPTH), latex.trim()).xml
+    } else {
+        wrap_row(items)
+    };
+    format!(
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"{}><semantics>{}<annotation encoding=\"application/x-tex\">{}</annotation></semantics></math>",
+        if display { " display=\"block\"" } else { "" },
+        body,
+        escape(latex.trim())
+    )
+}
+
+// ---- 符号表 ----
+
+const GREEK: &[(&str, &str)] = &[
+    ("alpha", "α"), ("beta", "β"), ("gamma", "γ"), ("delta", "δ"), ("epsilon", "ϵ"),
+    ("varepsilon", "ε"), ("zeta", "ζ"), ("eta", "η"), ("theta", "θ"), ("vartheta", "ϑ"),
+    ("iota", "ι"), ("kappa", "κ"), ("lambda", "λ"), ("mu", "μ"), ("nu", "ν"), ("xi", "ξ"),
+    ("omicron", "ο"), ("pi", "π"), ("varpi", "ϖ"), ("rho", "ρ"), ("varrho", "ϱ"), ("sigma", "σ"),
+    ("varsigma", "ς"), ("tau", "τ"), ("upsilon", "υ"), ("phi", "ϕ"), ("varphi", "φ"), ("chi", "χ"),
+    ("psi", "ψ"), ("omega", "ω"),
+    ("Gamma", "Γ"), ("Delta", "Δ"), ("Theta", "Θ"), ("Lambda", "Λ"), ("Xi", "Ξ"), ("Pi", "Π"),
+    ("Sigma", "Σ"), ("Upsilon", "Υ"), ("Phi", "Φ"), ("Psi", "Ψ"), ("Omega", "Ω"),
+];
+
+/// 渲染为 `<mi>` 的符号
+const IDENTIFIERS: &[(&str, &str)] = &[
+    ("infty", "∞"), ("emptyset", "∅"), ("varnothing", "∅"), ("hbar", "ℏ"), ("ell", "ℓ"),
+    ("aleph", "ℵ"), ("Re", "ℜ"), ("Im", "ℑ"), ("wp", "℘"), ("imath", "ı"), ("jmath", "ȷ"),
+];
+
+/// 渲染为 `<mo>` 的符号
+const OPERATORS: &[(&str, &str)] = &[
+    ("pm", "±"), ("mp", "∓"), ("times", "×"), ("div", "÷"), ("cdot", "⋅"), ("ast", "∗"),
+    ("star", "⋆"), ("circ", "∘"), ("bullet", "∙"), ("oplus", "⊕"), ("ominus", "⊖"),
+    ("otimes", "⊗"), ("odot", "⊙"), ("dagger", "†"), ("setminus", "∖"), ("wedge", "∧"),
+    ("land", "∧"), ("vee", "∨"), ("lor", "∨"), ("neg", "¬"), ("lnot", "¬"), ("cup", "∪"),
+    ("cap", "∩"), ("sqcup", "⊔"), ("sqcap", "⊓"),
+    ("leq", "≤"), ("le", "≤"), ("geq", "≥"), ("ge", "≥"), ("neq", "≠"), ("ne", "≠"),
+    ("leqslant", "⩽"), ("geqslant", "⩾"), ("ll", "≪"), ("gg", "≫"), ("approx", "≈"),
+    ("equiv", "≡"), ("sim", "∼"), ("simeq", "≃"), ("cong", "≅"), ("propto", "∝"),
+    ("prec", "≺"), ("succ", "≻"), ("preceq", "⪯"), ("succeq", "⪰"), ("doteq", "≐"),
+    ("in", "∈"), ("notin", "∉"), ("ni", "∋"), ("subset", "⊂"), ("subseteq", "⊆"),
+    ("supset", "⊃"), ("supseteq", "⊇"), ("subsetneq", "⊊"), ("supsetneq", "⊋"),
+    ("mid", "∣"), ("nmid", "∤"), ("parallel", "∥"), ("perp", "⊥"), ("models", "⊨"),
+    ("vdash", "⊢"), ("dashv", "⊣"),
+    ("to", "→"), ("rightarrow", "→"), ("leftarrow", "←"), ("gets", "←"),
+    ("leftrightarrow", "↔"), ("Rightarrow", "⇒"), ("Leftarrow", "⇐"), ("Leftrightarrow", "⇔"),
+    ("implies", "⟹"), ("impliedby", "⟸"), ("iff", "⟺"), ("mapsto", "↦"),
+    ("longrightarrow", "⟶"), ("longleftarrow", "⟵"), ("Longrightarrow", "⟹"),
+    ("Longleftarrow", "⟸"), ("longmapsto", "⟼"), ("uparrow", "↑"), ("downarrow", "↓"),
+    ("Uparrow", "⇑"), ("Downarrow", "⇓"), ("nearrow", "↗"), ("searrow", "↘"),
+    ("hookrightarrow", "↪"), ("rightleftharpoons", "⇌"),
+    ("forall", "∀"), ("exists", "∃"), ("nexists", "∄"), ("partial", "∂"), ("nabla", "∇"),
+    ("angle", "∠"), ("triangle", "△"), ("therefore", "∴"), ("because", "∵"), ("top", "⊤"),
+    ("bot", "⊥"), ("prime", "′"), ("degree", "°"), ("colon", ":"),
+    ("ldots", "…"), ("dots", "…"), ("dotsc", "…"), ("dotsb", "⋯"), ("cdots", "⋯"),
+    ("vdots", "⋮"), ("ddots", "⋱"),
+    ("langle", "⟨"), ("rangle", "⟩"), ("lfloor", "⌊"), ("rfloor", "⌋"), ("lceil", "⌈"),
+    ("rceil", "⌉"), ("vert", "|"), ("Vert", "‖"), ("lvert", "|"), ("rvert", "|"),
+    ("lVert", "‖"), ("rVert", "‖"), ("backslash", "\\"),
+    ("{", "{"), ("}", "}"), ("|", "‖"), ("%", "%"), ("$", "$"), ("#", "#"), ("&", "&"),
+    ("_", "_"),
+];
+
+/// 大型运算符：(命令, 符号, 是否在显示模式下上下限置于正上下方)
+const BIG_OPERATORS: &[(&str, &str, bool)] = &[
+    ("sum", "∑", true), ("prod", "∏", true), ("coprod", "∐", true), ("bigcup", "⋃", true),
+    ("bigcap", "⋂", true), ("bigvee", "⋁", true), ("bigwedge", "⋀", true),
+    ("bigoplus", "⨁", true), ("bigotimes", "⨂", true), ("bigodot", "⨀", true),
+    ("bigsqcup", "⨆", true), ("int", "∫", false), ("iint", "∬", false), ("iiint", "∭", false),
+    ("oint", "∮", false),
+];
+
+/// 函数名：(命令, 是否在显示模式下上下限置于正下方)
+const FUNCTIONS: &[(&str, bool)] = &[
+    ("sin", false), ("cos", false), ("tan", false), ("cot", false), ("sec", false), ("csc", false),
+    ("arcsin", false), ("arccos", false), ("arctan", false), ("sinh", false), ("cosh", false),
+    ("tanh", false), ("coth", false), ("log", false), ("ln", false), ("lg", false), ("exp", false),
+    ("dim", false), ("ker", false), ("deg", false), ("arg", false), ("hom", false),
+    ("lim", true), ("liminf", true), ("limsup", true), ("max", true), ("min", true), ("sup", true),
+    ("inf", true), ("det", true), ("gcd", true), ("Pr", true), ("argmax", true), ("argmin", true),
+];
+
+/// 水平间距（em）
+const SPACES: &[(&str, &str)] = &[
+    (",", "0.1667em"), ("thinspace", "0.1667em"), (":", "0.2222em"), (">", "0.2222em"),
+    ("medspace", "0.2222em"), (";", "0.2778em"), ("thickspace", "0.2778em"), ("!", "-0.1667em"),
+    ("negthinspace", "-0.1667em"), (" ", "0.25em"), ("enspace", "0.5em"), ("quad", "1em"),
+    ("qquad", "2em"),
+];
+
+/// 重音：(命令, 符号, 是否伸缩)
+const ACCENTS: &[(&str, &str, bool)] = &[
+    ("hat", "^", false), ("widehat", "^", true), ("bar", "¯", false), ("overline", "‾", true),
+    ("vec", "→", false), ("overrightarrow", "→", true), ("overleftarrow", "←", true),
+    ("tilde", "~", false), ("widetilde", "~", true), ("dot", "˙", false), ("ddot", "¨", false),
+    ("check", "ˇ", false), ("breve", "˘", false), ("acute", "´", false), ("grave", "`", false),
+];
+
+/// `\not` 组合后的否定符号
+const NEGATIONS: &[(&str, &str)] = &[
+    ("=", "≠"), ("∈", "∉"), ("⊂", "⊄"), ("⊃", "⊅"), ("⊆", "⊈"), ("⊇", "⊉"), ("≡", "≢"),
+    ("<", "≮"), (">", "≯"), ("≤", "≰"), ("≥", "≱"), ("∼", "≁"), ("≈", "≉"), ("∋", "∌"),
+    ("∣", "∤"), ("∥", "∦"),
+];
+
+/// `\big` 系列的固定尺寸
+const BIG_SIZES: &[(&str, &str)] = &[
+    ("big", "1.2em"), ("bigl", "1.2em"), ("bigr", "1.2em"), ("bigm", "1.2em"),
+    ("Big", "1.623em"), ("Bigl", "1.623em"), ("Bigr", "1.623em"), ("Bigm", "1.623em"),
+    ("bigg", "2.047em"), ("biggl", "2.047em"), ("biggr", "2.047em"), ("biggm", "2.047em"),
+    ("Bigg", "2.470em"), ("Biggl", "2.470em"), ("Biggr", "2.470em"), ("Biggm", "2.470em"),
+];
+
+fn lookup<'t>(table: &'t [(&str, &str)], name: &str) -> Option<&'t str> {
+    table.iter().find(|(n, _)| *n == name).map(|(_, s)| *s)
+}
+
+// ---- 字体 ----
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Font {
+    Roman,
+    Bold,
+    Italic,
+    BoldItalic,
+    Script,
+    Fraktur,
+    DoubleStruck,
+    SansSerif,
+    Monospace,
+}
+
+impl Font {
+    fn from_command(name: &str) -> Option<Font> {
+        Some(match name {
+            "mathrm" | "mathup" => Font::Roman,
+            "mathbf" => Font::Bold,
+            "mathit" => Font::Italic,
+            "boldsymbol" | "bm" | "mathbfit" => Font::BoldItalic,
+            "mathcal" | "mathscr" => Font::Script,
+            "mathfrak" => Font::Fraktur,
+            "mathbb" => Font::DoubleStruck,
+            "mathsf" => Font::SansSerif,
+            "mathtt" => Font::Monospace,
+            _ => return None,
+        })
+    }
+
+    /// 映射到 Unicode 数学字母数字符号（U+1D400 起）；Roman 与不支持的字符返回 None
+    fn styled(self, c: char) -> Option<char> {
+        let (upper, digits, exceptions): (u32, Option<u32>, &[(char, char)]) = match self {
+            Font::Roman => return None,
+            Font::Bold => (0x1D400, Some(0x1D7CE), &[]),
+            Font::Italic => (0x1D434, None, &[('h', 'ℎ')]),
+            Font::BoldItalic => (0x1D468, None, &[]),
+            Font::Script => (
+                0x1D49C,
+                None,
+                &[('B', 'ℬ'), ('E', 'ℰ'), ('F', 'ℱ'), ('H', 'ℋ'), ('I', 'ℐ'), ('L', 'ℒ'), ('M', 'ℳ'),
+                  ('R', 'ℛ'), ('e', 'ℯ'), ('g', 'ℊ'), ('o', 'ℴ')],
+            ),
+            Font::Fraktur => (0x1D504, None, &[('C', 'ℭ'), ('H', 'ℌ'), ('I', 'ℑ'), ('R', 'ℜ'), ('Z', 'ℨ')]),
+            Font::DoubleStruck => (
+                0x1D538,
+                Some(0x1D7D8),
+                &[('C', 'ℂ'), ('H', 'ℍ'), ('N', 'ℕ'), ('P', 'ℙ'), ('Q', 'ℚ'), ('R', 'ℝ'), ('Z', 'ℤ')],
+            ),
+            Font::SansSerif => (0x1D5A0, Some(0x1D7E2), &[]),
+            Font::Monospace => (0x1D670, Some(0x1D7F6), &[]),
+        };
+        if let Some((_, mapped)) = exceptions.iter().find(|(from, _)| *from == c) {
+            return Some(*mapped);
+        }
+        let code = match c {
+            'A'..='Z' => upper + (c as u32 - 'A' as u32),
+            'a'..='z' => upper + 26 + (c as u32 - 'a' as u32),
+            '0'..='9' => digits? + (c as u32 - '0' as u32),
+            _ => return None,
+        };
+        char::from_u32(code)
+    }
+}
+
+// ---- 解析器 ----
+
+/// 已生成的 MathML 片段
+struct Item {
+    xml: String,
+    /// 上下标置于正上下方（munder / mover）
+    limits: bool,
+    /// 函数名，其后需要插入函数应用符 U+2061
+    func: bool,
+}
+
+impl Item {
+    fn new(xml: String) -> Self {
+        Item { xml, limits: false, func: false }
+    }
+}
+
+/// 当前行在遇到哪些记号时结束
+#[derive(Debug, Clone, Copy, Default)]
+struct Ctx {
+    brace: bool,
+    bracket: bool,
+    table: bool,
+    left: bool,
+    env: bool,
+}
+
+/// 行结束的原因（结束记号已被消费）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stop {
+    Eof,
+    Brace,
+    Bracket,
+    Cell,
+    Row,
+    Right,
+    End,
+}
+
+/// 分组、参数与环境的最大嵌套层数（解析器递归下降，防止恶意或错误输入耗尽栈空间）
+const MAX_DEPTH: usize = 256;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    display: bool,
+    font: Option<Font>,
+    /// 当前嵌套层数
+    depth: usize,
+    /// 是否超过了嵌套上限（此后剩余输入被丢弃）
+    too_deep: bool,
+}
+
+impl Parser {
+    fn new(latex: &str, display: bool) -> Self {
+        Parser {
+            chars: latex.chars().collect(),
+            pos: 0,
+            display,
+            font: None,
+            depth: 0,
+            too_deep: false,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// 跳过空白与 `%` 注释
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += 1;
+            } else if c == '%' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// 读取 `\` 之后的命令名：连续字母，或单个非字母字符
+    fn read_command(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            if let Some(c) = self.peek() {
+                self.pos += 1;
+                return c.to_string();
+            }
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// 读取 `{…}` 中的原始文本（花括号配对）；没有花括号时读取单个字符
+    fn read_raw_group(&mut self) -> Option<String> {
+        self.skip_ws();
+        match self.peek()? {
+            '{' => {
+                self.pos += 1;
+                let start = self.pos;
+                let mut depth = 1;
+                while let Some(c) = self.peek() {
+                    match c {
+                        '\\' => self.pos += 1,
+                        '{' => depth += 1,
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                let text = self.chars[start..self.pos].iter().collect();
+                                self.pos += 1;
+                                return Some(text);
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+                None
+            }
+            c => {
+                self.pos += 1;
+                Some(c.to_string())
+            }
+        }
+    }
+
+    /// 进入一层嵌套；超过上限时丢弃剩余输入并返回 false
+    fn enter(&mut self) -> bool {
+        if self.depth >= MAX_DEPTH {
+            self.too_deep = true;
+            self.pos = self.chars.len();
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    fn parse_row(&mut self, ctx: Ctx) -> (Vec<Item>, Stop) {
+        if !self.enter() {
+            return (Vec::new(), Stop::Eof);
+        }
+        let result = self.parse_row_inner(ctx);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_row_inner(&mut self, ctx: Ctx) -> (Vec<Item>, Stop) {
+        let mut items: Vec<Item> = Vec::new();
+        loop {
+            self.skip_ws();
+            let Some(c) = self.peek() else {
+                return (items, Stop::Eof);
+            };
+            self.pos += 1;
+            match c {
+                '}' if ctx.brace => return (items, Stop::Brace),
+                '}' => items.push(error("多余的 }", "}")),
+                ']' if ctx.bracket => return (items, Stop::Bracket),
+                '&' if ctx.table => return (items, Stop::Cell),
+                '&' => {}
+                '{' => {
+                    let group = self.parse_group_rest();
+                    items.push(Item::new(group));
+                }
+                '^' | '_' => self.attach_scripts(&mut items, c),
+                '\'' => {
+                    let mut count = 1;
+                    while self.peek() == Some('\'') {
+                        self.pos += 1;
+                        count += 1;
+                    }
+                    let prime = match count {
+                        1 => "′",
+                        2 => "″",
+                        3 => "‴",
+                        _ => "⁗",
+                    };
+                    let base = items.pop().map(|i| i.xml).unwrap_or_else(|| "<mrow></mrow>".to_string());
+                    items.push(Item::new(format!("<msup>{}<mo>{}</mo></msup>", base, prime)));
+                }
+                '~' => items.push(Item::new("<mtext>&#xa0;</mtext>".to_string())),
+                '\\' => {
+                    let name = self.read_command();
+                    match name.as_str() {
+                        "\\" | "cr" | "newline" => {
+                            if ctx.table {
+                                // 可选的行距参数，如 \\[2pt]
+                                self.skip_optional();
+                                return (items, Stop::Row);
+                            }
+                            items.push(Item::new("<mspace linebreak=\"newline\"></mspace>".to_string()));
+                        }
+                        "right" if ctx.left => return (items, Stop::Right),
+                        "right" => {
+                            self.read_delimiter();
+                            items.push(error("缺少与 \\right 配对的 \\left", "\\right"));
+                        }
+                        "end" if ctx.env => return (items, Stop::End),
+                        "end" => {
+                            let name = self.read_raw_group().unwrap_or_default();
+                            items.push(error(&format!("多余的 \\end{{{}}}", name), "\\end"));
+                        }
+                        "middle" if ctx.left => {
+                            let delimiter = self.read_delimiter().unwrap_or_default();
+                            items.push(Item::new(format!(
+                                "<mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">{}</mo>",
+                                escape(&delimiter)
+                            )));
+                        }
+                        "limits" | "nolimits" => {
+                            if let Some(last) = items.last_mut() {
+                                last.limits = name == "limits";
+                            }
+                        }
+                        // 作用于当前组剩余部分的命令
+                        "displaystyle" | "textstyle" | "scriptstyle" => {
+                            let (rest, stop) = self.parse_row(ctx);
+                            let attrs = match name.as_str() {
+                                "displaystyle" => "displaystyle=\"true\" scriptlevel=\"0\"",
+                                "textstyle" => "displaystyle=\"false\" scriptlevel=\"0\"",
+                                _ => "displaystyle=\"false\" scriptlevel=\"1\"",
+                            };
+                            items.push(Item::new(format!("<mstyle {}>{}</mstyle>", attrs, join(rest))));
+                            return (items, stop);
+                        }
+                        "color" => {
+                            let color = self.read_raw_group().unwrap_or_default();
+                            let (rest, stop) = self.parse_row(ctx);
+                            items.push(Item::new(format!(
+                                "<mstyle mathcolor=\"{}\">{}</mstyle>",
+                                escape(color.trim()),
+                                join(rest)
+                            )));
+                            return (items, stop);
+                        }
+                        _ => {
+                            let item = self.command(&name);
+                            items.push(item);
+                        }
+                    }
+                }
+                _ => items.push(self.char_atom(c, true)),
+            }
+        }
+    }
+
+    /// 已消费 `{`，解析到配对的 `}`
+    fn parse_group_rest(&mut self) -> String {
+        let (items, stop) = self.parse_row(Ctx { brace: true, ..Ctx::default() });
+        let mut xml = wrap_row(items);
+        if stop != Stop::Brace {
+            xml.push_str(&error("缺少 }", "").xml);
+        }
+        xml
+    }
+
+    /// 读取一个参数：`{…}`、一条命令或单个字符
+    fn parse_arg(&mut self) -> String {
+        if !self.enter() {
+            return String::new();
+        }
+        let xml = self.parse_arg_inner();
+        self.depth -= 1;
+        xml
+    }
+
+    fn parse_arg_inner(&mut self) -> String {
+        self.skip_ws();
+        match self.peek() {
+            None => error("缺少参数", "").xml,
+            Some('{') => {
+                self.pos += 1;
+                self.parse_group_rest()
+            }
+            Some('\\') => {
+                self.pos += 1;
+                let name = self.read_command();
+                self.command(&name).xml
+            }
+            Some(c) => {
+                self.pos += 1;
+                self.char_atom(c, false).xml
+            }
+        }
+    }
+
+    /// 跳过可选参数 `[…]`
+    fn skip_optional(&mut self) {
+        let save = self.pos;
+        self.skip_ws();
+        if self.peek() != Some('[') {
+            self.pos = save;
+            return;
+        }
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == ']' {
+                return;
+            }
+        }
+    }
+
+    /// 为上一个元素附加上下标
+    fn attach_scripts(&mut self, items: &mut Vec<Item>, first: char) {
+        let base = items.pop().unwrap_or_else(|| Item::new("<mrow></mrow>".to_string()));
+        let mut sub = None;
+        let mut sup = None;
+        if first == '^' {
+            sup = Some(self.parse_arg());
+        } else {
+            sub = Some(self.parse_arg());
+        }
+        self.skip_ws();
+        match self.peek() {
+            Some('^') if sup.is_none() => {
+                self.pos += 1;
+                sup = Some(self.parse_arg());
+            }
+            Some('_') if sub.is_none() => {
+                self.pos += 1;
+                sub = Some(self.parse_arg());
+            }
+            _ => {}
+        }
+        let (under, over, both) = if base.limits {
+            ("munder", "mover", "munderover")
+        } else {
+            ("msub", "msup", "msubsup")
+        };
+        let xml = match (sub, sup) {
+            (Some(sub), Some(sup)) => format!("<{both}>{}{}{}</{both}>", base.xml, sub, sup),
+            (Some(sub), None) => format!("<{under}>{}{}</{under}>", base.xml, sub),
+            (None, Some(sup)) => format!("<{over}>{}{}</{over}>", base.xml, sup),
+            (None, None) => base.xml,
+        };
+        items.push(Item { xml, limits: false, func: base.func });
+    }
+
+    /// 普通字符：字母为标识符，数字为数值（行内连续数字合并），其余为运算符
+    fn char_atom(&mut self, c: char, in_row: bool) -> Item {
+        if c.is_ascii_digit() {
+            let mut number = c.to_string();
+            if in_row {
+                loop {
+                    match self.peek() {
+                        Some(d) if d.is_ascii_digit() => number.push(d),
+                        Some('.') if self.chars.get(self.pos + 1).is_some_and(|d| d.is_ascii_digit()) => number.push('.'),
+                        _ => break,
+                    }
+                    self.pos += 1;
+                }
+            }
+            let number = match self.font.filter(|f| *f != Font::Roman) {
+                Some(font) => number.chars().map(|d| font.styled(d).unwrap_or(d)).collect(),
+                None => number,
+            };
+            return Item::new(format!("<mn>{}</mn>", number));
+        }
+        if c.is_alphabetic() {
+            return match self.font {
+                Some(Font::Roman) => {
+                    let mut word = c.to_string();
+                    while in_row && self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                        word.push(self.chars[self.pos]);
+                        self.pos += 1;
+                    }
+                    let attr = if word.chars().count() == 1 { " mathvariant=\"normal\"" } else { "" };
+                    Item::new(format!("<mi{}>{}</mi>", attr, escape(&word)))
+                }
+                Some(font) => match font.styled(c) {
+                    Some(styled) => Item::new(format!("<mi>{}</mi>", styled)),
+                    None => Item::new(format!("<mi>{}</mi>", escape(&c.to_string()))),
+                },
+                None => Item::new(format!("<mi>{}</mi>", escape(&c.to_string()))),
+            };
+        }
+        let op = match c {
+            '-' => "−".to_string(),
+            '*' => "∗".to_string(),
+            _ => c.to_string(),
+        };
+        Item::new(operator(&op))
+    }
+
+    /// 读取 `\left` / `\right` / `\big` 之后的定界符；`.` 表示空
+    fn read_delimiter(&mut self) -> Option<String> {
+        self.skip_ws();
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\\' {
+            let name = self.read_command();
+            return lookup(OPERATORS, &name).map(str::to_string);
+        }
+        Some(if c == '.' { String::new() } else { c.to_string() })
+    }
+
+    fn command(&mut self, name: &str) -> Item {
+        if let Some(c) = lookup(GREEK, name) {
+            let upright = name.starts_with(|c: char| c.is_ascii_uppercase());
+            let attr = if upright { " mathvariant=\"normal\"" } else { "" };
+            return Item::new(format!("<mi{}>{}</mi>", attr, c));
+        }
+        if let Some(c) = lookup(IDENTIFIERS, name) {
+            return Item::new(format!("<mi>{}</mi>", c));
+        }
+        if let Some(op) = lookup(OPERATORS, name) {
+            return Item::new(operator(op));
+        }
+        if let Some((_, op, limits)) = BIG_OPERATORS.iter().find(|(n, _, _)| *n == name) {
+            return Item {
+                xml: format!("<mo largeop=\"true\">{}</mo>", op),
+                limits: *limits && self.display,
+                func: false,
+            };
+        }
+        if let Some((_, limits)) = FUNCTIONS.iter().find(|(n, _)| *n == name) {
+            let text = match name {
+                "liminf" => "lim inf",
+                "limsup" => "lim sup",
+                "argmax" => "arg max",
+                "argmin" => "arg min",
+                _ => name,
+            };
+            return Item {
+                xml: format!("<mi>{}</mi>", text),
+                limits: *limits && self.display,
+                func: true,
+            };
+        }
+        if let Some(width) = lookup(SPACES, name) {
+            return Item::new(format!("<mspace width=\"{}\"></mspace>", width));
+        }
+        if let Some((_, accent, stretchy)) = ACCENTS.iter().find(|(n, _, _)| *n == name) {
+            let base = self.parse_arg();
+            return Item::new(format!(
+                "<mover accent=\"true\">{}<mo stretchy=\"{}\">{}</mo></mover>",
+                base, stretchy, escape(accent)
+            ));
+        }
+        if let Some(size) = lookup(BIG_SIZES, name) {
+            let delimiter = self.read_delimiter().unwrap_or_default();
+            return Item::new(format!(
+                "<mo fence=\"true\" stretchy=\"true\" symmetric=\"true\" minsize=\"{0}\" maxsize=\"{0}\">{1}</mo>",
+                size,
+                escape(&delimiter)
+            ));
+        }
+        if let Some(font) = Font::from_command(name) {
+            let saved = self.font.replace(font);
+            let arg = self.parse_arg();
+            self.font = saved;
+            return Item::new(arg);
+        }
+
+        match name {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let num = self.parse_arg();
+                let den = self.parse_arg();
+                let frac = format!("<mfrac>{}{}</mfrac>", num, den);
+                Item::new(match name {
+                    "dfrac" | "cfrac" => format!("<mstyle displaystyle=\"true\" scriptlevel=\"0\">{}</mstyle>", frac),
+                    "tfrac" => format!("<mstyle displaystyle=\"false\" scriptlevel=\"0\">{}</mstyle>", frac),
+                    _ => frac,
+                })
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let n = self.parse_arg();
+                let k = self.parse_arg();
+                Item::new(format!(
+                    "<mrow><mo fence=\"true\">(</mo><mfrac linethickness=\"0\">{}{}</mfrac><mo fence=\"true\">)</mo></mrow>",
+                    n, k
+                ))
+            }
+            "sqrt" => {
+                self.skip_ws();
+                let index = if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let (items, _) = self.parse_row(Ctx { bracket: true, ..Ctx::default() });
+                    Some(wrap_row(items))
+                } else {
+                    None
+                };
+                let radicand = self.parse_arg();
+                Item::new(match index {
+                    Some(index) => format!("<mroot>{}{}</mroot>", radicand, index),
+                    None => format!("<msqrt>{}</msqrt>", radicand),
+                })
+            }
+            "underline" => {
+                let base = self.parse_arg();
+                Item::new(format!("<munder accentunder=\"true\">{}<mo stretchy=\"true\">_</mo></munder>", base))
+            }
+            "overbrace" | "underbrace" => {
+                let base = self.parse_arg();
+                let (tag, brace) = if name == "overbrace" { ("mover", "⏞") } else { ("munder", "⏟") };
+                Item {
+                    xml: format!("<mrow><{tag}>{}<mo stretchy=\"true\">{}</mo></{tag}></mrow>", base, brace),
+                    limits: true,
+                    func: false,
+                }
+            }
+            "overset" | "stackrel" | "underset" => {
+                let script = self.parse_arg();
+                let base = self.parse_arg();
+                let tag = if name == "underset" { "munder" } else { "mover" };
+                Item::new(format!("<{tag}>{}{}</{tag}>", base, script))
+            }
+            "text" | "textrm" | "textnormal" | "textup" | "mbox" | "hbox" | "textit" | "textbf" | "textsf"
+            | "texttt" => {
+                let text = self.read_raw_group().unwrap_or_default();
+                let variant = match name {
+                    "textit" => " mathvariant=\"italic\"",
+                    "textbf" => " mathvariant=\"bold\"",
+                    "textsf" => " mathvariant=\"sans-serif\"",
+                    "texttt" => " mathvariant=\"monospace\"",
+                    _ => "",
+                };
+                Item::new(format!("<mtext{}>{}</mtext>", variant, escape(&text_unescape(&text))))
+            }
+            "operatorname" => {
+                let limits = self.peek() == Some('*');
+                if limits {
+                    self.pos += 1;
+                }
+                let text = self.read_raw_group().unwrap_or_default();
+                Item {
+                    xml: format!("<mi>{}</mi>", escape(text.trim())),
+                    limits: limits && self.display,
+                    func: true,
+                }
+            }
+            "mathop" => {
+                let arg = self.parse_arg();
+                Item {
+                    xml: arg,
+                    limits: self.display,
+                    func: false,
+                }
+            }
+            "not" => {
+                self.skip_ws();
+                let symbol = match self.peek() {
+                    Some('\\') => {
+                        self.pos += 1;
+                        let name = self.read_command();
+                        lookup(OPERATORS, &name).map(str::to_string)
+                    }
+                    Some(c) => {
+                        self.pos += 1;
+                        Some(c.to_string())
+                    }
+                    None => None,
+                };
+                match symbol {
+                    Some(symbol) => {
+                        let negated = lookup(NEGATIONS, &symbol)
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("{}\u{338}", symbol));
+                        Item::new(operator(&negated))
+                    }
+                    None => error("\\not 后缺少符号", "\\not"),
+                }
+            }
+            "left" => self.left_right(),
+            "begin" => self.environment(),
+            "pmod" => {
+                let arg = self.parse_arg();
+                Item::new(format!(
+                    "<mrow><mspace width=\"1em\"></mspace><mo stretchy=\"false\">(</mo><mi>mod</mi><mspace width=\"0.3333em\"></mspace>{}<mo stretchy=\"false\">)</mo></mrow>",
+                    arg
+                ))
+            }
+            "bmod" | "mod" => Item::new("<mo lspace=\"0.2222em\" rspace=\"0.2222em\">mod</mo>".to_string()),
+            "boxed" => {
+                let arg = self.parse_arg();
+                Item::new(format!("<menclose notation=\"box\">{}</menclose>", arg))
+            }
+            "textcolor" => {
+                let color = self.read_raw_group().unwrap_or_default();
+                let arg = self.parse_arg();
+                Item::new(format!("<mstyle mathcolor=\"{}\">{}</mstyle>", escape(color.trim()), arg))
+            }
+            "hline" | "hdashline" | "nonumber" | "notag" | "label" => {
+                if name == "label" {
+                    self.read_raw_group();
+                }
+                Item::new(String::new())
+            }
+            _ => error(&format!("不支持的命令 \\{}", name), &format!("\\{}", name)),
+        }
+    }
+
+    /// 已消费 `\left`：解析到配对的 `\right`
+    fn left_right(&mut self) -> Item {
+        let open = self.read_delimiter().unwrap_or_default();
+        let (items, stop) = self.parse_row(Ctx { left: true, ..Ctx::default() });
+        let close = if stop == Stop::Right {
+            self.read_delimiter().unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let fence = |d: &str| format!("<mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">{}</mo>", escape(d));
+        let mut xml = format!("<mrow>{}{}{}</mrow>", fence(&open), join(items), fence(&close));
+        if stop != Stop::Right {
+            xml.push_str(&error("缺少 \\right", "").xml);
+        }
+        Item::new(xml)
+    }
+
+    /// 已消费 `\begin`：解析环境直到 `\end{…}`
+    fn environment(&mut self) -> Item {
+        let name = self.read_raw_group().unwrap_or_default();
+        let name = name.trim();
+        // (左定界符, 右定界符, 各列对齐方式)
+        let (open, close, align): (&str, &str, Vec<&str>) = match name {
+            "matrix" | "smallmatrix" => ("", "", vec!["center"]),
+            "pmatrix" => ("(", ")", vec!["center"]),
+            "bmatrix" => ("[", "]", vec!["center"]),
+            "Bmatrix" => ("{", "}", vec!["center"]),
+            "vmatrix" => ("|", "|", vec!["center"]),
+            "Vmatrix" => ("‖", "‖", vec!["center"]),
+            "cases" => ("{", "", vec!["left"]),
+            "rcases" => ("", "}", vec!["left"]),
+            "aligned" | "align" | "align*" | "split" | "alignedat" | "alignat" | "alignat*" | "eqnarray"
+            | "eqnarray*" => {
+                if name.starts_with("alignat") || name == "alignedat" {
+                    self.read_raw_group();
+                }
+                ("", "", vec!["right", "left"])
+            }
+            "gathered" | "gather" | "gather*" | "equation" | "equation*" => ("", "", vec!["center"]),
+            "array" => {
+                let spec = self.read_raw_group().unwrap_or_default();
+                let align = spec
+                    .chars()
+                    .filter_map(|c| match c {
+                        'l' => Some("left"),
+                        'r' => Some("right"),
+                        'c' => Some("center"),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                ("", "", if align.is_empty() { vec!["center"] } else { align })
+            }
+            _ => ("", "", vec!["center"]),
+        };
+
+        let mut rows: Vec<Vec<String>> = vec![Vec::new()];
+        let mut closed = false;
+        loop {
+            let (items, stop) = self.parse_row(Ctx { table: true, env: true, ..Ctx::default() });
+            let row = rows.last_mut().expect("至少有一行");
+            row.push(join(items));
+            match stop {
+                Stop::Cell => {}
+                Stop::Row => rows.push(Vec::new()),
+                Stop::End => {
+                    closed = true;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        // 末尾 `\\` 留下的空行
+        if rows.len() > 1 && rows.last().is_some_and(|r| r.len() == 1 && r[0].is_empty()) {
+            rows.pop();
+        }
+
+        let paired = name.starts_with("align") || name == "split" || name.starts_with("eqnarray");
+        let cell_align = |i: usize| {
+            if paired {
+                align[i % 2]
+            } else {
+                align.get(i).or(align.last()).copied().unwrap_or("center")
+            }
+        };
+        let mut table = String::new();
+        for row in rows {
+            table.push_str("<mtr>");
+            for (i, cell) in row.into_iter().enumerate() {
+                let style = match cell_align(i) {
+                    "center" => String::new(),
+                    a => format!(" style=\"text-align:{}\"", a),
+                };
+                // 对齐列的右半部分以关系符开头（`&= b`），补一个空元素使其按中缀运算符留出间距
+                let lead = if paired && i % 2 == 1 { "<mi></mi>" } else { "" };
+                table.push_str(&format!("<mtd{}>{}{}</mtd>", style, lead, cell));
+            }
+            table.push_str("</mtr>");
+        }
+        let small = name == "smallmatrix";
+        let display = if small { "false" } else { "true" };
+        let mut xml = format!("<mtable displaystyle=\"{}\">{}</mtable>", display, table);
+        if small {
+            xml = format!("<mstyle scriptlevel=\"1\">{}</mstyle>", xml);
+        }
+        if !open.is_empty() || !close.is_empty() {
+            let fence = |d: &str| format!("<mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">{}</mo>", escape(d));
+            xml = format!("<mrow>{}{}{}</mrow>", fence(open), xml, fence(close));
+        }
+
+        if !closed {
+            xml.push_str(&error(&format!("缺少 \\end{{{}}}", name), "").xml);
+        } else {
+            let end = self.read_raw_group().unwrap_or_default();
+            if end.trim() != name {
+                xml.push_str(&error(&format!("\\begin{{{}}} 与 \\end{{{}}} 不匹配", name, end.trim()), "").xml);
+            }
+        }
+        const KNOWN: &[&str] = &[
+            "matrix", "smallmatrix", "pmatrix", "bmatrix", "Bmatrix", "vmatrix", "Vmatrix", "cases", "rcases",
+            "aligned", "align", "align*", "split", "alignedat", "alignat", "alignat*", "eqnarray", "eqnarray*",
+            "gathered", "gather", "gather*", "equation", "equation*", "array",
+        ];
+        if !KNOWN.contains(&name) {
+            xml.push_str(&error(&format!("不支持的环境 {}", name), "").xml);
+        }
+        Item::new(xml)
+    }
+}
+
+// ---- 输出辅助 ----
+
+/// 运算符；括号类不随内容伸缩（只有 `\left…\right` 才伸缩）
+fn operator(op: &str) -> String {
+    let stretchy = matches!(op, "(" | ")" | "[" | "]" | "{" | "}" | "|" | "‖" | "⟨" | "⟩" | "⌊" | "⌋" | "⌈" | "⌉" | "/");
+    if stretchy {
+        format!("<mo stretchy=\"false\">{}</mo>", escape(op))
+    } else {
+        format!("<mo>{}</mo>", escape(op))
+    }
+}
+
+fn error(message: &str, source: &str) -> Item {
+    Item::new(format!(
+        "<merror data-error=\"{}\"><mtext>{}</mtext></merror>",
+        escape(message),
+        escape(source)
+    ))
+}
+
+fn join(items: Vec<Item>) -> String {
+    let mut xml = String::new();
+    for item in items {
+        xml.push_str(&item.xml);
+        if item.func {
+            xml.push_str("<mo>&#x2061;</mo>");
+        }
+    }
+    xml
+}
+
+/// 多个元素包在 `<mrow>` 中，单个元素原样输出
+fn wrap_row(items: Vec<Item>) -> String {
+    if items.len() == 1 && !items[0].func {
+        return items.into_iter().next().map(|i| i.xml).unwrap_or_default();
+    }
+    format!("<mrow>{}</mrow>", join(items))
+}
+
+/// `\text{…}` 中的转义字符
+fn text_unescape(text: &str) -> String {
+    text.replace("\\{", "{")
+        .replace("\\}", "}")
+        .replace("\\%", "%")
+        .replace("\\$", "$")
+        .replace("\\&", "&")
+        .replace("\\_", "_")
+        .replace("\\#", "#")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
@@ -7,7 +7,7 @@ pub mod api;
 
 use crate::config::Config;
 use crate::error::Result;
-use crate::plugins::PluginDescriptor;
+use crate::plugins::{ApiContext, PluginDescriptor};
 use tera::Context;
 
 /// 评论模板（HTML + CSS + JS）
@@ -80,7 +80,7 @@ pub fn comments_on_post_render(config: &Config, context: &mut Context) -> Result
 
 // ---- API 路由工厂 ----
 
-pub fn comments_api_routes(config: &Config) -> Option<(&'static str, axum::Router)> {
+pub fn comments_api_routes(config: &Config, _ctx: &ApiContext) -> Option<(&'static str, axum::Router)> {
     let comments = config.data.get("comments")?;
     let enabled = comments.get("enabled")?.as_bool()?;
     if !enabled {
@@ -5,12 +5,36 @@
 //! 即可被主程序自动发现，无需手动注册。
 
 pub mod comments;
+pub mod paywall;
+pub mod search;
 
 use crate::config::Config;
 use crate::error::Result;
+use crate::generator::MemFs;
 use axum::Router;
+use std::path::{Path, PathBuf};
 use tera::Context;
 
+/// API 路由工厂的运行环境
+#[derive(Debug, Clone, Default)]
+pub struct ApiContext {
+    /// 构建输出目录
+    pub output_dir: PathBuf,
+    /// 项目根目录（md_dir 的上级目录），相对路径的密钥文件等按它解析
+    pub root: PathBuf,
+    /// 内存文件系统（`serve --in-memory`）：构建输出不落盘，需读取输出的插件从这里读取
+    pub mem_fs: Option<MemFs>,
+}
+
+impl ApiContext {
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(output_dir: P, root: Q) -> Self {
+        ApiContext { output_dir: output_dir.as_ref().to_path_buf(), root: root.as_ref().to_path_buf(), mem_fs: None }
+    }
+}
+
+/// API 路由工厂：传入站点配置与运行环境，返回 (路径前缀, Router)；返回 None 时不挂载
+pub type ApiRouteFactory = fn(&Config, &ApiContext) -> Option<(&'static str, Router)>;
+
 /// 插件描述符（静态，linkme 兼容）
 pub struct PluginDescriptor {
     /// 插件名称
@@ -19,21 +43,25 @@ pub struct PluginDescriptor {
     /// 文章渲染钩子：在模板渲染之前修改上下文
     pub on_post_render: Option<fn(&Config, &mut Context) -> Result<()>>,
 
-    /// API 路由工厂：返回 (路径前缀, Router)
-    pub api_routes: Option<fn(&Config) -> Option<(&'static str, Router)>>,
+    /// API 路由工厂：传入站点配置与运行环境（构建输出目录、项目根），返回 (路径前缀, Router)
+    pub api_routes: Option<ApiRouteFactory>,
 }
 
 /// 全局插件注册表（编译时自动收集）
 #[linkme::distributed_slice]
 pub static PLUGINS: [PluginDescriptor];
 
+/// 已在下方显式调用的内置插件，遍历注册表时跳过以免重复执行
+const EXPLICIT_PLUGINS: [&str; 3] = ["Comments", "Paywall", "Search"];
+
 /// 遍历所有插件，执行 on_post_render 钩子
 pub fn run_post_render_hooks(config: &Config, context: &mut Context) -> Result<()> {
     // 显式调用 Comments 插件的钩子，防止 linkme 分布式切片在特定平台（如 macOS）上被 linker 优化剥离
     comments::comments_on_post_render(config, context)?;
+    paywall::paywall_on_post_render(config, context)?;
 
     for plugin in PLUGINS.iter() {
-        if plugin.name == "Comments" {
+        if EXPLICIT_PLUGINS.contains(&plugin.name) {
             continue;
         }
         if let Some(hook) = plugin.on_post_render {
@@ -44,21 +72,29 @@ pub fn run_post_render_hooks(config: &Config, context: &mut Context) -> Result<(
 }
 
 /// 遍历所有插件，收集 API 路由并挂载到 Router
-pub fn collect_api_routes(config: &Config) -> Router {
+pub fn collect_api_routes(config: &Config, ctx: &ApiContext) -> Router {
     let mut router = Router::new();
 
     // 显式挂载 Comments 插件的 API 路由，防止 linkme 链接丢失
-    if let Some((prefix, sub_router)) = comments::comments_api_routes(config) {
+    if let Some((prefix, sub_router)) = comments::comments_api_routes(config, ctx) {
         println!("插件 [Comments] API 已挂载: {}/*", prefix);
         router = router.nest(prefix, sub_router);
     }
+    if let Some((prefix, sub_router)) = paywall::paywall_api_routes(config, ctx) {
+        println!("插件 [Paywall] API 已挂载: {}/*", prefix);
+        router = router.nest(prefix, sub_router);
+    }
+    if let Some((prefix, sub_router)) = search::search_api_routes(config, ctx) {
+        println!("插件 [Search] API 已挂载: {}", prefix);
+        router = router.nest(prefix, sub_router);
+    }
 
     for plugin in PLUGINS.iter() {
-        if plugin.name == "Comments" {
+        if EXPLICIT_PLUGINS.contains(&plugin.name) {
             continue;
         }
         if let Some(factory) = plugin.api_routes {
-            if let Some((prefix, sub_router)) = factory(config) {
+            if let Some((prefix, sub_router)) = factory(config, ctx) {
                 println!("插件 [{}] API 已挂载: {}/*", plugin.name, prefix);
                 router = router.nest(prefix, sub_router);
             }
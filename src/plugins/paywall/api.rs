@@ -0,0 +1,174 @@
+//! 付费专栏 API 路由
+//!
+//! 提供本地换钥接口（校验购买凭证后返回内容密钥）；`[paywall] dev_tokens = true` 时另挂载开发用凭证签发接口
+
+use crate::config::Config;
+use crate::crypto::{self, PurchaseClaims};
+use axum::{Json, Router, http::StatusCode, response::IntoResponse, routing::post};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 开发用凭证的有效期（秒）
+const DEV_TOKEN_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// 付费专栏 API 所需的配置
+#[derive(Clone)]
+pub struct PaywallConfig {
+    pub jwt_secret: String,
+    /// 站点配置（每次请求重新读取密钥，以便轮换后立即生效）
+    pub site: Config,
+    /// 项目根目录，密钥文件按它定位
+    pub root: PathBuf,
+    /// 是否挂载 `/token`：任何人都可借此签发凭证换取密钥，仅供本地调试
+    pub dev_tokens: bool,
+}
+
+/// 创建付费专栏 API 路由
+pub fn api_routes(config: PaywallConfig) -> Router {
+    let config = Arc::new(config);
+
+    let router = Router::new().route(
+        "/key",
+        post({
+            let cfg = Arc::clone(&config);
+            move |body| exchange_key(body, cfg)
+        }),
+    );
+    if !config.dev_tokens {
+        return router;
+    }
+    router.route(
+        "/token",
+        post({
+            let cfg = Arc::clone(&config);
+            move |body| issue_token(body, cfg)
+        }),
+    )
+}
+
+// ---- 换钥 ----
+
+#[derive(Deserialize)]
+pub struct ExchangeKeyBody {
+    pub token: String,
+}
+
+#[derive(Serialize)]
+pub struct ExchangeKeyResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+}
+
+impl ExchangeKeyResponse {
+    fn fail(message: String) -> Self {
+        ExchangeKeyResponse {
+            success: false,
+            message,
+            doc_id: None,
+            key: None,
+            key_id: None,
+        }
+    }
+}
+
+async fn exchange_key(
+    Json(body): Json<ExchangeKeyBody>,
+    config: Arc<PaywallConfig>,
+) -> impl IntoResponse {
+    let claims = match crypto::verify_purchase_token(&config.jwt_secret, &body.token) {
+        Ok(c) => c,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(ExchangeKeyResponse::fail(e.to_string())),
+            );
+        }
+    };
+
+    match crypto::resolve_content_key(&config.site, &config.root, &claims.doc_id) {
+        Ok(Some(key)) => (
+            StatusCode::OK,
+            Json(ExchangeKeyResponse {
+                success: true,
+                message: "ok".to_string(),
+                key: Some(crypto::encode_key(&key)),
+                key_id: Some(crypto::key_id(&key)),
+                doc_id: Some(claims.doc_id),
+            }),
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ExchangeKeyResponse::fail(format!(
+                "未找到 doc_id `{}` 的内容密钥",
+                claims.doc_id
+            ))),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ExchangeKeyResponse::fail(e.to_string())),
+        ),
+    }
+}
+
+// ---- 开发用凭证签发（替代线上 PayPal 验单后的签发步骤） ----
+
+#[derive(Deserialize)]
+pub struct IssueTokenBody {
+    pub email: String,
+    pub doc_id: String,
+}
+
+#[derive(Serialize)]
+pub struct IssueTokenResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+async fn issue_token(
+    Json(body): Json<IssueTokenBody>,
+    config: Arc<PaywallConfig>,
+) -> impl IntoResponse {
+    if body.email.trim().is_empty() || body.doc_id.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(IssueTokenResponse {
+                success: false,
+                message: "email 与 doc_id 均不能为空".to_string(),
+                token: None,
+            }),
+        );
+    }
+
+    let claims = PurchaseClaims {
+        email: body.email.trim().to_string(),
+        doc_id: body.doc_id.trim().to_string(),
+        exp: Some(chrono::Utc::now().timestamp() + DEV_TOKEN_TTL_SECS),
+    };
+    match crypto::sign_purchase_token(&config.jwt_secret, &claims) {
+        Ok(token) => (
+            StatusCode::OK,
+            Json(IssueTokenResponse {
+                success: true,
+                message: "ok".to_string(),
+                token: Some(token),
+            }),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(IssueTokenResponse {
+                success: false,
+                message: e.to_string(),
+                token: None,
+            }),
+        ),
+    }
+}
@@ -0,0 +1,81 @@
+//! 付费专栏插件入口
+//!
+//! 为加密文章注入前端解锁脚本，并在本地开发服务器上提供换钥接口，
+//! 无需 Serverless 后端即可在 localhost 跑通完整解锁流程。
+//! 通过 linkme 分布式切片自动注册到全局插件列表
+
+pub mod api;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::plugins::{ApiContext, PluginDescriptor};
+use tera::Context;
+
+/// 解锁模板（HTML + CSS + JS）
+const PAYWALL_TEMPLATE: &str = include_str!("paywall.html");
+
+/// 换钥接口默认前缀
+const DEFAULT_API_BASE: &str = "/api/paywall";
+
+// ---- 自动注册 ----
+
+#[linkme::distributed_slice(crate::plugins::PLUGINS)]
+static PAYWALL_PLUGIN: PluginDescriptor = PluginDescriptor {
+    name: "Paywall",
+    on_post_render: Some(paywall_on_post_render),
+    api_routes: Some(paywall_api_routes),
+};
+
+// ---- on_post_render 钩子 ----
+
+pub fn paywall_on_post_render(config: &Config, context: &mut Context) -> Result<()> {
+    // 仅对构建期已加密的文章注入解锁脚本
+    let encrypted = context
+        .get("page")
+        .and_then(|page| page.get("encrypted"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !encrypted {
+        return Ok(());
+    }
+
+    // 线上可指向 Serverless 换钥服务，本地默认走开发服务器
+    let api_base = config
+        .data
+        .get("paywall")
+        .and_then(|v| v.get("api_base"))
+        .and_then(|v| v.as_str())
+        .unwrap_or(DEFAULT_API_BASE);
+
+    let script = PAYWALL_TEMPLATE.replace("{{API_BASE}}", api_base.trim_end_matches('/'));
+    context.insert("paywall_script", &script);
+
+    Ok(())
+}
+
+// ---- API 路由工厂 ----
+
+pub fn paywall_api_routes(config: &Config, ctx: &ApiContext) -> Option<(&'static str, axum::Router)> {
+    // 未配置签名密钥时不挂载，避免以空密钥校验凭证
+    let jwt_secret = crate::crypto::jwt_secret(config)?;
+
+    // 开发用凭证签发接口默认关闭：开启后无需付款即可换取密钥
+    let dev_tokens = config
+        .data
+        .get("paywall")
+        .and_then(|v| v.get("dev_tokens"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if dev_tokens {
+        eprintln!("警告: 已开启 [paywall] dev_tokens，{}/token 可为任意 doc_id 签发凭证，切勿用于线上", DEFAULT_API_BASE);
+    }
+
+    let cfg = api::PaywallConfig {
+        jwt_secret,
+        site: config.clone(),
+        root: ctx.root.clone(),
+        dev_tokens,
+    };
+
+    Some((DEFAULT_API_BASE, api::api_routes(cfg)))
+}
@@ -0,0 +1,176 @@
+//! 站内搜索 API 路由
+//!
+//! `GET /api/search?q=&tag=&category=&page=&per_page=`：BM25 排序，返回高亮摘要。
+//! 索引在首次请求时载入内存，重新构建（清单文件更新）后自动重新载入。
+//! `serve --in-memory` 时索引从内存文件系统读取。
+
+use crate::error::Error;
+use crate::generator::MemFs;
+use crate::manifest::MANIFEST_FILE;
+use crate::search::{INDEX_DIR, SearchIndex, SearchQuery, SearchResults};
+use axum::{Json, Router, extract::Query, http::StatusCode, response::IntoResponse, routing::get};
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// 每页默认条数
+const DEFAULT_PER_PAGE: usize = 10;
+/// 每页最多条数
+const MAX_PER_PAGE: usize = 50;
+
+/// 搜索 API 所需的配置
+#[derive(Clone)]
+pub struct SearchApiConfig {
+    /// 构建输出中的索引目录（`public/search`）
+    pub index_dir: PathBuf,
+    /// 内存文件系统；设置时从中读取 `/search/*` 而非 `index_dir`
+    pub mem_fs: Option<MemFs>,
+}
+
+/// 判断索引是否需要重新载入的标记
+#[derive(PartialEq)]
+enum Stamp {
+    /// 磁盘上索引清单文件的修改时间
+    Modified(Option<SystemTime>),
+    /// 内存中构建清单的内容哈希（内存文件没有修改时间，每次构建都会重写构建清单）
+    Hash(Option<u64>),
+}
+
+/// 已载入的索引及其载入时的标记
+struct LoadedIndex {
+    stamp: Stamp,
+    index: Arc<SearchIndex>,
+}
+
+struct SearchState {
+    config: SearchApiConfig,
+    loaded: RwLock<Option<LoadedIndex>>,
+}
+
+impl SearchState {
+    /// 取得内存中的索引；清单文件变化时重新载入
+    fn index(&self) -> crate::error::Result<Arc<SearchIndex>> {
+        let stamp = self.stamp();
+        if let Ok(guard) = self.loaded.read() {
+            if let Some(loaded) = guard.as_ref().filter(|l| l.stamp == stamp) {
+                return Ok(Arc::clone(&loaded.index));
+            }
+        }
+
+        let index = Arc::new(self.load()?);
+        if let Ok(mut guard) = self.loaded.write() {
+            *guard = Some(LoadedIndex { stamp, index: Arc::clone(&index) });
+        }
+        Ok(index)
+    }
+
+    fn stamp(&self) -> Stamp {
+        match &self.config.mem_fs {
+            Some(mem_fs) => Stamp::Hash(mem_fs.read().unwrap().get(&format!("/{}", MANIFEST_FILE)).map(|content| {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                content.hash(&mut hasher);
+                hasher.finish()
+            })),
+            None => Stamp::Modified(
+                std::fs::metadata(self.config.index_dir.join("manifest.json"))
+                    .and_then(|m| m.modified())
+                    .ok(),
+            ),
+        }
+    }
+
+    fn load(&self) -> crate::error::Result<SearchIndex> {
+        let Some(mem_fs) = &self.config.mem_fs else {
+            return SearchIndex::load(&self.config.index_dir);
+        };
+        let files = mem_fs.read().unwrap();
+        SearchIndex::load_with(|name| {
+            let key = format!("/{}/{}", INDEX_DIR, name);
+            files
+                .get(&key)
+                .map(|content| String::from_utf8_lossy(content).into_owned())
+                .ok_or_else(|| Error::Other(format!("无法读取搜索索引 {}: 内存中不存在", key)))
+        })
+    }
+}
+
+/// 创建搜索 API 路由
+pub fn api_routes(config: SearchApiConfig) -> Router {
+    let state = Arc::new(SearchState {
+        config,
+        loaded: RwLock::new(None),
+    });
+
+    Router::new().route(
+        "/",
+        get({
+            let state = Arc::clone(&state);
+            move |query| search(query, state)
+        }),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    #[serde(default)]
+    pub q: String,
+    pub tag: Option<String>,
+    pub category: Option<String>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub results: Option<SearchResults>,
+}
+
+impl SearchResponse {
+    fn fail(message: String) -> Self {
+        SearchResponse {
+            success: false,
+            message,
+            results: None,
+        }
+    }
+}
+
+async fn search(Query(params): Query<SearchParams>, state: Arc<SearchState>) -> impl IntoResponse {
+    if params.q.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(SearchResponse::fail("缺少查询参数 q".to_string())),
+        );
+    }
+
+    let index = match state.index() {
+        Ok(index) => index,
+        Err(e) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(SearchResponse::fail(format!("搜索索引不可用，请先构建站点: {}", e))),
+            );
+        }
+    };
+
+    let query = SearchQuery {
+        q: params.q.trim().to_string(),
+        tag: params.tag,
+        category: params.category,
+        page: params.page.unwrap_or(1),
+        per_page: params.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+    };
+    (
+        StatusCode::OK,
+        Json(SearchResponse {
+            success: true,
+            message: "ok".to_string(),
+            results: Some(index.search(&query)),
+        }),
+    )
+}
@@ -0,0 +1,45 @@
+//! 站内搜索插件入口
+//!
+//! 将构建生成的 `search/` 索引读入内存，在开发/生产服务器上提供 `/api/search`，
+//! 供 Pushpen 桌面端与本地预览检索内容，无需把索引下发到浏览器。
+//! 通过 linkme 分布式切片自动注册到全局插件列表
+
+pub mod api;
+
+use crate::config::Config;
+use crate::plugins::{ApiContext, PluginDescriptor};
+use crate::search::INDEX_DIR;
+
+/// 搜索接口路径
+const DEFAULT_API_BASE: &str = "/api/search";
+
+// ---- 自动注册 ----
+
+#[linkme::distributed_slice(crate::plugins::PLUGINS)]
+static SEARCH_PLUGIN: PluginDescriptor = PluginDescriptor {
+    name: "Search",
+    on_post_render: None,
+    api_routes: Some(search_api_routes),
+};
+
+// ---- API 路由工厂 ----
+
+pub fn search_api_routes(config: &Config, ctx: &ApiContext) -> Option<(&'static str, axum::Router)> {
+    // `[search] api = false` 时不挂载
+    let enabled = config
+        .data
+        .get("search")
+        .and_then(|v| v.get("api"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if !enabled {
+        return None;
+    }
+
+    let cfg = api::SearchApiConfig {
+        index_dir: ctx.output_dir.join(INDEX_DIR),
+        mem_fs: ctx.mem_fs.clone(),
+    };
+
+    Some((DEFAULT_API_BASE, api::api_routes(cfg)))
+}
@@ -2,30 +2,84 @@
 //!
 //! 负责解析 Markdown 文件，提取元数据和内容
 
+use crate::cache::PostCache;
+use crate::callout;
+use crate::config::Config;
 use crate::error::{Error, Result};
-use comrak::{Options, markdown_to_html};
+use crate::excerpt;
+use crate::reading;
+use crate::front_matter::{self, FrontMatter, Layout};
+use crate::highlight::{self, HighlightOptions};
+use crate::markdown::MarkdownOptions;
+use crate::math;
+use crate::report::{describe_error, BuildIssue, IssueKind};
+use crate::toc;
+use comrak::options::Plugins;
+use comrak::{format_html_with_plugins, parse_document, Arena};
 use regex::Regex;
+use rayon::prelude::*;
 use serde_json::Value;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 use walkdir::WalkDir;
 
+/// 专栏 README 的 front matter 标题
+static RE_FRONT_MATTER_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^title:\s*['"]?([^'"\n]+)['"]?"#).unwrap());
+/// 代码块尾部的多余空行
+static RE_CODE_TRAILING_NEWLINES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)(<code[^>]*>)([\s\S]*?)(\n{2,})</code>").unwrap());
+
 /// 文章结构
 #[derive(Debug, Clone)]
 pub struct Post {
-    /// 文章元数据和内容
+    /// 文章元数据和内容（模板上下文中的 `page`）
     pub data: Value,
+    /// 强类型的 front matter
+    pub front_matter: FrontMatter,
 }
 
 impl Post {
     /// 从 JSON 值创建文章
     pub fn from_value(data: Value) -> Self {
-        Post { data }
+        let front_matter = FrontMatter::from_value(&data);
+        Post { data, front_matter }
     }
 
     /// 获取文章标题
     pub fn title(&self) -> Option<&str> {
-        self.data.get("title").and_then(|v| v.as_str())
+        self.front_matter.title.as_deref()
+    }
+
+    /// 获取文章布局
+    pub fn layout(&self) -> Option<&Layout> {
+        self.front_matter.layout.as_ref()
+    }
+
+    /// 是否为指定布局
+    pub fn has_layout(&self, layout: Layout) -> bool {
+        self.front_matter.layout.as_ref() == Some(&layout)
+    }
+
+    /// 获取封面路径
+    pub fn cover(&self) -> Option<&str> {
+        self.front_matter.cover.as_deref()
+    }
+
+    /// 获取专栏/书籍目录
+    pub fn catalog(&self) -> &[String] {
+        &self.front_matter.catalog
+    }
+
+    /// 获取排序权重
+    pub fn weight(&self) -> Option<i64> {
+        self.front_matter.weight
+    }
+
+    /// 是否为草稿
+    pub fn is_draft(&self) -> bool {
+        self.front_matter.draft
     }
 
     /// 获取文章 slug
@@ -52,25 +106,9 @@ impl Post {
             .unwrap_or_default()
     }
 
-    /// 获取文章标签
+    /// 获取文章标签（已去空、去重）
     pub fn tags(&self) -> Vec<String> {
-        let mut tags: Vec<String> = self
-            .data
-            .get("tags")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect::<Vec<String>>()
-            })
-            .unwrap_or_default();
-
-        // 去重（保持顺序）
-        let mut seen = std::collections::HashSet::new();
-        tags.retain(|t| seen.insert(t.clone()));
-        tags
+        self.front_matter.tags.clone()
     }
 
     /// 获取文章日期
@@ -97,6 +135,78 @@ impl Post {
     pub fn modified_epoch(&self) -> Option<i64> {
         self.data.get("modified_epoch").and_then(|v| v.as_i64())
     }
+
+    /// 获取摘要 HTML
+    pub fn summary_html(&self) -> Option<&str> {
+        self.data.get("summary_html").and_then(|v| v.as_str())
+    }
+
+    /// 获取摘要纯文本
+    pub fn summary_text(&self) -> Option<&str> {
+        self.data.get("summary_text").and_then(|v| v.as_str())
+    }
+}
+
+/// 解析选项（来自站点配置；单文件解析等无配置场景使用默认值）
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// 自动摘要长度（`[excerpt] length`）
+    pub excerpt_length: usize,
+    /// 中文阅读速度（`[reading] cjk_chars_per_minute`）
+    pub cjk_chars_per_minute: usize,
+    /// 英文阅读速度（`[reading] words_per_minute`）
+    pub words_per_minute: usize,
+    /// 构建时代码高亮（`[highlight]`）
+    pub highlight: HighlightOptions,
+    /// Markdown 扩展（`[markdown]`，可被 front matter `markdown:` 按篇覆盖）
+    pub markdown: MarkdownOptions,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            excerpt_length: excerpt::DEFAULT_EXCERPT_LENGTH,
+            cjk_chars_per_minute: reading::DEFAULT_CJK_CHARS_PER_MINUTE,
+            words_per_minute: reading::DEFAULT_WORDS_PER_MINUTE,
+            highlight: HighlightOptions::default(),
+            markdown: MarkdownOptions::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// 从站点配置读取解析选项
+    pub fn from_config(config: &Config) -> Self {
+        let mut options = ParseOptions {
+            highlight: HighlightOptions::from_config(config),
+            markdown: MarkdownOptions::from_config(config),
+            ..ParseOptions::default()
+        };
+        if let Some(length) = config
+            .data
+            .get("excerpt")
+            .and_then(|v| v.get("length"))
+            .and_then(|v| v.as_integer())
+        {
+            options.excerpt_length = length.max(0) as usize;
+        }
+        let reading_speed = |key: &str| {
+            config
+                .data
+                .get("reading")
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_integer())
+                .filter(|n| *n > 0)
+                .map(|n| n as usize)
+        };
+        if let Some(speed) = reading_speed("cjk_chars_per_minute") {
+            options.cjk_chars_per_minute = speed;
+        }
+        if let Some(speed) = reading_speed("words_per_minute") {
+            options.words_per_minute = speed;
+        }
+        options
+    }
 }
 
 /// 文章解析器
@@ -104,7 +214,7 @@ pub struct PostParser;
 
 impl PostParser {
     /// 从 Markdown 文本中提取标题：优先首个 H1（`# 标题`），否则首个任意级别标题
-    fn extract_title_from_markdown(markdown: &str) -> Option<String> {
+    pub(crate) fn extract_title_from_markdown(markdown: &str) -> Option<String> {
         // 先扫描首个 H1
         let mut in_code_fence = false;
         for line in markdown.lines() {
@@ -147,9 +257,27 @@ impl PostParser {
         None
     }
 
-    /// 列出指定目录下的所有文章
+    /// 列出指定目录下的所有文章（解析失败的文章被跳过）
     pub fn list_posts<P: AsRef<Path>>(md_dir: P) -> Result<Vec<Post>> {
+        Self::list_posts_with_errors(md_dir, &ParseOptions::default()).map(|(posts, _)| posts)
+    }
+
+    /// 列出指定目录下的所有文章，并返回被跳过的解析失败文章
+    pub fn list_posts_with_errors<P: AsRef<Path>>(
+        md_dir: P,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Post>, Vec<BuildIssue>)> {
+        Self::list_posts_cached(md_dir, options, None)
+    }
+
+    /// 同 [`list_posts_with_errors`](Self::list_posts_with_errors)，内容未变的文章从解析缓存读取，不再渲染 Markdown
+    pub fn list_posts_cached<P: AsRef<Path>>(
+        md_dir: P,
+        options: &ParseOptions,
+        cache: Option<&PostCache>,
+    ) -> Result<(Vec<Post>, Vec<BuildIssue>)> {
         let mut posts = Vec::new();
+        let mut issues = Vec::new();
         let content_dir = md_dir.as_ref();
 
         // 检查目录是否存在
@@ -161,113 +289,136 @@ impl PostParser {
             std::fs::create_dir_all(content_dir)?;
         }
 
+        // 单次遍历收集全部 Markdown 文件
+        let md_files: Vec<PathBuf> = WalkDir::new(content_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "md"))
+            .map(|e| e.into_path())
+            .collect();
+
         // 预扫描：查找所有 docs 下 README.md 标记为 draft 的目录，以及显式标记为 layout: doc 的书籍目录
+        // （只需 front matter，不渲染正文）
         let mut draft_dirs = std::collections::HashSet::new();
         let mut book_dirs = std::collections::HashMap::new(); // 存储目录 -> 封面路径的映射
-        for entry in WalkDir::new(content_dir).into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.file_name().map_or(false, |n| n == "README.md") {
-                if let Ok(content) = std::fs::read_to_string(path) {
-                    if let Ok(Some(post_data)) = Self::parse_post(&content, path, content_dir) {
-                        let is_draft = post_data.get("draft").and_then(|v| v.as_bool()).unwrap_or(false);
-                        if is_draft {
-                            if let Some(parent) = path.parent() {
-                                draft_dirs.insert(parent.to_path_buf());
+        for path in md_files.iter().filter(|p| p.file_name().is_some_and(|n| n == "README.md")) {
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let Some(raw) = front_matter::split(&content) else {
+                continue;
+            };
+            if let Ok(metadata) = front_matter::parse_metadata(&raw, path) {
+                let fm = FrontMatter::from_value(&metadata);
+                if fm.draft {
+                    if let Some(parent) = path.parent() {
+                        draft_dirs.insert(parent.to_path_buf());
+                    }
+                }
+
+                // 识别书籍目录：在 docs/ 下且 README.md 的 layout 为 doc
+                let cats = Self::extract_categories_from_path(path.as_path(), content_dir);
+                if cats.len() == 2 && cats[0] == "docs" && fm.layout == Some(Layout::Doc) {
+                    if let Some(parent) = path.parent() {
+                        let mut cover_path = fm.cover.clone();
+
+                        // 1. 如果手动设置了封面且是相对路径，转换为站点绝对路径
+                        if let Some(cp) = cover_path.as_mut() {
+                            if !cp.starts_with('/') && !cp.starts_with("http") {
+                                if let Ok(rel_dir) = parent.strip_prefix(content_dir) {
+                                    *cp = format!("/{}", rel_dir.join(&cp).to_string_lossy());
+                                }
                             }
                         }
 
-                        // 识别书籍目录：在 docs/ 下且 README.md 的 layout 为 doc
-                        let cats = Self::extract_categories_from_path(path, content_dir);
-                        if cats.len() == 2 && cats[0] == "docs" {
-                            if let Some("doc") = post_data.get("layout").and_then(|v| v.as_str()) {
-                                if let Some(parent) = path.parent() {
-                                    let mut cover_path = post_data.get("cover").and_then(|v| v.as_str()).map(|s| s.to_string());
-                                    
-                                    // 1. 如果手动设置了封面且是相对路径，转换为站点绝对路径
-                                    if let Some(cp) = cover_path.as_mut() {
-                                        if !cp.starts_with('/') && !cp.starts_with("http") {
-                                            if let Ok(rel_dir) = parent.strip_prefix(content_dir) {
-                                                *cp = format!("/{}", rel_dir.join(&cp).to_string_lossy());
-                                            }
-                                        }
-                                    }
-
-                                    // 2. 自动探测同级目录或 assets/ 下的 cover.jpg 或 cover.png
-                                    if cover_path.is_none() {
-                                        let candidates = [
-                                            parent.join("cover.jpg"), parent.join("cover.png"),
-                                            parent.join("assets").join("cover.jpg"), parent.join("assets").join("cover.png")
-                                        ];
-                                        for cand in candidates {
-                                            if cand.exists() {
-                                                if let Ok(rel) = cand.strip_prefix(content_dir) {
-                                                    cover_path = Some(format!("/{}", rel.to_string_lossy()));
-                                                    break;
-                                                }
-                                            }
-                                        }
+                        // 2. 自动探测同级目录或 assets/ 下的 cover.jpg 或 cover.png
+                        if cover_path.is_none() {
+                            let candidates = [
+                                parent.join("cover.jpg"), parent.join("cover.png"),
+                                parent.join("assets").join("cover.jpg"), parent.join("assets").join("cover.png")
+                            ];
+                            for cand in candidates {
+                                if cand.exists() {
+                                    if let Ok(rel) = cand.strip_prefix(content_dir) {
+                                        cover_path = Some(format!("/{}", rel.to_string_lossy()));
+                                        break;
                                     }
-                                    book_dirs.insert(parent.to_path_buf(), cover_path);
                                 }
                             }
                         }
+                        book_dirs.insert(parent.to_path_buf(), cover_path);
                     }
                 }
             }
         }
 
-        for entry in WalkDir::new(content_dir).into_iter().filter_map(|e| e.ok()) {
-            if entry.path().extension().map_or(false, |ext| ext == "md") {
+        let root_readme = content_dir.join("README.md");
+        let sources: Vec<&PathBuf> = md_files
+            .iter()
+            .filter(|path| {
                 // 跳过根层下的 README.md（它是主页本身的配置文件，不作为文章列表项）
-                if entry.path() == content_dir.join("README.md") {
-                    continue;
+                **path != root_readme
+                    // 如果文件在被禁用的 draft 目录下，则跳过
+                    && !draft_dirs.iter().any(|d| path.starts_with(d))
+                    // 跳过隐藏的 Markdown 文件（文件名以点开头）
+                    && !path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+
+        // 读取与解析（Markdown 渲染、高亮、公式）在线程池中并行执行，结果保持遍历顺序
+        let parsed: Vec<(&PathBuf, Result<Option<Value>>)> = sources
+            .par_iter()
+            .map(|path| {
+                let content = std::fs::read_to_string(path)
+                    .map_err(|e| Error::Other(format!("无法读取文件 {:?}: {}", path, e)))?;
+                Ok((*path, Self::parse_post_cached(&content, path, content_dir, options, cache)))
+            })
+            .collect::<Result<_>>()?;
+
+        for (path, parsed) in parsed {
+            let parsed = match parsed {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    issues.push(BuildIssue::new(IssueKind::Parse, path, describe_error(&e)));
+                    None
                 }
-                // 如果文件在被禁用的 draft 目录下，则跳过
-                if draft_dirs.iter().any(|d| entry.path().starts_with(d)) {
+            };
+            if let Some(mut post) = parsed {
+                // 检查 draft 字段，如果是 true 则跳过
+                if FrontMatter::from_value(&post).draft {
                     continue;
                 }
 
-                // 跳过隐藏的 Markdown 文件（文件名以点开头）
-                let hidden = entry.file_name().to_string_lossy().starts_with('.');
-                if hidden {
-                    continue;
-                }
-                let content = std::fs::read_to_string(entry.path())
-                    .map_err(|e| Error::Other(format!("无法读取文件 {:?}: {}", entry.path(), e)))?;
-                    if let Ok(Some(mut post)) = Self::parse_post(&content, entry.path(), content_dir) {
-                        // 检查 draft 字段，如果是 true 则跳过
-                        let is_draft = post
-                            .get("draft")
-                            .and_then(|v| v.as_bool())
-                            .unwrap_or(false);
-                        if is_draft {
-                            continue;
-                        }
+                // 字段拼写与布局诊断（如 `layuot:`）记入构建报告，仅在严格模式下阻断构建
+                issues.extend(
+                    front_matter::diagnose(&post)
+                        .into_iter()
+                        .map(|diagnostic| BuildIssue::new(IssueKind::FrontMatter, path, diagnostic.message)),
+                );
 
-                        // 处理布局与封面数据逻辑
-                        let cats = Post::from_value(post.clone()).categories();
-                        if let Some(obj) = post.as_object_mut() {
-                            // 1. 自动应用分支逻辑（仅在未设置布局时）
-                            if !obj.contains_key("layout") {
-                                if cats.first().map(|c| c == "projects").unwrap_or(false) {
-                                    obj.insert("layout".to_string(), Value::String("project".to_string()));
-                                } else if book_dirs.iter().any(|(d, _)| entry.path().starts_with(d)) {
-                                    obj.insert("layout".to_string(), Value::String("doc".to_string()));
-                                }
-                            }
+                // 处理布局与封面数据逻辑
+                let cats = Post::from_value(post.clone()).categories();
+                if let Some(obj) = post.as_object_mut() {
+                    // 1. 自动应用分支逻辑（仅在未设置布局时）
+                    if !obj.contains_key("layout") {
+                        if cats.first().map(|c| c == "projects").unwrap_or(false) {
+                            obj.insert("layout".to_string(), Value::String("project".to_string()));
+                        } else if book_dirs.iter().any(|(d, _)| path.starts_with(d)) {
+                            obj.insert("layout".to_string(), Value::String("doc".to_string()));
+                        }
+                    }
 
-                            // 2. 注入探测到的书籍封面（仅限 README.md）
-                            if entry.path().file_name().map_or(false, |n| n == "README.md") {
-                                if let Some((_, cover_opt)) = book_dirs.iter().find(|(d, _)| entry.path().starts_with(d)) {
-                                    if let Some(cp) = cover_opt {
-                                        // 始终使用 pre-scan 阶段处理过的标准化路径（绝对路径）
-                                        obj.insert("cover".to_string(), Value::String(cp.clone()));
-                                    }
-                                }
+                    // 2. 注入探测到的书籍封面（仅限 README.md）
+                    if path.file_name().map_or(false, |n| n == "README.md") {
+                        if let Some((_, cover_opt)) = book_dirs.iter().find(|(d, _)| path.starts_with(d)) {
+                            if let Some(cp) = cover_opt {
+                                // 始终使用 pre-scan 阶段处理过的标准化路径（绝对路径）
+                                obj.insert("cover".to_string(), Value::String(cp.clone()));
                             }
                         }
-                        posts.push(Post::from_value(post));
                     }
+                }
+                posts.push(Post::from_value(post));
             }
         }
 
@@ -278,61 +429,40 @@ impl PostParser {
             time_b.cmp(time_a)
         });
 
-        Ok(posts)
+        Ok((posts, issues))
     }
 
-    /// 解析单篇文章
-    fn parse_post<P: AsRef<Path>>(content: &str, path: P, md_dir: P) -> Result<Option<Value>> {
-        let path = path.as_ref();
-        let md_dir = md_dir.as_ref();
-
-        // 检查 front matter 类型
-        let (fm_marker, end_marker) = if content.starts_with("+++") {
-            ("+++", "+++\n")
-        } else if content.starts_with("---") {
-            ("---", "---\n")
-        } else {
-            return Ok(None);
+    /// 解析文章内容：front matter 与渲染后的 HTML 等，只取决于内容、相对路径与解析选项（可缓存）
+    fn parse_content(content: &str, path: &Path, md_dir: &Path, options: &ParseOptions) -> Result<Option<Value>> {
+        // 拆分并解析 front matter（YAML 会先做中文冒号、缺少空格等容错修复）
+        let raw = match front_matter::split(content) {
+            Some(raw) => raw,
+            None => return Ok(None),
         };
-
-        // 查找 front matter 结束位置
-        let start = fm_marker.len();
-        let end = if let Some(pos) = content[start..].find(end_marker) {
-            start + pos
-        } else if let Some(pos) = content[start..].find(fm_marker) {
-            start + pos
-        } else {
-            return Ok(None);
+        let body = raw.body;
+        let metadata_json = front_matter::parse_metadata(&raw, path)?;
+        // front matter `markdown:` 按篇覆盖 Markdown 扩展
+        let post_options;
+        let options = match options.markdown.with_overrides(metadata_json.get("markdown")) {
+            Some(markdown) => {
+                post_options = ParseOptions { markdown, ..options.clone() };
+                &post_options
+            }
+            None => options,
         };
 
-        let front_matter = &content[start..end];
-        let body = &content[end + fm_marker.len()..];
-
-        // 解析front matter
-        let metadata_json = if fm_marker == "+++" {
-            let metadata: toml::Value = toml::from_str(front_matter).map_err(|e| {
-                Error::Markdown(format!("解析TOML front matter失败 {:?}: {}", path, e))
-            })?;
-            serde_json::to_value(metadata)?
+        // 解析Markdown为HTML（不在解析阶段追加任何额外内容）
+        let (html, headings) = Self::render_markdown(body, options)?;
+        let toc_entries = if options.markdown.header_ids {
+            Self::build_toc(headings, &metadata_json)
         } else {
-            // 针对 YAML 做鲁棒性处理：
-            // 1. 修复中文冒号为英文冒号
-            let mut fixed_front_matter = front_matter.replace('：', ":");
-            
-            // 2. 修复缺少空格的键值对 (e.g. "key:value" -> "key: value")
-            let re = Regex::new(r"(?m)^([ \t]*[a-zA-Z0-9_-]+):([^\s].*)$").unwrap();
-            fixed_front_matter = re.replace_all(&fixed_front_matter, "${1}: ${2}").to_string();
-
-            let metadata: serde_yaml::Value =
-                serde_yaml::from_str(&fixed_front_matter).map_err(|e| {
-                    // 如果解析失败，打印原始内容以便调试
-                    Error::Markdown(format!("解析YAML front matter失败 {:?}: {}", path, e))
-                })?;
-            serde_json::to_value(metadata)?
+            Vec::new()
         };
-
-        // 解析Markdown为HTML（不在解析阶段追加任何额外内容）
-        let html = Self::markdown_to_html(body);
+        let excerpt = excerpt::extract(body, &html, &metadata_json, options.excerpt_length, |md| {
+            Self::render_markdown(md, options).map(|(html, _)| html)
+        })?;
+        let word_count = reading::count_html(&html);
+        let reading_minutes = word_count.reading_minutes(options.cjk_chars_per_minute, options.words_per_minute);
 
         // 优先使用 front matter 中的 slug 字段，否则用文件名
         let mut slug = path
@@ -395,42 +525,15 @@ impl PostParser {
 
         // 处理日期相关字段
         if let Some(obj) = post.as_object_mut() {
-            // 记录源文件路径与修改时间戳（用于增量编译）
-            obj.insert(
-                "source_path".to_string(),
-                Value::String(path.to_string_lossy().to_string()),
-            );
+            obj.insert("summary_html".to_string(), Value::String(excerpt.html));
+            obj.insert("summary_text".to_string(), Value::String(excerpt.text));
+            obj.insert("toc".to_string(), serde_json::to_value(&toc_entries).unwrap_or(Value::Array(vec![])));
+            obj.insert("word_count".to_string(), Value::from(word_count.total()));
+            obj.insert("reading_time_minutes".to_string(), Value::from(reading_minutes));
             obj.insert(
                 "file_name".to_string(),
                 Value::String(path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string()),
             );
-            let modified_epoch = std::fs::metadata(path)
-                .and_then(|m| m.modified())
-                .ok()
-                .and_then(|st| st.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs() as i64)
-                .unwrap_or(0);
-            obj.insert(
-                "modified_epoch".to_string(),
-                Value::Number(modified_epoch.into()),
-            );
-            
-            // 如果属于某个专栏 (首个分类是 "columns" 且分类数 >= 2)，提取专栏标题 (即同级 README.md 的 title) 并注入 column_title
-            if categories.first().map(|c| c == "columns").unwrap_or(false) && categories.len() >= 2 {
-                if let Some(parent) = path.parent() {
-                    let readme_path = parent.join("README.md");
-                    if readme_path.exists() {
-                        if let Ok(readme_content) = std::fs::read_to_string(&readme_path) {
-                            let re_title = Regex::new(r#"(?m)^title:\s*['"]?([^'"\n]+)['"]?"#).unwrap();
-                            if let Some(caps) = re_title.captures(&readme_content) {
-                                let col_title = caps.get(1).map(|m| m.as_str().trim().to_string()).unwrap_or_default();
-                                obj.insert("column_title".to_string(), Value::String(col_title.clone()));
-
-                            }
-                        }
-                    }
-                }
-            }
 
             // 如果没有 title 字段，尝试从 Markdown 内容提取标题
             if !obj.contains_key("title") {
@@ -591,6 +694,97 @@ impl PostParser {
         Ok(Some(post))
     }
 
+    /// 解析单篇文章
+    fn parse_post<P: AsRef<Path>>(
+        content: &str,
+        path: P,
+        md_dir: P,
+        options: &ParseOptions,
+    ) -> Result<Option<Value>> {
+        let path = path.as_ref();
+        let mut post = Self::parse_content(content, path, md_dir.as_ref(), options)?;
+        if let Some(post) = post.as_mut() {
+            Self::attach_file_metadata(post, path, md_dir.as_ref());
+        }
+        Ok(post)
+    }
+
+    /// 优先从解析缓存读取文章；未命中时解析并写入缓存
+    fn parse_post_cached(
+        content: &str,
+        path: &Path,
+        md_dir: &Path,
+        options: &ParseOptions,
+        cache: Option<&PostCache>,
+    ) -> Result<Option<Value>> {
+        let Some((cache, key)) = cache.zip(crate::manifest::source_key(md_dir, path)) else {
+            return Self::parse_post(content, path, md_dir, options);
+        };
+        let hash = crate::manifest::hash_bytes(content.as_bytes());
+        let mut post = match cache.get(&key, &hash) {
+            Some(post) => Some(post),
+            None => {
+                let post = Self::parse_content(content, path, md_dir, options)?;
+                if let Some(post) = &post {
+                    // 未写时间的文章以构建时刻为准，不能缓存
+                    if Self::has_explicit_time(content, path) {
+                        cache.insert(key, hash, post.clone());
+                    }
+                }
+                post
+            }
+        };
+        if let Some(post) = post.as_mut() {
+            Self::attach_file_metadata(post, path, md_dir);
+        }
+        Ok(post)
+    }
+
+    /// front matter 是否显式给出 createTime / date
+    fn has_explicit_time(content: &str, path: &Path) -> bool {
+        front_matter::split(content)
+            .and_then(|raw| front_matter::parse_metadata(&raw, path).ok())
+            .is_some_and(|m| m.get("createTime").or_else(|| m.get("date")).and_then(|v| v.as_str()).is_some())
+    }
+
+    /// 注入依赖文件系统的字段（不进入解析缓存）：源文件路径、修改时间戳与所属专栏标题
+    fn attach_file_metadata(post: &mut Value, path: &Path, md_dir: &Path) {
+        let Some(obj) = post.as_object_mut() else {
+            return;
+        };
+        // 记录源文件路径与修改时间戳（用于增量编译）
+        obj.insert(
+            "source_path".to_string(),
+            Value::String(path.to_string_lossy().to_string()),
+        );
+        let modified_epoch = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|st| st.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        obj.insert(
+            "modified_epoch".to_string(),
+            Value::Number(modified_epoch.into()),
+        );
+
+        // 如果属于某个专栏 (首个分类是 "columns" 且分类数 >= 2)，提取专栏标题 (即同级 README.md 的 title) 并注入 column_title
+        let categories = Self::extract_categories_from_path(path, md_dir);
+        if categories.first().map(|c| c == "columns").unwrap_or(false) && categories.len() >= 2 {
+            if let Some(parent) = path.parent() {
+                let readme_path = parent.join("README.md");
+                if readme_path.exists() {
+                    if let Ok(readme_content) = std::fs::read_to_string(&readme_path) {
+                        if let Some(caps) = RE_FRONT_MATTER_TITLE.captures(&readme_content) {
+                            let col_title = caps.get(1).map(|m| m.as_str().trim().to_string()).unwrap_or_default();
+                            obj.insert("column_title".to_string(), Value::String(col_title));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// 从文件路径提取分类信息
     fn extract_categories_from_path<P: AsRef<Path>>(path: P, md_dir: P) -> Vec<String> {
         let path = path.as_ref();
@@ -615,28 +809,64 @@ impl PostParser {
         categories
     }
 
-    /// 将Markdown转换为HTML
-    fn markdown_to_html(markdown: &str) -> String {
-        // 复用 comrak 渲染，对照原 pulldown-cmark 启用的扩展：
-        // ENABLE_TABLES / ENABLE_FOOTNOTES / ENABLE_STRIKETHROUGH / ENABLE_TASKLISTS。
-        // 额外启用 highlight（==高亮== → <mark>），pulldown-cmark 0.9 不支持此扩展。
-        let mut options = Options::default();
-        options.extension.table = true;
-        options.extension.strikethrough = true;
-        options.extension.footnotes = true;
-        options.extension.tasklist = true;
-        options.extension.highlight = true;
-
-        let html = markdown_to_html(markdown, &options);
+    /// 将Markdown转换为HTML，同时返回按文档顺序排列的标题 (层级, 文本, 锚点)
+    ///
+    /// 代码高亮与标题插件出错时渲染会提前中止，此时返回错误，避免发布被截断的页面。
+    fn render_markdown(markdown: &str, parse_options: &ParseOptions) -> Result<(String, Vec<toc::Heading>)> {
+        // 扩展由 [markdown] 配置决定（默认：表格、删除线、脚注、任务列表与 ==高亮==）
+        let markdown_options = &parse_options.markdown;
+        let mut options = markdown_options.comrak_options();
+
+        // 标题由插件渲染：生成稳定的锚点 id 并收集目录条目
+        let headings = toc::HeadingCollector::new(markdown_options.header_ids, &markdown_options.header_id_prefix);
+        let mut plugins = Plugins::default();
+        plugins.render.heading_adapter = Some(&headings);
+        // 构建时代码高亮：语言与围栏属性经 github_pre_lang + full_info_string 传给插件
+        let highlighter = highlight::CodeHighlighter::new(&parse_options.highlight);
+        if parse_options.highlight.enable {
+            options.render.github_pre_lang = true;
+            options.render.full_info_string = true;
+            plugins.render.codefence_syntax_highlighter = Some(&highlighter);
+        }
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown, &options);
+        if markdown_options.alerts {
+            callout::transform(&arena, root, &markdown_options.callout_titles);
+        }
+        let mut html = String::new();
+        format_html_with_plugins(root, &options, &mut html, &plugins)
+            .map_err(|_| Error::Markdown("渲染 HTML 失败（代码高亮或标题插件出错）".to_string()))?;
+        drop(plugins);
+        let headings = headings.into_headings();
 
         // 归一化代码块尾部多余空行：围栏内若以一个空行结尾，渲染器会在 </code>
         // 前保留多换行，叠加 Typography 的 pre 上下 1em padding 导致底部空白偏大。
         // 把 <code> 内部 2 个及以上的尾部换行压缩为 1 个。
-        let re = Regex::new(r"(?s)(<code[^>]*>)([\s\S]*?)(\n{2,})</code>").unwrap();
-        re.replace_all(&html, |c: &regex::Captures| {
-            format!("{}{}\n</code>", &c[1], &c[2])
-        })
-        .to_string()
+        let html = RE_CODE_TRAILING_NEWLINES
+            .replace_all(&html, |c: &regex::Captures| {
+                format!("{}{}\n</code>", &c[1], &c[2])
+            })
+            .to_string();
+        // 数学公式在构建时转换为 MathML
+        let html = if markdown_options.math { math::render_math(&html) } else { html };
+        Ok((html, headings))
+    }
+
+    /// 生成文章目录：front matter `toc: false` 时为空；
+    /// 第一个 H1 是文章标题（模板渲染时会移除），不计入目录
+    fn build_toc(mut headings: Vec<toc::Heading>, metadata: &Value) -> Vec<toc::TocEntry> {
+        let enabled = match metadata.get("toc") {
+            Some(Value::Bool(b)) => *b,
+            Some(Value::String(s)) => !matches!(s.trim().to_lowercase().as_str(), "false" | "no" | "off" | "0"),
+            _ => true,
+        };
+        if !enabled {
+            return Vec::new();
+        }
+        if let Some(pos) = headings.iter().position(|(level, _, _)| *level == 1) {
+            headings.remove(pos);
+        }
+        toc::build_tree(headings)
     }
 
     /// 统计所有标签及计数
@@ -791,7 +1021,7 @@ impl PostParser {
         path: P,
         md_dir: P,
     ) -> Result<Option<Value>> {
-        Self::parse_post(content, path, md_dir)
+        Self::parse_post(content, path, md_dir, &ParseOptions::default())
     }
 
     /// 传入一个 md 绝对/相对路径，以及可选的文件内容（用于提取 slug），返回 url 路径信息（不包括域名），作为 id
@@ -806,7 +1036,7 @@ impl PostParser {
         };
 
         if let Some(c) = content_to_use {
-            if let Ok(Some(post_val)) = Self::parse_post(&c, path, md_dir) {
+            if let Ok(Some(post_val)) = Self::parse_post(&c, path, md_dir, &ParseOptions::default()) {
                 if let Some(url) = post_val.get("url").and_then(|v| v.as_str()) {
                     return url.to_string();
                 }
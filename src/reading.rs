@@ -0,0 +1,91 @@
+//! 字数与阅读时长统计模块
+//!
+//! 中文（含日文假名、韩文）逐字计数，英文等按空白分隔的单词计数，代码块不计入。
+//! 阅读速度可在 `[reading]` 中配置。
+
+use crate::post::Post;
+use crate::utils::strip_html_tags;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// 默认中文阅读速度（字/分钟）
+pub const DEFAULT_CJK_CHARS_PER_MINUTE: usize = 300;
+/// 默认英文阅读速度（词/分钟）
+pub const DEFAULT_WORDS_PER_MINUTE: usize = 200;
+
+static RE_PRE_BLOCK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<pre\b[^>]*>.*?</pre>").unwrap());
+
+/// 单篇文章的字数统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WordCount {
+    /// 中日韩字符数
+    pub cjk_chars: usize,
+    /// 其他语言的单词数
+    pub words: usize,
+}
+
+impl WordCount {
+    /// 总字数（中文字数 + 英文单词数）
+    pub fn total(&self) -> usize {
+        self.cjk_chars + self.words
+    }
+
+    /// 阅读时长（分钟，向上取整；有内容时至少 1 分钟）
+    pub fn reading_minutes(&self, cjk_chars_per_minute: usize, words_per_minute: usize) -> usize {
+        if self.total() == 0 {
+            return 0;
+        }
+        let minutes = self.cjk_chars as f64 / cjk_chars_per_minute.max(1) as f64
+            + self.words as f64 / words_per_minute.max(1) as f64;
+        (minutes.ceil() as usize).max(1)
+    }
+}
+
+/// 是否为按字计数的中日韩字符
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3400..=0x4DBF      // CJK 扩展 A
+        | 0x4E00..=0x9FFF    // CJK 统一表意文字
+        | 0xF900..=0xFAFF    // CJK 兼容表意文字
+        | 0x20000..=0x2FA1F  // CJK 扩展 B 及之后
+        | 0x3040..=0x30FF    // 平假名、片假名
+        | 0xAC00..=0xD7AF    // 韩文音节
+    )
+}
+
+/// 统计纯文本字数
+pub fn count_text(text: &str) -> WordCount {
+    let mut count = WordCount::default();
+    // 当前是否处于一个尚未计数的单词中
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            count.cjk_chars += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                count.words += 1;
+                in_word = true;
+            }
+        } else if c.is_whitespace() || !matches!(c, '\'' | '’' | '-' | '_' | '.') {
+            // 撇号、连字符等视为单词内部字符（如 don't、well-known、v1.2）
+            in_word = false;
+        }
+    }
+    count
+}
+
+/// 统计渲染后 HTML 的字数（代码块不计入）
+pub fn count_html(html: &str) -> WordCount {
+    let without_code = RE_PRE_BLOCK.replace_all(html, " ");
+    count_text(&strip_html_tags(&without_code.replace('<', " <").replace('>', "> ")))
+}
+
+/// 全站字数与阅读时长合计
+pub fn site_totals(posts: &[Post]) -> (u64, u64) {
+    posts.iter().fold((0, 0), |(words, minutes), p| {
+        let w = p.data.get("word_count").and_then(|v| v.as_u64()).unwrap_or(0);
+        let m = p.data.get("reading_time_minutes").and_then(|v| v.as_u64()).unwrap_or(0);
+        (words + w, minutes + m)
+    })
+}
@@ -0,0 +1,237 @@
+//! 构建报告模块
+//!
+//! 收集构建过程中单篇文章的解析失败、模板渲染失败、缺失的引用资源（封面、图片、图标）
+//! 、无法转换的数学公式以及指向不存在文章的 `.md` 链接。
+//! 默认（宽松）模式下在构建结束时输出汇总表，严格模式（`build --strict`）下使构建失败。
+
+use crate::error::Error;
+use crate::post::Post;
+use regex::Regex;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// 正文中的图片引用（兼容 Markdown 渲染结果与手写 HTML）
+static RE_IMG_SRC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<img\b[^>]*?\bsrc\s*=\s*["']([^"']+)["']"#).unwrap());
+
+const IMAGE_EXTENSIONS: &[&str] = &[".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp", ".ico", ".avif"];
+
+/// 问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// front matter 解析失败，文章被跳过
+    Parse,
+    /// front matter 字段疑似拼写错误或布局未知（如 `layuot:`），文章照常生成
+    FrontMatter,
+    /// 文章页面模板渲染失败
+    Template,
+    /// 引用的资源文件不存在
+    Asset,
+    /// 数学公式含不支持的 LaTeX 命令（该处显示原文）
+    Math,
+    /// 正文中指向 `.md` 源文件的链接找不到目标文章（保持原样输出）
+    Link,
+}
+
+impl IssueKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IssueKind::Parse => "解析",
+            IssueKind::FrontMatter => "字段",
+            IssueKind::Template => "模板",
+            IssueKind::Asset => "资源",
+            IssueKind::Math => "公式",
+            IssueKind::Link => "链接",
+        }
+    }
+}
+
+/// 单条构建问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildIssue {
+    pub kind: IssueKind,
+    /// 出问题的源文件
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl BuildIssue {
+    pub fn new<P: Into<PathBuf>>(kind: IssueKind, path: P, message: String) -> Self {
+        BuildIssue {
+            kind,
+            path: path.into(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for BuildIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.kind.label(), self.path.display(), self.message)
+    }
+}
+
+/// 构建报告
+#[derive(Debug, Default)]
+pub struct BuildReport {
+    pub issues: Vec<BuildIssue>,
+}
+
+impl BuildReport {
+    pub fn push(&mut self, issue: BuildIssue) {
+        self.issues.push(issue);
+    }
+
+    pub fn extend<I: IntoIterator<Item = BuildIssue>>(&mut self, issues: I) {
+        self.issues.extend(issues);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    pub fn count(&self, kind: IssueKind) -> usize {
+        self.issues.iter().filter(|i| i.kind == kind).count()
+    }
+
+    /// 汇总表（按类型、文件排序）
+    pub fn summary_table(&self) -> String {
+        let mut issues: Vec<&BuildIssue> = self.issues.iter().collect();
+        issues.sort_by(|a, b| (a.kind, &a.path).cmp(&(b.kind, &b.path)));
+
+        let mut out = format!(
+            "构建问题汇总：共 {} 项（解析 {}，字段 {}，模板 {}，资源 {}，公式 {}，链接 {}）\n",
+            self.len(),
+            self.count(IssueKind::Parse),
+            self.count(IssueKind::FrontMatter),
+            self.count(IssueKind::Template),
+            self.count(IssueKind::Asset),
+            self.count(IssueKind::Math),
+            self.count(IssueKind::Link)
+        );
+        out.push_str("| 类型 | 文件 | 说明 |\n|------|------|------|\n");
+        for issue in issues {
+            // 表格单元内不能换行（tera 错误链可能是多行）
+            let message = issue.message.replace('\n', " ");
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                issue.kind.label(),
+                issue.path.display(),
+                message
+            ));
+        }
+        out
+    }
+}
+
+/// 展开错误链：tera 的顶层错误只有 “Failed to render 'post.html'”，具体原因在 source 中
+pub fn describe_error(err: &Error) -> String {
+    match err {
+        Error::Template(e) => {
+            let mut parts = vec![e.to_string()];
+            let mut source = std::error::Error::source(e);
+            while let Some(s) = source {
+                parts.push(s.to_string());
+                source = s.source();
+            }
+            parts.join(": ")
+        }
+        other => other.to_string(),
+    }
+}
+
+/// 检查文章引用的本地资源是否存在于输出目录（需在静态资源拷贝完成后调用）
+///
+/// 检查 front matter 中的 `cover`、`images`、`screenshots`、图片形式的 `icon`，以及正文中的 `<img>`。
+/// 外链、data URI 与含模板语法的地址不检查。`exists` 按站点路径（如 `/blog/assets/a.png`）判断
+/// 输出中是否有该文件，以便同时支持磁盘输出目录与内存文件系统。
+pub fn missing_assets<F: Fn(&str) -> bool>(post: &Post, exists: F) -> Vec<String> {
+    let page_url = post.url().unwrap_or("/");
+    let data = &post.data;
+
+    let mut refs: Vec<&str> = Vec::new();
+    if let Some(cover) = data.get("cover").and_then(|v| v.as_str()) {
+        refs.push(cover);
+    }
+    if let Some(icon) = data.get("icon").and_then(|v| v.as_str()) {
+        let lower = icon.trim().to_lowercase();
+        if IMAGE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) {
+            refs.push(icon);
+        }
+    }
+    for key in ["images", "screenshots"] {
+        if let Some(arr) = data.get(key).and_then(|v| v.as_array()) {
+            refs.extend(arr.iter().filter_map(|v| v.as_str()));
+        }
+    }
+    if let Some(content) = post.content() {
+        refs.extend(RE_IMG_SRC.captures_iter(content).filter_map(|c| c.get(1)).map(|m| m.as_str()));
+    }
+
+    let mut missing = Vec::new();
+    for reference in refs {
+        let Some(site_path) = resolve_site_path(reference, page_url) else {
+            continue;
+        };
+        if !exists(&site_path) && !missing.contains(&reference.to_string()) {
+            missing.push(reference.to_string());
+        }
+    }
+    missing
+}
+
+/// 将页面中的资源引用解析为站点绝对路径；外链等无需检查的地址返回 None
+fn resolve_site_path(reference: &str, page_url: &str) -> Option<String> {
+    let reference = reference.trim();
+    let lower = reference.to_lowercase();
+    let external = ["http://", "https://", "//", "data:", "mailto:", "#"];
+    if reference.is_empty() || external.iter().any(|p| lower.starts_with(p)) || reference.contains("{{") {
+        return None;
+    }
+    let reference = reference.split(['?', '#']).next().unwrap_or("");
+
+    let joined = if reference.starts_with('/') {
+        reference.to_string()
+    } else {
+        let page_dir = page_url.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        format!("{}/{}", page_dir, reference)
+    };
+
+    // 归一化 `.` 与 `..`
+    let mut segments: Vec<&str> = Vec::new();
+    for seg in joined.split('/') {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    Some(format!("/{}", percent_decode(&segments.join("/"))))
+}
+
+/// 解码 URL 中的百分号编码（Markdown 渲染会对中文文件名做编码）
+pub(crate) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
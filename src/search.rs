@@ -0,0 +1,463 @@
+//! 搜索索引模块
+//!
+//! 构建时生成倒排索引，写入 `search/` 目录：
+//! - `manifest.json`：文档数、分片列表等元信息（体积很小，首先加载）
+//! - `docs.json`：每篇文章的标题、URL、日期、标签、分类与摘要片段（不含全文）
+//! - `texts.json`：每篇文章的纯文本，仅供服务端截取命中词附近的片段，前端不加载
+//! - `<分片>.json`：按词项首字符分片的倒排表 `{词项: [[文档序号, 权重], ...]}`
+//!
+//! 分词规则：英文与数字按单词切分并转小写；中日韩文字按相邻两字（bigram）切分，
+//! 单独出现的一个字保留为单字。前端按同样的规则切分查询词，只加载所需分片。
+//!
+//! 服务端（`search` 插件）通过 [`SearchIndex::load`] 将整个索引读入内存，
+//! 用 BM25 排序，并截取正文中第一个命中词附近的片段高亮返回（摘要已含命中词时用摘要）。
+
+use crate::error::{Error, Result};
+use crate::excerpt;
+use crate::post::Post;
+use crate::reading::is_cjk;
+use crate::utils::escape_html;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// 索引输出目录（相对站点根目录）
+pub const INDEX_DIR: &str = "search";
+/// 索引格式版本，前端据此判断兼容性
+pub const INDEX_VERSION: u32 = 1;
+/// 摘要片段的最大字数
+pub const SNIPPET_CHARS: usize = 120;
+
+/// 倒排表中的一项：(文档序号, 加权词频)
+pub type Posting = (usize, u32);
+/// 一个分片：词项 -> 倒排表
+pub type Shard<'a> = BTreeMap<&'a str, &'a [Posting]>;
+
+/// BM25 参数
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// 各字段的权重
+const TITLE_WEIGHT: u32 = 10;
+const TAG_WEIGHT: u32 = 8;
+const CATEGORY_WEIGHT: u32 = 6;
+const CONTENT_WEIGHT: u32 = 1;
+
+/// 切分文本为词项
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+
+    let flush_word = |word: &mut String, tokens: &mut Vec<String>| {
+        if !word.is_empty() {
+            tokens.push(std::mem::take(word));
+        }
+    };
+    let flush_cjk = |run: &mut Vec<char>, tokens: &mut Vec<String>| {
+        match run.len() {
+            0 => {}
+            1 => tokens.push(run[0].to_string()),
+            _ => tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>())),
+        }
+        run.clear();
+    };
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk(&mut cjk_run, &mut tokens);
+    tokens
+}
+
+/// 词项所在分片：英文与数字按首字母，其余字符按 Unicode 码位每 256 个一组（如 `u4e`）
+pub fn shard_key(term: &str) -> String {
+    match term.chars().next() {
+        Some(c) if c.is_ascii_alphanumeric() => c.to_ascii_lowercase().to_string(),
+        Some(c) => format!("u{:x}", (c as u32) >> 8),
+        None => "_".to_string(),
+    }
+}
+
+/// 索引中的文档（用于展示与筛选搜索结果）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchDoc {
+    pub title: String,
+    pub url: String,
+    pub date: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    pub snippet: String,
+}
+
+/// 倒排索引
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    pub docs: Vec<SearchDoc>,
+    /// 各文档的纯文本（空白已合并），用于截取命中词附近的片段；旧索引没有时为空
+    pub texts: Vec<String>,
+    /// 词项 -> 倒排表，文档序号升序
+    pub terms: BTreeMap<String, Vec<Posting>>,
+    /// 各文档的加权词项总数（BM25 文档长度）
+    doc_lens: Vec<u32>,
+}
+
+/// 搜索请求
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub q: String,
+    /// 仅返回带该标签的文章
+    pub tag: Option<String>,
+    /// 仅返回该分类（如 `blog` 或 `blog/技术`）下的文章
+    pub category: Option<String>,
+    /// 页码，从 1 开始
+    pub page: usize,
+    pub per_page: usize,
+}
+
+/// 单条搜索结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub title: String,
+    pub url: String,
+    pub date: String,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
+    /// 已转义并用 `<mark>` 标出命中词的摘要 HTML
+    pub snippet: String,
+    pub score: f64,
+}
+
+/// 一页搜索结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResults {
+    pub query: String,
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub total_pages: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+impl SearchIndex {
+    pub fn build(posts: &[Post]) -> Self {
+        let mut index = SearchIndex::default();
+        for post in posts {
+            index.add(post);
+        }
+        index
+    }
+
+    fn add(&mut self, post: &Post) {
+        let doc_id = self.docs.len();
+        let title = post.title().unwrap_or("").to_string();
+        let text = excerpt::html_to_text(post.content().unwrap_or(""));
+
+        let mut weights: BTreeMap<String, u32> = BTreeMap::new();
+        let mut add_field = |text: &str, weight: u32| {
+            for token in tokenize(text) {
+                *weights.entry(token).or_insert(0) += weight;
+            }
+        };
+        add_field(&title, TITLE_WEIGHT);
+        for tag in post.tags() {
+            add_field(&tag, TAG_WEIGHT);
+        }
+        for category in post.categories() {
+            add_field(&category, CATEGORY_WEIGHT);
+        }
+        add_field(&text, CONTENT_WEIGHT);
+
+        self.doc_lens.push(weights.values().sum());
+        for (term, weight) in weights {
+            self.terms.entry(term).or_default().push((doc_id, weight));
+        }
+
+        let snippet_source = post.summary_text().filter(|s| !s.trim().is_empty()).unwrap_or(&text);
+        self.docs.push(SearchDoc {
+            title,
+            url: post.url().unwrap_or("").to_string(),
+            date: post.date().unwrap_or("").to_string(),
+            tags: post.tags(),
+            categories: post.categories(),
+            snippet: excerpt::truncate_text(snippet_source.trim(), SNIPPET_CHARS),
+        });
+        self.texts.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+
+    /// 按分片分组的倒排表
+    pub fn shards(&self) -> BTreeMap<String, Shard<'_>> {
+        let mut shards: BTreeMap<String, Shard<'_>> = BTreeMap::new();
+        for (term, postings) in &self.terms {
+            shards.entry(shard_key(term)).or_default().insert(term, postings);
+        }
+        shards
+    }
+
+    /// 索引清单
+    pub fn manifest(&self) -> Value {
+        let shards: BTreeMap<String, usize> =
+            self.shards().into_iter().map(|(key, terms)| (key, terms.len())).collect();
+        json!({
+            "version": INDEX_VERSION,
+            "tokenizer": "cjk-bigram",
+            "docs": self.docs.len(),
+            "shards": shards,
+        })
+    }
+
+    /// 生成输出文件：`(相对 search/ 的文件名, 紧凑 JSON)`
+    pub fn files(&self) -> Vec<(String, String)> {
+        let mut files = vec![
+            ("manifest.json".to_string(), self.manifest().to_string()),
+            ("docs.json".to_string(), serde_json::to_string(&self.docs).unwrap_or_default()),
+            ("texts.json".to_string(), serde_json::to_string(&self.texts).unwrap_or_default()),
+        ];
+        for (key, terms) in self.shards() {
+            files.push((format!("{}.json", key), serde_json::to_string(&terms).unwrap_or_default()));
+        }
+        files
+    }
+
+    /// 从构建输出的 `search/` 目录读入完整索引
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        Self::load_with(|name| {
+            let path = dir.join(name);
+            std::fs::read_to_string(&path).map_err(|e| Error::Other(format!("无法读取搜索索引 {:?}: {}", path, e)))
+        })
+    }
+
+    /// 用 `read`（参数为相对 search/ 的文件名）读入完整索引，如从内存文件系统读取
+    pub fn load_with<F: Fn(&str) -> Result<String>>(read: F) -> Result<Self> {
+        let read_json = |name: &str| -> Result<Value> { Ok(serde_json::from_str(&read(name)?)?) };
+
+        let manifest = read_json("manifest.json")?;
+        let version = manifest.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version != u64::from(INDEX_VERSION) {
+            return Err(Error::Other(format!("不支持的搜索索引版本: {}", version)));
+        }
+
+        let docs: Vec<SearchDoc> = serde_json::from_value(read_json("docs.json")?)?;
+        // 旧版本构建的索引没有 texts.json，此时只在摘要内高亮
+        let texts: Vec<String> = match read("texts.json") {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(_) => Vec::new(),
+        };
+        let mut index = SearchIndex { doc_lens: vec![0; docs.len()], docs, texts, ..SearchIndex::default() };
+        let keys = manifest.get("shards").and_then(|v| v.as_object()).map(|m| m.keys().cloned().collect::<Vec<_>>());
+        for key in keys.unwrap_or_default() {
+            let shard: BTreeMap<String, Vec<Posting>> = serde_json::from_value(read_json(&format!("{}.json", key))?)?;
+            for (term, postings) in shard {
+                for &(doc_id, weight) in &postings {
+                    if let Some(len) = index.doc_lens.get_mut(doc_id) {
+                        *len += weight;
+                    }
+                }
+                index.terms.insert(term, postings);
+            }
+        }
+        Ok(index)
+    }
+
+    /// BM25 排序搜索：命中查询词越多越靠前，其次按 BM25 得分；
+    /// 查询词没有完全匹配的词项时按前缀匹配（输入到一半的单词、单个汉字）
+    pub fn search(&self, query: &SearchQuery) -> SearchResults {
+        let per_page = query.per_page.max(1);
+        let page = query.page.max(1);
+        let tokens = {
+            let mut tokens = tokenize(&query.q);
+            let mut seen = std::collections::HashSet::new();
+            tokens.retain(|t| seen.insert(t.clone()));
+            tokens
+        };
+
+        let total_docs = self.docs.len() as f64;
+        let avg_len = if self.doc_lens.is_empty() {
+            1.0
+        } else {
+            (self.doc_lens.iter().map(|&l| l as f64).sum::<f64>() / total_docs).max(1.0)
+        };
+
+        // 文档序号 -> (命中查询词数, 得分)
+        let mut scores: HashMap<usize, (usize, f64)> = HashMap::new();
+        for token in &tokens {
+            let lists: Vec<&Vec<Posting>> = match self.terms.get(token) {
+                Some(postings) => vec![postings],
+                None => self
+                    .terms
+                    .range::<str, _>((std::ops::Bound::Included(token.as_str()), std::ops::Bound::Unbounded))
+                    .take_while(|(term, _)| term.starts_with(token.as_str()))
+                    .map(|(_, postings)| postings)
+                    .collect(),
+            };
+            let mut matched = std::collections::HashSet::new();
+            for postings in lists {
+                let df = postings.len() as f64;
+                let idf = (1.0 + (total_docs - df + 0.5) / (df + 0.5)).ln();
+                for &(doc_id, weight) in postings {
+                    if !self.matches_filters(doc_id, query) {
+                        continue;
+                    }
+                    let tf = weight as f64;
+                    let len = self.doc_lens.get(doc_id).copied().unwrap_or(0) as f64;
+                    let score = idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * len / avg_len));
+                    let entry = scores.entry(doc_id).or_insert((0, 0.0));
+                    entry.1 += score;
+                    if matched.insert(doc_id) {
+                        entry.0 += 1;
+                    }
+                }
+            }
+        }
+
+        let mut ranked: Vec<(usize, (usize, f64))> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            b.1 .0
+                .cmp(&a.1 .0)
+                .then(b.1 .1.partial_cmp(&a.1 .1).unwrap_or(std::cmp::Ordering::Equal))
+                .then(a.0.cmp(&b.0))
+        });
+
+        let total = ranked.len();
+        let hits = ranked
+            .into_iter()
+            // 页码来自查询串，超大页码不能溢出
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .map(|(doc_id, (_, score))| {
+                let doc = &self.docs[doc_id];
+                SearchHit {
+                    title: doc.title.clone(),
+                    url: doc.url.clone(),
+                    date: doc.date.clone(),
+                    tags: doc.tags.clone(),
+                    categories: doc.categories.clone(),
+                    snippet: highlight(&self.snippet(doc_id, &tokens), &tokens),
+                    score,
+                }
+            })
+            .collect();
+
+        SearchResults {
+            query: query.q.clone(),
+            total,
+            page,
+            per_page,
+            total_pages: total.div_ceil(per_page),
+            hits,
+        }
+    }
+
+    /// 结果摘要：摘要片段已含命中词时直接使用，否则截取正文中第一个命中词附近的片段
+    fn snippet(&self, doc_id: usize, tokens: &[String]) -> String {
+        let doc = &self.docs[doc_id];
+        if find_first(&doc.snippet, tokens).is_some() {
+            return doc.snippet.clone();
+        }
+        self.texts
+            .get(doc_id)
+            .and_then(|text| snippet_window(text, tokens))
+            .unwrap_or_else(|| doc.snippet.clone())
+    }
+
+    fn matches_filters(&self, doc_id: usize, query: &SearchQuery) -> bool {
+        let Some(doc) = self.docs.get(doc_id) else {
+            return false;
+        };
+        if let Some(tag) = query.tag.as_deref().filter(|t| !t.is_empty()) {
+            if !doc.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                return false;
+            }
+        }
+        if let Some(category) = query.category.as_deref().map(|c| c.trim_matches('/')).filter(|c| !c.is_empty()) {
+            let path: Vec<&str> = category.split('/').collect();
+            let is_prefix = doc.categories.len() >= path.len()
+                && doc.categories.iter().zip(&path).all(|(a, b)| a == b);
+            // 单个分类名时也匹配任意层级
+            let is_segment = path.len() == 1 && doc.categories.iter().any(|c| c == category);
+            if !is_prefix && !is_segment {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 第一个命中词项（不区分大小写）的字符位置
+fn find_first(text: &str, tokens: &[String]) -> Option<usize> {
+    let lower: Vec<char> = text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect();
+    tokens
+        .iter()
+        .filter_map(|token| {
+            let token: Vec<char> = token.chars().collect();
+            if token.is_empty() || token.len() > lower.len() {
+                return None;
+            }
+            lower.windows(token.len()).position(|w| w == &token[..])
+        })
+        .min()
+}
+
+/// 截取 `text` 中第一个命中词附近最多 [`SNIPPET_CHARS`] 字的片段，前后被截断时加省略号
+fn snippet_window(text: &str, tokens: &[String]) -> Option<String> {
+    let first = find_first(text, tokens)?;
+    let chars: Vec<char> = text.chars().collect();
+    // 命中词前保留少量上下文；靠近结尾时向前补足长度
+    let end = (first.saturating_sub(SNIPPET_CHARS / 4) + SNIPPET_CHARS).min(chars.len());
+    let start = end.saturating_sub(SNIPPET_CHARS);
+    let mut snippet: String = chars[start..end].iter().collect::<String>().trim().to_string();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// 转义文本并用 `<mark>` 标出命中的词项（不区分大小写，相邻命中合并）
+pub fn highlight(text: &str, tokens: &[String]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let mut marked = vec![false; chars.len()];
+    for token in tokens {
+        let token: Vec<char> = token.chars().collect();
+        if token.is_empty() || token.len() > lower.len() {
+            continue;
+        }
+        for start in 0..=lower.len() - token.len() {
+            if lower[start..start + token.len()] == token[..] {
+                marked[start..start + token.len()].iter_mut().for_each(|m| *m = true);
+            }
+        }
+    }
+
+    // 按是否命中分段转义，命中段包上 <mark>
+    let mut html = String::with_capacity(text.len() + 16);
+    let mut start = 0;
+    while start < chars.len() {
+        let end = (start..chars.len()).find(|&i| marked[i] != marked[start]).unwrap_or(chars.len());
+        let run = escape_html(&chars[start..end].iter().collect::<String>());
+        if marked[start] {
+            html.push_str(&format!("<mark>{}</mark>", run));
+        } else {
+            html.push_str(&run);
+        }
+        start = end;
+    }
+    html
+}
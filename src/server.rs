@@ -4,6 +4,8 @@
 
 use crate::config::Config;
 use crate::error::{Error, Result};
+use crate::generator::MemFs;
+use crate::livereload::{self, ErrorEntry, LiveReload, ReloadEvent};
 use crate::plugins;
 use axum::{
     Router,
@@ -39,17 +41,58 @@ impl DevServer {
         md_dir: Option<PathBuf>,
         shutdown: impl std::future::Future<Output = ()> + Send + 'static,
     ) -> Result<()> {
-        let output_dir = output_dir.as_ref().to_path_buf();
-        let addr = SocketAddr::from(([127, 0, 0, 1], port));
-        let state = Arc::new(AppState { md_dir });
+        let app = Self::router(output_dir, config, md_dir, None);
+        Self::listen(port, app, shutdown).await
+    }
+
+    /// 创建路由：插件 API、tweet 发表接口与静态文件；传入 `live` 时挂载实时刷新事件流并向 HTML 注入客户端脚本
+    pub fn router<P: AsRef<std::path::Path>>(
+        output_dir: P,
+        config: Option<&Config>,
+        md_dir: Option<PathBuf>,
+        live: Option<&LiveReload>,
+    ) -> Router {
+        let output_dir = output_dir.as_ref();
 
         // 静态文件服务
-        let static_service = ServeDir::new(&output_dir)
+        let static_service = ServeDir::new(output_dir)
             .not_found_service(ServeFile::new(output_dir.join("index.html")));
 
+        // 静态文件路由放在最后作为 fallback
+        let app = Self::api_routes(output_dir, config, md_dir, live, None).fallback_service(static_service);
+        Self::with_layers(app, live)
+    }
+
+    /// 创建内存文件系统模式的路由：静态文件从 `mem_fs` 读取（按扩展名确定 MIME 类型），其余同 [`router`](Self::router)
+    pub fn memory_router<P: AsRef<std::path::Path>>(
+        mem_fs: MemFs,
+        output_dir: P,
+        config: Option<&Config>,
+        md_dir: Option<PathBuf>,
+        live: Option<&LiveReload>,
+    ) -> Router {
+        let app = Self::api_routes(output_dir.as_ref(), config, md_dir, live, Some(Arc::clone(&mem_fs)))
+            .fallback(move |uri: axum::http::Uri| serve_from_memory(Arc::clone(&mem_fs), uri));
+        Self::with_layers(app, live)
+    }
+
+    /// 插件 API、tweet 发表接口与实时刷新路由；`mem_fs` 为内存文件系统模式下的构建输出
+    fn api_routes(
+        output_dir: &Path,
+        config: Option<&Config>,
+        md_dir: Option<PathBuf>,
+        live: Option<&LiveReload>,
+        mem_fs: Option<MemFs>,
+    ) -> Router {
+        // 项目根为 md_dir 的上级目录；未知 md_dir 时为当前目录
+        let root = md_dir.as_deref().map(crate::utils::project_root).unwrap_or_default();
+        let state = Arc::new(AppState { md_dir });
+
         // 创建路由，自动收集所有插件的 API 路由
         let mut app = if let Some(cfg) = config {
-            plugins::collect_api_routes(cfg)
+            let mut ctx = plugins::ApiContext::new(output_dir, root);
+            ctx.mem_fs = mem_fs;
+            plugins::collect_api_routes(cfg, &ctx)
         } else {
             Router::new()
         };
@@ -66,12 +109,28 @@ impl DevServer {
             }),
         );
 
-        // 静态文件路由放在最后作为 fallback
-        app = app
-            .fallback_service(static_service)
-            .layer(axum::middleware::from_fn(set_no_cache_headers));
+        // 实时刷新：事件流与客户端脚本
+        if let Some(live) = live {
+            app = app.merge(live.routes());
+        }
+        app
+    }
 
-        // 启动服务器
+    /// 挂载中间件：实时刷新时向 HTML 注入客户端脚本，所有响应禁用缓存
+    fn with_layers(mut app: Router, live: Option<&LiveReload>) -> Router {
+        if live.is_some() {
+            app = app.layer(axum::middleware::from_fn(livereload::inject_client));
+        }
+        app.layer(axum::middleware::from_fn(set_no_cache_headers))
+    }
+
+    /// 在本机端口上运行路由
+    async fn listen(
+        port: u16,
+        app: Router,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> Result<()> {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
         let listener = tokio::net::TcpListener::bind(addr)
             .await
             .map_err(|e| Error::Server(format!("无法绑定地址 {}: {}", addr, e)))?;
@@ -92,6 +151,33 @@ impl DevServer {
         config_file: &str,
         open_browser: bool,
         shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> Result<()> {
+        Self::serve_live_with(port, md_dir, output_dir, config_file, open_browser, None, shutdown).await
+    }
+
+    /// 启动实时预览服务器，构建结果只保存在内存中（`serve --in-memory`）
+    ///
+    /// 页面直接从内存文件系统返回，`output_dir` 仅作为虚拟输出根目录，不会被创建或改动。
+    pub async fn serve_live_in_memory<P: AsRef<Path>, Q: AsRef<Path>>(
+        port: u16,
+        md_dir: P,
+        output_dir: Q,
+        config_file: &str,
+        open_browser: bool,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> Result<()> {
+        let mem_fs = MemFs::default();
+        Self::serve_live_with(port, md_dir, output_dir, config_file, open_browser, Some(mem_fs), shutdown).await
+    }
+
+    async fn serve_live_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        port: u16,
+        md_dir: P,
+        output_dir: Q,
+        config_file: &str,
+        open_browser: bool,
+        mem_fs: Option<MemFs>,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
     ) -> Result<()> {
         let md_dir = md_dir.as_ref().to_path_buf();
         let output_dir = output_dir.as_ref().to_path_buf();
@@ -100,13 +186,16 @@ impl DevServer {
         // 1. 初始化配置与构建
         let config_path = crate::utils::resolve_config_toml_path_read(&md_dir, &config_file_owned);
         let config = Config::from_file(&config_path)?;
-        let generator = crate::generator::Generator::new(config.clone(), &md_dir)?;
+        let mut generator = crate::generator::Generator::new(config.clone(), &md_dir)?;
+        if let Some(mem_fs) = &mem_fs {
+            generator = generator.with_mem_fs(Arc::clone(mem_fs));
+        }
         
         // 首次构建
         generator.build(&md_dir, &output_dir)?;
 
-        // 2. 准备文件监听
-        let (tx, mut rx) = mpsc::channel(100);
+        // 2. 准备文件监听（传递变化的路径，用于判断是否只需热替换 CSS）
+        let (tx, mut rx) = mpsc::channel::<Vec<PathBuf>>(100);
         let tx_clone = tx.clone();
         
         let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
@@ -118,7 +207,7 @@ impl DevServer {
                 if event.paths.iter().any(|p| p.file_name().and_then(|n| n.to_str()) == Some("build.toml")) {
                     return;
                 }
-                let _ = tx_clone.blocking_send(());
+                let _ = tx_clone.blocking_send(event.paths);
             }
         }).map_err(|e| Error::Server(format!("无法初始化监听器: {}", e)))?;
 
@@ -135,6 +224,18 @@ impl DevServer {
             watcher.watch(&runtime_paths.theme_templates_dir, RecursiveMode::Recursive)
                 .map_err(|e| Error::Server(format!("监听模板目录失败: {}", e)))?;
         }
+        // 监听主题静态目录（若存在），其中的 CSS 变化只需热替换样式表
+        let theme_static_dir = runtime_paths.theme_static_dir.clone();
+        if theme_static_dir.exists() {
+            watcher.watch(&theme_static_dir, RecursiveMode::Recursive)
+                .map_err(|e| Error::Server(format!("监听主题静态目录失败: {}", e)))?;
+        }
+        // 复制到输出目录根的源目录（监听器报告的路径可能是绝对路径，两种形式都保留）
+        let css_roots: Vec<PathBuf> = [&theme_static_dir, &md_dir]
+            .into_iter()
+            .flat_map(|dir| [dir.clone(), dir.canonicalize().unwrap_or_else(|_| dir.clone())])
+            .collect();
+        let live = LiveReload::new();
 
         // 3. 并行运行服务与监听循环
         println!("预览服务器正在运行，已开启实时热重载 (Hot Reload)...");
@@ -154,34 +255,68 @@ impl DevServer {
             });
         }
         
+        let app = match &mem_fs {
+            Some(mem_fs) => Self::memory_router(Arc::clone(mem_fs), &output_dir, Some(&config), Some(md_dir.clone()), Some(&live)),
+            None => Self::router(&output_dir, Some(&config), Some(md_dir.clone()), Some(&live)),
+        };
         tokio::select! {
-            res = Self::serve(port, &output_dir, Some(&config), Some(md_dir.clone()), shutdown) => res,
+            res = Self::listen(port, app, shutdown) => res,
             _ = async {
-                while let Some(_) = rx.recv().await {
+                while let Some(mut changed) = rx.recv().await {
                     tokio::time::sleep(Duration::from_millis(200)).await;
-                    while let Ok(_) = rx.try_recv() {}
+                    while let Ok(paths) = rx.try_recv() {
+                        changed.extend(paths);
+                    }
                     
                     println!("检测到内容或模板变动，正在自动重构...");
-                    if let Ok(new_cfg) = Config::from_file(&config_path) {
-                        if let Ok(generator_inc) = crate::generator::Generator::new(new_cfg, &md_dir) {
-                            if let Err(e) = generator_inc.build_incremental(&md_dir, &output_dir) {
-                                eprintln!("自动重构失败: {}", e);
-                            } else {
-                                let _ = crate::utils::log_build_info(&md_dir);
-                                println!("自动重构完成！");
-                            }
+                    let event = Self::rebuild(&config_path, &md_dir, &output_dir, mem_fs.as_ref()).unwrap_or_else(|errors| {
+                        for error in &errors {
+                            eprintln!("自动重构失败: {} {}", error.file, error.message);
                         }
-                    }
+                        ReloadEvent::Error { errors }
+                    });
+                    // 只有 CSS 变化时热替换样式表，其余情况刷新页面
+                    let event = match event {
+                        ReloadEvent::Reload => {
+                            let roots: Vec<&Path> = css_roots.iter().map(PathBuf::as_path).collect();
+                            livereload::css_only_changes(&changed, &roots)
+                                .map_or(ReloadEvent::Reload, |paths| ReloadEvent::Css { paths })
+                        }
+                        other => other,
+                    };
+                    live.send(event);
                 }
             } => Ok(()),
         }
     }
 
+    /// 重新读取配置并增量构建；失败（含文章解析、模板渲染失败）时返回错误浮层条目
+    fn rebuild(config_path: &Path, md_dir: &Path, output_dir: &Path, mem_fs: Option<&MemFs>) -> std::result::Result<ReloadEvent, Vec<ErrorEntry>> {
+        let file = |path: &Path| path.display().to_string();
+        let config = Config::from_file(config_path).map_err(|e| vec![ErrorEntry::new(file(config_path), e.to_string())])?;
+        let mut generator = crate::generator::Generator::new(config, md_dir)
+            .map_err(|e| vec![ErrorEntry::new("", e.to_string())])?;
+        if let Some(mem_fs) = mem_fs {
+            generator = generator.with_mem_fs(Arc::clone(mem_fs));
+        }
+        generator
+            .build_incremental(md_dir, output_dir)
+            .map_err(|e| vec![ErrorEntry::new("", e.to_string())])?;
+        let _ = crate::utils::log_build_info(md_dir);
+        let errors = livereload::blocking_issues(&generator.issues());
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        println!("自动重构完成！");
+        Ok(ReloadEvent::Reload)
+    }
+
     /// 同步启动服务器（用于阻塞调用）
     pub fn serve_sync<P: AsRef<std::path::Path>>(
         port: u16,
         output_dir: P,
         config: Option<&Config>,
+        md_dir: Option<PathBuf>,
     ) -> Result<()> {
         let output_dir = output_dir.as_ref().to_path_buf();
         let config_owned = config.cloned();
@@ -191,7 +326,7 @@ impl DevServer {
             .build()
             .map_err(|e| Error::Server(format!("无法创建异步运行时: {}", e)))?;
 
-        rt.block_on(Self::serve(port, output_dir, config_owned.as_ref(), None, std::future::pending()))
+        rt.block_on(Self::serve(port, output_dir, config_owned.as_ref(), md_dir, std::future::pending()))
     }
 }
 
@@ -214,6 +349,41 @@ async fn set_no_cache_headers(
     response
 }
 
+/// 从内存文件系统返回静态文件：目录地址返回其中的 index.html，不带 `/` 的目录地址重定向，
+/// 找不到时以 404 状态返回首页（与磁盘模式的 `ServeDir` 行为一致）
+async fn serve_from_memory(mem_fs: MemFs, uri: axum::http::Uri) -> axum::response::Response {
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Redirect};
+
+    let path = crate::report::percent_decode(uri.path());
+    let files = mem_fs.read().unwrap();
+    let key = if path.ends_with('/') { format!("{}index.html", path) } else { path.clone() };
+    if let Some(content) = files.get(&key) {
+        return memory_file_response(&key, content.clone(), StatusCode::OK);
+    }
+    if files.contains_key(&format!("{}/index.html", path)) {
+        let query = uri.query().map(|q| format!("?{}", q)).unwrap_or_default();
+        return Redirect::temporary(&format!("{}/{}", uri.path(), query)).into_response();
+    }
+    match files.get("/index.html") {
+        Some(content) => memory_file_response("/index.html", content.clone(), StatusCode::NOT_FOUND),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn memory_file_response(key: &str, content: Vec<u8>, status: axum::http::StatusCode) -> axum::response::Response {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let mime = mime_guess::from_path(key).first_or_octet_stream();
+    let content_type = if mime.type_() == mime_guess::mime::TEXT {
+        format!("{}; charset=utf-8", mime.essence_str())
+    } else {
+        mime.essence_str().to_string()
+    };
+    (status, [(header::CONTENT_TYPE, content_type)], content).into_response()
+}
+
 /// 处理发表 tweet
 async fn handle_post_tweet(
     mut multipart: Multipart,
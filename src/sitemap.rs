@@ -0,0 +1,222 @@
+//! Sitemap 模块
+//!
+//! 生成符合 sitemaps.org 协议的 `sitemap.xml`：文章带 `<lastmod>`，可选输出 `<image:image>`。
+//! 单个文件超过 50,000 条 URL 或 50 MB 时自动拆分为 `sitemap-1.xml`、`sitemap-2.xml` …，
+//! 并生成 `sitemap_index.xml` 作为入口。
+//!
+//! front matter 中 `sitemap: false` 或 `noindex: true` 的文章不会出现在 Sitemap 中。
+
+use crate::config::Config;
+use crate::feed::{self, absolute_url, escape_xml};
+use crate::post::Post;
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat, TimeZone};
+use serde_json::Value;
+
+/// 协议规定的单个 Sitemap 最大 URL 数
+pub const MAX_URLS: usize = 50_000;
+/// 协议规定的单个 Sitemap 最大体积（未压缩）
+pub const MAX_BYTES: usize = 50 * 1024 * 1024;
+/// 未拆分时的文件名
+pub const SITEMAP_FILE: &str = "sitemap.xml";
+/// 拆分后的索引文件名
+pub const INDEX_FILE: &str = "sitemap_index.xml";
+
+const URLSET_OPEN: &str = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n";
+const URLSET_OPEN_IMAGES: &str = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:image=\"http://www.google.com/schemas/sitemap-image/1.1\">\n";
+const URLSET_CLOSE: &str = "</urlset>\n";
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+/// Sitemap 选项（`[sitemap]`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapOptions {
+    /// 是否根据 `cover`/`images` 输出图片条目
+    pub images: bool,
+    /// 单个文件最大 URL 数（不超过协议上限）
+    pub max_urls: usize,
+    /// 单个文件最大字节数（不超过协议上限）
+    pub max_bytes: usize,
+}
+
+impl Default for SitemapOptions {
+    fn default() -> Self {
+        Self { images: false, max_urls: MAX_URLS, max_bytes: MAX_BYTES }
+    }
+}
+
+impl SitemapOptions {
+    pub fn from_config(config: &Config) -> Self {
+        let section = config.data.get("sitemap");
+        let limit = |key: &str, max: usize| {
+            section
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_integer())
+                .filter(|n| *n > 0)
+                .map(|n| (n as usize).min(max))
+                .unwrap_or(max)
+        };
+        Self {
+            images: section
+                .and_then(|v| v.get("images"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            max_urls: limit("max_urls", MAX_URLS),
+            max_bytes: limit("max_bytes", MAX_BYTES),
+        }
+    }
+}
+
+/// 单条 URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<DateTime<FixedOffset>>,
+    /// 图片的绝对地址
+    pub images: Vec<String>,
+}
+
+impl SitemapEntry {
+    /// 列表页等无修改时间的页面
+    pub fn new(loc: String) -> Self {
+        Self { loc, lastmod: None, images: Vec::new() }
+    }
+
+    /// 文章页：带最后修改时间，按选项附带图片
+    pub fn from_post(post: &Post, base_url: &str, options: &SitemapOptions) -> Self {
+        Self {
+            loc: format!("{}{}", base_url, post.url().unwrap_or("")),
+            lastmod: Some(lastmod(post)),
+            images: if options.images { post_images(post, base_url) } else { Vec::new() },
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        let mut xml = format!("  <url>\n    <loc>{}</loc>\n", escape_xml(&self.loc));
+        if let Some(lastmod) = self.lastmod {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", w3c_datetime(lastmod)));
+        }
+        for image in &self.images {
+            xml.push_str(&format!(
+                "    <image:image><image:loc>{}</image:loc></image:image>\n",
+                escape_xml(image)
+            ));
+        }
+        xml.push_str("  </url>\n");
+        xml
+    }
+}
+
+/// 输出文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapFile {
+    pub name: String,
+    pub content: String,
+}
+
+/// 文章是否排除在 Sitemap 之外（`sitemap: false` 或 `noindex: true`）
+pub fn is_excluded(post: &Post) -> bool {
+    flag(post.data.get("sitemap")) == Some(false) || flag(post.data.get("noindex")) == Some(true)
+}
+
+fn flag(value: Option<&Value>) -> Option<bool> {
+    match value? {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 文章最后修改时间：优先 front matter `updated`，否则取文件修改时间与发布时间中较晚者
+pub fn lastmod(post: &Post) -> DateTime<FixedOffset> {
+    if let Some(updated) = post
+        .data
+        .get("updated")
+        .and_then(|v| v.as_str())
+        .and_then(feed::parse_local_datetime)
+    {
+        return updated;
+    }
+    let published = feed::published_at(post);
+    post.modified_epoch()
+        .filter(|epoch| *epoch > 0)
+        .and_then(|epoch| Local.timestamp_opt(epoch, 0).single())
+        .map(|dt| dt.fixed_offset())
+        .filter(|dt| *dt > published)
+        .unwrap_or(published)
+}
+
+/// 文章图片：`cover` 与 `images`（解析阶段已将相对路径转为站内路径）
+pub fn post_images(post: &Post, base_url: &str) -> Vec<String> {
+    let mut paths: Vec<&str> = Vec::new();
+    for key in ["cover", "images"] {
+        match post.data.get(key) {
+            Some(Value::String(s)) => paths.push(s),
+            Some(Value::Array(items)) => paths.extend(items.iter().filter_map(|v| v.as_str())),
+            _ => {}
+        }
+    }
+
+    let mut images: Vec<String> = Vec::new();
+    for path in paths.into_iter().map(str::trim).filter(|p| !p.is_empty()) {
+        let image = absolute_url(base_url, path);
+        if !images.contains(&image) {
+            images.push(image);
+        }
+    }
+    images
+}
+
+/// 生成 Sitemap 文件：未超限时只有 `sitemap.xml`；
+/// 超限时第一个文件为 `sitemap_index.xml`，其后为各分片
+pub fn render(entries: &[SitemapEntry], options: &SitemapOptions, base_url: &str) -> Vec<SitemapFile> {
+    let open = if options.images { URLSET_OPEN_IMAGES } else { URLSET_OPEN };
+    let overhead = XML_DECLARATION.len() + open.len() + URLSET_CLOSE.len();
+
+    // 按数量与体积切分
+    let mut chunks: Vec<(String, Option<DateTime<FixedOffset>>)> = Vec::new();
+    let mut body = String::new();
+    let mut count = 0;
+    let mut latest: Option<DateTime<FixedOffset>> = None;
+    for entry in entries {
+        let xml = entry.to_xml();
+        if count > 0 && (count >= options.max_urls || overhead + body.len() + xml.len() > options.max_bytes) {
+            chunks.push((std::mem::take(&mut body), latest.take()));
+            count = 0;
+        }
+        body.push_str(&xml);
+        count += 1;
+        latest = latest.max(entry.lastmod);
+    }
+    if count > 0 || chunks.is_empty() {
+        chunks.push((body, latest));
+    }
+
+    let urlset = |body: &str| format!("{}{}{}{}", XML_DECLARATION, open, body, URLSET_CLOSE);
+    if chunks.len() == 1 {
+        return vec![SitemapFile { name: SITEMAP_FILE.to_string(), content: urlset(&chunks[0].0) }];
+    }
+
+    let mut index = String::from(XML_DECLARATION);
+    index.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    let mut files = Vec::with_capacity(chunks.len() + 1);
+    for (i, (body, latest)) in chunks.iter().enumerate() {
+        let name = format!("sitemap-{}.xml", i + 1);
+        index.push_str(&format!("  <sitemap>\n    <loc>{}</loc>\n", escape_xml(&absolute_url(base_url, &name))));
+        if let Some(latest) = latest {
+            index.push_str(&format!("    <lastmod>{}</lastmod>\n", w3c_datetime(*latest)));
+        }
+        index.push_str("  </sitemap>\n");
+        files.push(SitemapFile { name, content: urlset(body) });
+    }
+    index.push_str("</sitemapindex>\n");
+    files.insert(0, SitemapFile { name: INDEX_FILE.to_string(), content: index });
+    files
+}
+
+/// W3C Datetime（如 `2024-05-01T10:30:00+08:00`）
+fn w3c_datetime(dt: DateTime<FixedOffset>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
@@ -4,6 +4,8 @@
 
 use crate::config::Config;
 use crate::error::{Error, Result};
+use crate::feed;
+use crate::front_matter::Layout;
 use crate::plugins;
 use crate::post::Post;
 use crate::utils::{RuntimePathsBuilder, ThemeTemplates};
@@ -418,6 +420,11 @@ impl TemplateEngine {
             site_config.insert("analytics".to_string(), Self::toml_to_json(analytics));
         }
 
+        // 合并代码高亮配置到 site.highlight（模板据此引用 highlight.css）
+        if let Some(highlight) = self.config.data.get("highlight") {
+            site_config.insert("highlight".to_string(), Self::toml_to_json(highlight));
+        }
+
         // 合并评论（giscus）配置到 site.comments
         if let Some(comments) = self.config.data.get("comments") {
             site_config.insert("comments".to_string(), Self::toml_to_json(comments));
@@ -826,6 +833,14 @@ impl TemplateEngine {
                         // 寻找书籍首页 (README.md -> index) 以获取书籍名称
                         let index_post = all_book_posts.iter().find(|p| p.slug() == Some("index"));
                         book_display_name = index_post.and_then(|p| p.title()).unwrap_or(book_id).to_string();
+                        context.insert(
+                            "feed_links",
+                            &feed::alternate_links(
+                                &self.config,
+                                &feed::scoped_dir(&[&current_cats[0], book_id]),
+                                &book_display_name,
+                            ),
+                        );
                         
                         let mut catalog_order: Vec<(String, usize, String)> = Vec::new();
                         let mut has_catalog = false;
@@ -894,8 +909,8 @@ impl TemplateEngine {
                                 if na != nb {
                                     na.cmp(&nb)
                                 } else {
-                                    let wa = a.data.get("weight").and_then(|v| v.as_i64()).unwrap_or(i64::MAX);
-                                    let wb = b.data.get("weight").and_then(|v| v.as_i64()).unwrap_or(i64::MAX);
+                                    let wa = a.weight().unwrap_or(i64::MAX);
+                                    let wb = b.weight().unwrap_or(i64::MAX);
                                     if wa != wb {
                                         wa.cmp(&wb)
                                     } else {
@@ -991,7 +1006,7 @@ impl TemplateEngine {
                     if layout == "friends" {
                         // 从所有 posts 中收集 layout: friend 的页面作为友链列表，按 weight 正序排列
                         let mut friend_posts: Vec<Value> = all_posts.iter()
-                            .filter(|p| p.data.get("layout").and_then(|v| v.as_str()) == Some("friend"))
+                            .filter(|p| p.has_layout(Layout::Friend))
                             .map(|p| p.data.clone())
                             .collect();
                         friend_posts.sort_by(|a, b| {
@@ -1011,6 +1026,10 @@ impl TemplateEngine {
                         
                         let all_categories = crate::post::PostParser::generate_hierarchical_categories(all_posts);
                         context.insert("all_categories", &all_categories);
+
+                        let (total_words, total_minutes) = crate::reading::site_totals(all_posts);
+                        context.insert("total_word_count", &total_words);
+                        context.insert("total_reading_time_minutes", &total_minutes);
                     }
                 }
                 "columns" => {
@@ -1135,7 +1154,7 @@ impl TemplateEngine {
                     let mut works_list: Vec<&Post> = all_posts.iter()
                         .filter(|p| {
                             let c = p.categories();
-                            c.len() == 1 && c[0] == "works" && p.data.get("layout").and_then(|v| v.as_str()) == Some("work")
+                            c.len() == 1 && c[0] == "works" && p.has_layout(Layout::Work)
                         })
                         .collect();
 
@@ -1197,7 +1216,7 @@ impl TemplateEngine {
             if cats.first().map(|c| c == "docs").unwrap_or(false)
                 && cats.len() == 2
                 && post.slug() == Some("index")
-                && post.data.get("layout").and_then(|v| v.as_str()) == Some("doc")
+                && post.has_layout(Layout::Doc)
             {
                 books.push(post.data.clone());
             }
@@ -1213,7 +1232,7 @@ impl TemplateEngine {
             if cats.first().map(|c| c == "columns").unwrap_or(false)
                 && cats.len() == 2
                 && post.slug() == Some("index")
-                && post.data.get("layout").and_then(|v| v.as_str()) == Some("columns")
+                && post.has_layout(Layout::Columns)
             {
                 columns.push(post.data.clone());
             }
@@ -1233,39 +1252,37 @@ impl TemplateEngine {
     fn get_project_posts(&self, posts: &[Post]) -> Vec<serde_json::Value> {
         let mut projects: Vec<serde_json::Value> = Vec::new();
         for post in posts {
-            if let Some(layout) = post.data.get("layout").and_then(|v| v.as_str()) {
-                if layout == "project" {
-                    let mut project = serde_json::Map::new();
-                    if let Some(title) = post.data.get("title") {
-                        project.insert("title".to_string(), title.clone());
-                    }
-                    if let Some(version) = post.data.get("version") {
-                        project.insert("version".to_string(), version.clone());
-                    }
-                    if let Some(desc) = post.data.get("description") {
-                        project.insert("description".to_string(), desc.clone());
-                    }
-                    if let Some(icon) = post.data.get("icon") {
-                        let (is_img, icon_url) = Self::process_icon_value(icon);
-                        project.insert("icon".to_string(), icon.clone());
-                        project.insert("icon_url".to_string(), serde_json::Value::String(icon_url));
-                        project.insert("is_img".to_string(), serde_json::Value::Bool(is_img));
-                    }
-                    if let Some(tags) = post.data.get("tags") {
-                        project.insert("tags".to_string(), tags.clone());
-                    }
-                    if let Some(slug) = post.slug() {
-                        // 构造与 generator 一致的输出路径
-                        let categories = post.categories();
-                        let path = if categories.is_empty() {
-                            slug.to_string()
-                        } else {
-                            format!("{}/{}", categories.join("/"), slug)
-                        };
-                        project.insert("slug".to_string(), serde_json::Value::String(path));
-                    }
-                    projects.push(serde_json::Value::Object(project));
+            if post.has_layout(Layout::Project) {
+                let mut project = serde_json::Map::new();
+                if let Some(title) = post.data.get("title") {
+                    project.insert("title".to_string(), title.clone());
+                }
+                if let Some(version) = post.data.get("version") {
+                    project.insert("version".to_string(), version.clone());
+                }
+                if let Some(desc) = post.data.get("description") {
+                    project.insert("description".to_string(), desc.clone());
+                }
+                if let Some(icon) = post.data.get("icon") {
+                    let (is_img, icon_url) = Self::process_icon_value(icon);
+                    project.insert("icon".to_string(), icon.clone());
+                    project.insert("icon_url".to_string(), serde_json::Value::String(icon_url));
+                    project.insert("is_img".to_string(), serde_json::Value::Bool(is_img));
+                }
+                if let Some(tags) = post.data.get("tags") {
+                    project.insert("tags".to_string(), tags.clone());
+                }
+                if let Some(slug) = post.slug() {
+                    // 构造与 generator 一致的输出路径
+                    let categories = post.categories();
+                    let path = if categories.is_empty() {
+                        slug.to_string()
+                    } else {
+                        format!("{}/{}", categories.join("/"), slug)
+                    };
+                    project.insert("slug".to_string(), serde_json::Value::String(path));
                 }
+                projects.push(serde_json::Value::Object(project));
             }
         }
         projects
@@ -1423,6 +1440,10 @@ impl TemplateEngine {
 
         context.insert("posts", &page_posts);
         context.insert("tag_name", &tag_name);
+        context.insert(
+            "feed_links",
+            &feed::alternate_links(&self.config, &feed::scoped_dir(&["tags", tag_name]), tag_name),
+        );
         context.insert("paginator", &paginator);
 
         self.tera
@@ -1519,7 +1540,7 @@ impl TemplateEngine {
         let works_count = posts.iter()
             .filter(|p| {
                 let c = p.categories();
-                c.len() == 1 && c[0] == "works" && p.data.get("layout").and_then(|v| v.as_str()) == Some("work")
+                c.len() == 1 && c[0] == "works" && p.has_layout(Layout::Work)
             })
             .count();
         context.insert("works_count", &works_count);
@@ -1528,6 +1549,11 @@ impl TemplateEngine {
         let columns_list = self.get_columns(posts);
         context.insert("columns_count", &columns_list.len());
 
+        // 全站字数与阅读时长
+        let (total_words, total_minutes) = crate::reading::site_totals(posts);
+        context.insert("total_word_count", &total_words);
+        context.insert("total_reading_time_minutes", &total_minutes);
+
         // 读取并解析 about.md，注入页面内容与 frontmatter
         let possible_about_paths = [
             self.content_dir.join("about.md"),
@@ -1572,39 +1598,37 @@ impl TemplateEngine {
         // 筛选 layout 为 project 的文章，提取关键信息
         let mut projects: Vec<serde_json::Value> = Vec::new();
         for post in posts {
-            if let Some(layout) = post.data.get("layout").and_then(|v| v.as_str()) {
-                if layout == "project" {
-                    let mut project = serde_json::Map::new();
-                    if let Some(title) = post.data.get("title") {
-                        project.insert("title".to_string(), title.clone());
-                    }
-                    if let Some(version) = post.data.get("version") {
-                        project.insert("version".to_string(), version.clone());
-                    }
-                    if let Some(desc) = post.data.get("description") {
-                        project.insert("description".to_string(), desc.clone());
-                    }
-                    if let Some(icon) = post.data.get("icon") {
-                        let (is_img, icon_url) = Self::process_icon_value(icon);
-                        project.insert("icon".to_string(), icon.clone());
-                        project.insert("icon_url".to_string(), serde_json::Value::String(icon_url));
-                        project.insert("is_img".to_string(), serde_json::Value::Bool(is_img));
-                    }
-                    if let Some(tags) = post.data.get("tags") {
-                        project.insert("tags".to_string(), tags.clone());
-                    }
-                    if let Some(slug) = post.slug() {
-                        // 构造与 generator 一致的输出路径
-                        let categories = post.categories();
-                        let path = if categories.is_empty() {
-                            slug.to_string()
-                        } else {
-                            format!("{}/{}", categories.join("/"), slug)
-                        };
-                        project.insert("slug".to_string(), serde_json::Value::String(path));
-                    }
-                    projects.push(serde_json::Value::Object(project));
+            if post.has_layout(Layout::Project) {
+                let mut project = serde_json::Map::new();
+                if let Some(title) = post.data.get("title") {
+                    project.insert("title".to_string(), title.clone());
                 }
+                if let Some(version) = post.data.get("version") {
+                    project.insert("version".to_string(), version.clone());
+                }
+                if let Some(desc) = post.data.get("description") {
+                    project.insert("description".to_string(), desc.clone());
+                }
+                if let Some(icon) = post.data.get("icon") {
+                    let (is_img, icon_url) = Self::process_icon_value(icon);
+                    project.insert("icon".to_string(), icon.clone());
+                    project.insert("icon_url".to_string(), serde_json::Value::String(icon_url));
+                    project.insert("is_img".to_string(), serde_json::Value::Bool(is_img));
+                }
+                if let Some(tags) = post.data.get("tags") {
+                    project.insert("tags".to_string(), tags.clone());
+                }
+                if let Some(slug) = post.slug() {
+                    // 构造与 generator 一致的输出路径
+                    let categories = post.categories();
+                    let path = if categories.is_empty() {
+                        slug.to_string()
+                    } else {
+                        format!("{}/{}", categories.join("/"), slug)
+                    };
+                    project.insert("slug".to_string(), serde_json::Value::String(path));
+                }
+                projects.push(serde_json::Value::Object(project));
             }
         }
 
@@ -1620,7 +1644,7 @@ impl TemplateEngine {
 
         // 从所有 posts 中收集 layout: friend 的页面作为友链列表，按 weight 正序排列
         let mut friend_posts: Vec<Value> = all_posts.iter()
-            .filter(|p| p.data.get("layout").and_then(|v| v.as_str()) == Some("friend"))
+            .filter(|p| p.has_layout(Layout::Friend))
             .map(|p| p.data.clone())
             .collect();
         friend_posts.sort_by(|a, b| {
@@ -1779,6 +1803,11 @@ impl TemplateEngine {
         context.insert("posts", &page_posts);
         context.insert("category_name", &category_name);
         context.insert("paginator", &paginator);
+        let segments: Vec<&str> = category_path.iter().map(String::as_str).collect();
+        context.insert(
+            "feed_links",
+            &feed::alternate_links(&self.config, &feed::scoped_dir(&segments), &category_name),
+        );
 
         self.tera
             .render("category.html", &context)
@@ -0,0 +1,122 @@
+//! 文章目录（TOC）模块
+//!
+//! Markdown 渲染时通过 comrak 的标题插件为每个标题生成稳定的锚点 id，并同时收集目录条目。
+//! 锚点规则：转小写，保留字母、数字（含中文）与 `-`、`_`，空白转为 `-`；
+//! 重复标题依次追加 `-1`、`-2` 后缀，只含标点的标题回退为 `section`。
+
+use comrak::adapters::{HeadingAdapter, HeadingMeta};
+use comrak::nodes::Sourcepos;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Mutex;
+
+/// 按文档顺序收集的标题：(层级, 文本, 锚点)
+pub type Heading = (u8, String, String);
+
+/// 目录条目（按标题层级嵌套）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TocEntry {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+    pub children: Vec<TocEntry>,
+}
+
+/// 将标题文本转换为锚点（不含去重）
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if (c == '-' || c.is_whitespace()) && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// 同一文档内的锚点去重
+#[derive(Debug, Default)]
+pub struct Slugger {
+    used: HashSet<String>,
+}
+
+impl Slugger {
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = slugify(text);
+        let mut anchor = base.clone();
+        let mut n = 0;
+        while self.used.contains(&anchor) {
+            n += 1;
+            anchor = format!("{}-{}", base, n);
+        }
+        self.used.insert(anchor.clone());
+        anchor
+    }
+}
+
+/// 将平铺的标题列表按层级组装为嵌套目录
+pub fn build_tree(flat: Vec<Heading>) -> Vec<TocEntry> {
+    fn attach(nodes: &mut Vec<TocEntry>, entry: TocEntry) {
+        match nodes.last_mut() {
+            Some(last) if entry.level > last.level => attach(&mut last.children, entry),
+            _ => nodes.push(entry),
+        }
+    }
+    let mut roots = Vec::new();
+    for (level, text, anchor) in flat {
+        attach(&mut roots, TocEntry { level, text, anchor, children: Vec::new() });
+    }
+    roots
+}
+
+/// 渲染标题（带 id）并记录目录条目的 comrak 插件，每次渲染使用一个新实例
+#[derive(Default)]
+pub(crate) struct HeadingCollector {
+    state: Mutex<(Slugger, Vec<Heading>)>,
+    /// 锚点前缀（`[markdown] header_id_prefix`）
+    prefix: String,
+    /// 为 false 时标题不带 id（`[markdown] header_ids = false`）
+    no_ids: bool,
+}
+
+impl HeadingCollector {
+    pub(crate) fn new(ids: bool, prefix: &str) -> Self {
+        HeadingCollector {
+            prefix: prefix.to_string(),
+            no_ids: !ids,
+            ..HeadingCollector::default()
+        }
+    }
+
+    /// 取出按文档顺序排列的标题
+    pub(crate) fn into_headings(self) -> Vec<Heading> {
+        self.state.into_inner().map(|(_, headings)| headings).unwrap_or_default()
+    }
+}
+
+impl HeadingAdapter for HeadingCollector {
+    fn enter(&self, output: &mut dyn fmt::Write, heading: &HeadingMeta, _sourcepos: Option<Sourcepos>) -> fmt::Result {
+        let mut state = self.state.lock().map_err(|_| fmt::Error)?;
+        let text = heading.content.trim().to_string();
+        if self.no_ids {
+            write!(output, "<h{}>", heading.level)?;
+            state.1.push((heading.level, text, String::new()));
+            return Ok(());
+        }
+        let anchor = format!("{}{}", self.prefix, state.0.slug(&text));
+        write!(output, "<h{} id=\"{}\">", heading.level, anchor)?;
+        state.1.push((heading.level, text, anchor));
+        Ok(())
+    }
+
+    fn exit(&self, output: &mut dyn fmt::Write, heading: &HeadingMeta) -> fmt::Result {
+        writeln!(output, "</h{}>", heading.level)
+    }
+}
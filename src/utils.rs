@@ -155,6 +155,11 @@ pub fn resolve_build_toml_path_write<P: AsRef<std::path::Path>>(md_dir: P) -> st
     }
 }
 
+/// 项目根目录：md_dir 的上级目录（缓存、密钥文件等按它定位，与当前工作目录无关）
+pub fn project_root(md_dir: &Path) -> std::path::PathBuf {
+    md_dir.parent().unwrap_or(Path::new(".")).to_path_buf()
+}
+
 /// 解析 config.toml 的读取路径：优先 `md_dir/<config_filename>`，否则回退到项目根 `<config_filename>`
 pub fn resolve_config_toml_path_read<P: AsRef<std::path::Path>>(
     md_dir: P,
@@ -229,6 +234,22 @@ pub fn strip_html_tags(html: &str) -> String {
     result.split_whitespace().collect::<Vec<&str>>().join(" ")
 }
 
+/// 转义 HTML 特殊字符，结果可用于正文与属性值
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
 /// 获取 npm 命令名称（跨平台）
 pub fn get_npm_command() -> &'static str {
     if cfg!(target_os = "windows") {
@@ -589,42 +610,38 @@ pub fn copy_non_md_recursive_preserve_paths<P: AsRef<Path>, Q: AsRef<Path>>(
             .map_err(|e| Error::Other(format!("无法创建输出目录 {:?}: {}", output_dir, e)))?;
     }
 
-    for entry in WalkDir::new(md_dir).into_iter().filter_map(|e| e.ok()) {
-        let src_path = entry.path();
-        if src_path.is_file() {
-            // 跳过隐藏文件（文件名以 '.' 开头）
-            let name = src_path
-                .file_name()
-                .map(|s| s.to_string_lossy())
-                .unwrap_or(std::borrow::Cow::Borrowed(""));
-            if name.starts_with('.') {
-                continue;
-            }
-            // 跳过 Markdown 文件
-            if src_path.extension().map_or(false, |ext| ext == "md") {
-                continue;
-            }
-
-            // 计算相对路径并复制
-            let rel = src_path
-                .strip_prefix(md_dir)
-                .map_err(|e| Error::Other(format!("无法计算相对路径 {:?}: {}", src_path, e)))?;
-            let dst_path = output_dir.join(rel);
-            if let Some(parent) = dst_path.parent() {
-                fs::create_dir_all(parent)
-                    .map_err(|e| Error::Other(format!("无法创建父目录 {:?}: {}", parent, e)))?;
-            }
-            fs::copy(src_path, &dst_path).map_err(|e| {
-                Error::Other(format!(
-                    "无法复制文件 {:?} -> {:?}: {}",
-                    src_path, dst_path, e
-                ))
-            })?;
+    for src_path in non_md_files(md_dir) {
+        // 计算相对路径并复制
+        let rel = src_path
+            .strip_prefix(md_dir)
+            .map_err(|e| Error::Other(format!("无法计算相对路径 {:?}: {}", src_path, e)))?;
+        let dst_path = output_dir.join(rel);
+        if let Some(parent) = dst_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| Error::Other(format!("无法创建父目录 {:?}: {}", parent, e)))?;
         }
+        fs::copy(&src_path, &dst_path).map_err(|e| {
+            Error::Other(format!(
+                "无法复制文件 {:?} -> {:?}: {}",
+                src_path, dst_path, e
+            ))
+        })?;
     }
     Ok(())
 }
 
+/// 列出 `md_dir` 下需要原样复制到输出目录的文件：跳过以 '.' 开头的隐藏文件与所有 .md 文件
+pub fn non_md_files<P: AsRef<Path>>(md_dir: P) -> Vec<std::path::PathBuf> {
+    WalkDir::new(md_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter(|e| e.path().extension().is_none_or(|ext| ext != "md"))
+        .map(|e| e.into_path())
+        .collect()
+}
+
 /// 将打包在二进制中的主题静态资源写出到输出目录（覆盖写出）
 pub fn write_embedded_theme_static<P: AsRef<Path>>(output_dir: P) -> Result<()> {
     use std::fs;
@@ -944,7 +961,7 @@ pub fn init_source_dir<P: AsRef<Path>>(md_dir: P, config_filename: &str) -> Resu
     // 4. 生成 .gitignore
     let gitignore_path = root_dir.join(".gitignore");
     if !gitignore_path.exists() {
-        let gitignore_content = "node_modules\n.DS_Store\n.pushpen/\npublic\n";
+        let gitignore_content = "node_modules\n.DS_Store\n.pushpen/\npublic\ndoc_keys.json\n";
         std::fs::write(&gitignore_path, gitignore_content)
             .map_err(|e| Error::Other(format!("无法写入 .gitignore: {}", e)))?;
         println!("已生成 .gitignore 文件: {}", gitignore_path.display());
@@ -0,0 +1,21 @@
+//! 集成测试共用的夹具函数（各测试文件用 `mod common;` 引入，未用到的函数不报警告）
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+/// 写入文件，按需创建父目录
+pub fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// 带标题、时间与标签的最简文章源文件
+pub fn post(title: &str, date: &str, tags: &str) -> String {
+    post_with(title, date, tags, "")
+}
+
+/// 同 [`post`]，`extra` 为追加的 front matter 行（含换行）
+pub fn post_with(title: &str, date: &str, tags: &str, extra: &str) -> String {
+    format!("---\ntitle: {}\ncreateTime: {}\ntags: [{}]\n{}---\n正文\n", title, date, tags, extra)
+}
@@ -0,0 +1,74 @@
+use rustpress::report::IssueKind;
+use rustpress::post::ParseOptions;
+use rustpress::{Config, Error, Generator, PostParser};
+use std::fs;
+
+#[test]
+fn test_strict_build_aggregates_issues() {
+    let root = std::env::temp_dir().join(format!("rustpress_strict_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    fs::create_dir_all(md_dir.join("blog/assets")).expect("无法创建测试目录");
+    // 使用仓库中的示例配置与默认主题模板
+    let repo = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+
+    fs::write(md_dir.join("blog/ok.md"), "---\ntitle: 正常\ncreateTime: 2024-01-01\n---\n![图](assets/here.png)\n").unwrap();
+    fs::write(md_dir.join("blog/assets/here.png"), b"png").unwrap();
+    fs::write(md_dir.join("blog/broken.md"), "---\ntitle: 坏文件\ndescription: a: b\n---\n正文\n").unwrap();
+    fs::write(
+        md_dir.join("blog/missing.md"),
+        "---\ntitle: 缺图\ncover: cover.png\ncreateTime: 2024-01-02\n---\n![图](assets/gone.png)\n",
+    )
+    .unwrap();
+
+    // 解析失败的文章不再被静默吞掉
+    let (posts, issues) = PostParser::list_posts_with_errors(&md_dir, &ParseOptions::default()).expect("列出文章失败");
+    assert_eq!(posts.len(), 2);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].kind, IssueKind::Parse);
+    assert!(issues[0].path.ends_with("broken.md"));
+
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+
+    // 宽松模式：问题仅汇总输出，构建成功
+    let generator = Generator::new(config.clone(), &md_dir).expect("创建生成器失败");
+    generator.build(&md_dir, &output_dir).expect("宽松模式不应失败");
+    assert!(output_dir.join("blog/ok.html").exists());
+    assert!(output_dir.join("blog/missing.html").exists());
+
+    assert!(output_dir.join(rustpress::manifest::MANIFEST_FILE).exists());
+
+    // 严格模式：同样的问题使构建失败，错误中给出问题数量（1 个解析 + 2 个资源）
+    // 失败的构建不写清单（下次增量构建仍会重试），其余输出与宽松模式一致
+    fs::remove_dir_all(&output_dir).unwrap();
+    let strict = Generator::new(config, &md_dir).expect("创建生成器失败").with_strict(true);
+    let err = strict.build(&md_dir, &output_dir).expect_err("严格模式应失败");
+    let manifest_written = output_dir.join(rustpress::manifest::MANIFEST_FILE).exists();
+    let robots_written = output_dir.join("robots.txt").exists();
+    let _ = fs::remove_dir_all(&root);
+    assert!(!manifest_written);
+    assert!(robots_written);
+    match err {
+        Error::Build(msg) => assert!(msg.contains("3 个问题"), "{}", msg),
+        other => panic!("错误类型不符: {}", other),
+    }
+}
+
+#[test]
+fn test_front_matter_diagnostics_in_report() {
+    let md_dir = std::env::temp_dir().join(format!("rustpress_front_matter_report_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&md_dir);
+    fs::create_dir_all(md_dir.join("blog")).expect("无法创建测试目录");
+    fs::write(md_dir.join("blog/typo.md"), "---\ntitle: 拼写\nlayuot: doc\ncreateTime: 2024-01-01\n---\n正文\n").unwrap();
+
+    // 字段拼写诊断记入构建报告（严格模式据此失败），文章照常生成
+    let (posts, issues) = PostParser::list_posts_with_errors(&md_dir, &ParseOptions::default()).expect("列出文章失败");
+    let _ = fs::remove_dir_all(&md_dir);
+    assert_eq!(posts.len(), 1);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].kind, IssueKind::FrontMatter);
+    assert!(issues[0].path.ends_with("typo.md") && issues[0].message.contains("layout"), "{}", issues[0].message);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use rustpress::cache::{PostCache, POSTS_CACHE_FILE};
+use rustpress::post::ParseOptions;
+use rustpress::PostParser;
+use std::fs;
+use std::path::Path;
+use common::write;
+
+#[test]
+fn test_post_cache_reuses_unchanged_posts() {
+    let root = std::env::temp_dir().join(format!("rustpress_post_cache_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let cache_dir = root.join(".rustpress-cache");
+    write(&md_dir.join("blog/a.md"), "---\ntitle: 一\ncreateTime: 2024-01-01\n---\n# 标题\n\n正文 **一**\n");
+    write(&md_dir.join("blog/b.md"), "---\ntitle: 二\ncreateTime: 2024-01-02\n---\n正文二\n");
+    write(&md_dir.join("columns/1/README.md"), "---\ntitle: 专栏\ncreateTime: 2024-01-03\n---\n简介\n");
+    write(&md_dir.join("columns/1/c.md"), "---\ntitle: 章节\ncreateTime: 2024-01-04\n---\n正文\n");
+    write(&md_dir.join("blog/undated.md"), "---\ntitle: 无日期\n---\n正文\n");
+    let options = ParseOptions::default();
+    let list = |cache: &PostCache| PostParser::list_posts_cached(&md_dir, &options, Some(cache)).expect("列出文章失败").0;
+
+    // 首次解析写入缓存；未写时间的文章不缓存
+    let cache = PostCache::open(&cache_dir, &options);
+    let first = list(&cache);
+    assert_eq!((cache.hits(), cache.len()), (0, 4));
+    cache.save().unwrap();
+    assert!(cache_dir.join(POSTS_CACHE_FILE).exists());
+
+    // 再次解析全部命中，结果与直接解析一致（含源文件路径、专栏标题等非缓存字段）
+    let cache = PostCache::open(&cache_dir, &options);
+    let second = list(&cache);
+    assert_eq!(cache.hits(), 4);
+    let data = |posts: &[rustpress::Post]| {
+        posts
+            .iter()
+            .filter(|p| p.title() != Some("无日期"))
+            .map(|p| p.data.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(data(&first), data(&second));
+    let chapter = second.iter().find(|p| p.title() == Some("章节")).unwrap();
+    assert_eq!(chapter.data["column_title"], "专栏");
+    assert!(chapter.source_path().unwrap().ends_with("c.md"));
+
+    // 命中的文章不再渲染：篡改缓存中的 HTML 后读取到的是缓存内容
+    let cache_file = cache_dir.join(POSTS_CACHE_FILE);
+    let content = fs::read_to_string(&cache_file).unwrap();
+    fs::write(&cache_file, content.replace("正文二", "来自缓存")).unwrap();
+    let cache = PostCache::open(&cache_dir, &options);
+    let posts = list(&cache);
+    let b = posts.iter().find(|p| p.title() == Some("二")).unwrap();
+    assert!(b.content().unwrap().contains("来自缓存"));
+
+    // 内容变化的文章重新解析；已删除的文章在保存时从缓存移除
+    write(&md_dir.join("blog/a.md"), "---\ntitle: 一（修订）\ncreateTime: 2024-01-01\n---\n正文\n");
+    fs::remove_file(md_dir.join("blog/b.md")).unwrap();
+    let cache = PostCache::open(&cache_dir, &options);
+    let posts = list(&cache);
+    assert_eq!((cache.hits(), cache.len()), (2, 3));
+    assert!(posts.iter().any(|p| p.title() == Some("一（修订）")));
+    cache.save().unwrap();
+    assert!(!fs::read_to_string(&cache_file).unwrap().contains("来自缓存"));
+
+    // 解析选项变化时整个缓存失效
+    let mut changed = ParseOptions::default();
+    changed.markdown.hard_breaks = true;
+    let cache = PostCache::open(&cache_dir, &changed);
+    PostParser::list_posts_cached(&md_dir, &changed, Some(&cache)).expect("列出文章失败");
+    let _ = fs::remove_dir_all(&root);
+    assert_eq!(cache.hits(), 0);
+}
+
+#[test]
+fn test_post_cache_skips_paid_posts() {
+    let root = std::env::temp_dir().join(format!("rustpress_post_cache_paid_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    write(&md_dir.join("blog/free.md"), "---\ntitle: 公开\ncreateTime: 2024-01-01\n---\n公开正文\n");
+    write(
+        &md_dir.join("columns/1/README.md"),
+        "---\ntitle: 专栏\ncreateTime: 2024-01-02\nlayout: columns\nis_free: false\ndoc_id: columns_1\n---\n目录\n",
+    );
+    // 机密内容放在试读范围之外
+    let body = format!("{}\n\n付费正文机密\n", "试读内容。".repeat(60));
+    write(&md_dir.join("columns/1/a.md"), &format!("---\ntitle: 第一章\ncreateTime: 2024-01-03\nlayout: doc\n---\n{}", body));
+    let example = fs::read_to_string(repo.join("config.toml.example")).unwrap();
+    let config = toml::from_str::<toml::Value>(&example.replace("# columns_1 = ", "columns_1 = \"Key_123\"\n# "))
+        .map(|data| rustpress::Config { data })
+        .expect("配置解析失败");
+    let generator = rustpress::Generator::new(config, &md_dir).expect("创建生成器失败");
+
+    // 付费文章不写入缓存，重复构建时重新解析并加密
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+    generator.build(&md_dir, &output_dir).expect("再次构建失败");
+    let cache = fs::read_to_string(root.join(".rustpress-cache").join(POSTS_CACHE_FILE)).unwrap_or_default();
+    let page = fs::read_to_string(output_dir.join("columns/1/a.html")).unwrap_or_default();
+    let _ = fs::remove_dir_all(&root);
+    assert!(cache.contains("公开正文"), "{}", cache);
+    assert!(cache.contains("columns/1/README.md"));
+    assert!(!cache.contains("付费正文机密"), "{}", cache);
+    assert!(!cache.contains("columns/1/a.md"));
+    assert!(page.contains("data-key-id"), "{}", page);
+    assert!(!page.contains("付费正文机密"));
+}
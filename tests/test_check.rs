@@ -0,0 +1,45 @@
+use rustpress::check::{self, Severity};
+use std::fs;
+
+#[test]
+fn test_check_site_reports_issues_with_lines() {
+    let dir = std::env::temp_dir().join(format!("rustpress_check_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("columns/1")).expect("无法创建测试目录");
+
+    // 第 3 行的 YAML 语法错误
+    fs::write(dir.join("broken.md"), "---\ntitle: 坏文件\ndescription: a: b\n---\n正文\n").unwrap();
+    fs::write(dir.join("bad_time.md"), "---\ntitle: 时间\ncreateTime: 2024-13-45\n---\n正文\n").unwrap();
+    fs::write(dir.join("a.md"), "---\ntitle: A\nslug: same\n---\n正文\n").unwrap();
+    fs::write(dir.join("b.md"), "---\ntitle: B\nslug: same\n---\n正文\n").unwrap();
+    fs::write(dir.join("tweet.md"), "---\nlayout: tweet\ncreateTime: 2024-01-01\n---\n没有标题的动态\n").unwrap();
+    fs::write(
+        dir.join("columns/1/README.md"),
+        "---\ntitle: 专栏\nlayout: columns\ncatalog:\n  - 1.第一章：启程.md\n  - 2.missing.md\n---\n",
+    )
+    .unwrap();
+    fs::write(dir.join("columns/1/1.第一章：启程.md"), "---\ntitle: 第一章\n---\n正文\n").unwrap();
+
+    let report = check::check_site(&dir).expect("检查失败");
+    let _ = fs::remove_dir_all(&dir);
+
+    let find = |file: &str, needle: &str| {
+        report
+            .issues
+            .iter()
+            .find(|i| i.path.ends_with(file) && i.message.contains(needle))
+            .unwrap_or_else(|| panic!("未找到 {} 中包含 `{}` 的问题: {:#?}", file, needle, report.issues))
+    };
+
+    assert_eq!(find("broken.md", "解析失败").line, Some(3));
+    assert_eq!(find("bad_time.md", "2024-13-45").line, Some(3));
+    assert_eq!(find("a.md", "/same").line, Some(3));
+    assert_eq!(find("b.md", "/same").line, Some(3));
+    assert_eq!(find("columns/1/README.md", "2.missing.md").line, Some(6));
+
+    // 值中的中文冒号保持原样，章节能够被找到；动态不要求标题
+    assert!(!report.issues.iter().any(|i| i.message.contains("第一章")), "{:#?}", report.issues);
+    assert!(!report.issues.iter().any(|i| i.path.ends_with("tweet.md")), "{:#?}", report.issues);
+    assert!(report.issues.iter().all(|i| i.severity == Severity::Error));
+    assert_eq!(report.error_count(), 5);
+}
@@ -0,0 +1,44 @@
+use rustpress::excerpt;
+use rustpress::{Post, PostParser};
+use std::path::Path;
+
+fn parse(content: &str) -> Post {
+    let value = PostParser::parse_file_content(content, Path::new("source/blog/a.md"), Path::new("source"))
+        .expect("解析失败")
+        .expect("未识别到 front matter");
+    Post::from_value(value)
+}
+
+#[test]
+fn test_excerpt_sources() {
+    // 1. <!-- more --> 之前的内容（代码块中的标记不算），且优先于 description
+    let post = parse(
+        "---\ntitle: A\ndescription: 描述\n---\n# 标题\n\n第一段 **加粗** & 更多\n\n```md\n<!-- more -->\n```\n\n<!-- more -->\n\n付费之后的内容\n",
+    );
+    let html = post.summary_html().unwrap();
+    assert!(html.contains("<strong>加粗</strong>"), "{}", html);
+    assert!(html.contains("<pre>"), "代码块应完整保留在摘要中: {}", html);
+    assert!(!html.contains("标题") && !html.contains("之后"), "{}", html);
+    assert!(post.summary_text().unwrap().starts_with("第一段 加粗 & 更多"));
+
+    // 2. front matter 中的 summary / description
+    let post = parse("---\ntitle: B\ndescription: \"<简介>\"\n---\n正文\n");
+    assert_eq!(post.summary_text(), Some("<简介>"));
+    assert_eq!(post.summary_html(), Some("<p>&lt;简介&gt;</p>"));
+
+    // 3. 正文前 N 个字符（跳过开头的 H1）
+    let body: String = "中".repeat(300);
+    let post = parse(&format!("---\ntitle: C\n---\n# 标题\n\n{}\n", body));
+    let text = post.summary_text().unwrap();
+    assert_eq!(text.chars().count(), excerpt::DEFAULT_EXCERPT_LENGTH + 1);
+    assert!(text.starts_with('中') && text.ends_with('…'));
+}
+
+#[test]
+fn test_truncate_text_is_cjk_aware() {
+    assert_eq!(excerpt::truncate_text("你好世界", 2), "你好…");
+    assert_eq!(excerpt::truncate_text("Rust 很快", 10), "Rust 很快");
+    // 不从英文单词中间截断
+    assert_eq!(excerpt::truncate_text("学习 Rustacean 之路", 6), "学习…");
+    assert_eq!(excerpt::truncate_text("abc", 0), "");
+}
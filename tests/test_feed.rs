@@ -0,0 +1,151 @@
+use rustpress::feed::{self, FeedFormat, FeedMeta};
+use rustpress::{Config, Post, PostParser};
+use serde_json::Value;
+use std::path::Path;
+
+const CONFIG: &str = r#"
+[site]
+name = "测试站点"
+description = "描述 & 简介"
+domain = "https://example.com/"
+
+[author]
+name = "LIYI"
+email = "a@example.com"
+website = "https://example.com"
+
+[feed]
+content = "full"
+"#;
+
+fn post(content: &str, modified_epoch: i64) -> Post {
+    let mut value = PostParser::parse_file_content(content, Path::new("source/blog/a.md"), Path::new("source"))
+        .expect("解析失败")
+        .expect("未识别到 front matter");
+    value["modified_epoch"] = Value::from(modified_epoch);
+    Post::from_value(value)
+}
+
+fn meta(config: &str) -> FeedMeta {
+    FeedMeta::from_config(&Config { data: toml::from_str(config).unwrap() })
+}
+
+fn posts() -> Vec<Post> {
+    vec![
+        post(
+            "---\ntitle: Rust <入门>\ncreateTime: 2024-05-01 10:30:00\ntags: [Rust, 教程]\ndescription: 摘要内容\n---\n正文 ![图](/assets/a.png) [链接](/blog/b.html)\n",
+            // 2030-01-01，晚于发布时间
+            1893456000,
+        ),
+        post("---\nlayout: tweet\ncreateTime: 2024-04-01\n---\n今天天气不错，适合写代码\n", 0),
+    ]
+}
+
+#[test]
+fn test_rss_is_spec_correct() {
+    let meta = meta(CONFIG);
+    let rss = feed::render(FeedFormat::Rss, &meta, &posts(), "/rss.xml");
+    assert!(rss.contains("<title>Rust &lt;入门&gt;</title>"), "{}", rss);
+    assert!(rss.contains("<link>https://example.com/blog/a.html</link>"), "{}", rss);
+    assert!(rss.contains("<atom:link href=\"https://example.com/rss.xml\" rel=\"self\""), "{}", rss);
+    assert!(rss.contains("<dc:creator>LIYI</dc:creator>"), "{}", rss);
+    assert!(rss.contains("<category>Rust</category>") && rss.contains("<category>教程</category>"), "{}", rss);
+    assert!(rss.contains("<description>描述 &amp; 简介</description>"), "{}", rss);
+    // RFC 822 日期
+    let pub_date = rss.split("<pubDate>").nth(1).unwrap().split("</pubDate>").next().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc2822(pub_date).is_ok(), "{}", pub_date);
+    assert!(pub_date.contains("1 May 2024 10:30:00"), "{}", pub_date);
+    // 全文模式输出 content:encoded，站内地址转为绝对地址
+    assert!(rss.contains("<content:encoded><![CDATA["), "{}", rss);
+    assert!(rss.contains("src=\"https://example.com/assets/a.png\""), "{}", rss);
+    assert!(rss.contains("href=\"https://example.com/blog/b.html\""), "{}", rss);
+    // 无标题的动态使用正文开头作为标题
+    assert!(rss.contains("<title>今天天气不错，适合写代码</title>"), "{}", rss);
+}
+
+#[test]
+fn test_atom_and_json_feed() {
+    let meta = meta(CONFIG);
+    let atom = feed::render(FeedFormat::Atom, &meta, &posts(), "/atom.xml");
+    assert!(atom.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\""), "{}", atom);
+    assert!(atom.contains("<email>a@example.com</email>"), "{}", atom);
+    assert!(atom.contains("<published>2024-05-01T10:30:00"), "{}", atom);
+    assert!(atom.contains("<updated>2030-01-01T"), "更新时间应取自 modified_epoch: {}", atom);
+    assert!(atom.contains("<category term=\"Rust\"/>"), "{}", atom);
+    assert!(atom.contains("<content type=\"html\">&lt;p&gt;正文"), "{}", atom);
+
+    let json: Value = serde_json::from_str(&feed::render(FeedFormat::Json, &meta, &posts(), "/feed.json")).unwrap();
+    assert_eq!(json["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(json["feed_url"], "https://example.com/feed.json");
+    assert_eq!(json["authors"][0]["name"], "LIYI");
+    assert_eq!(json["items"][0]["id"], "https://example.com/blog/a.html");
+    assert_eq!(json["items"][0]["tags"][1], "教程");
+    assert_eq!(json["items"][0]["summary"], "摘要内容");
+    assert!(json["items"][0]["content_html"].as_str().unwrap().contains("正文"));
+    // 旧文章未修改：更新时间等于发布时间
+    assert_eq!(json["items"][1]["date_modified"], json["items"][1]["date_published"]);
+}
+
+#[test]
+fn test_summary_mode_limit_and_feature_toggles() {
+    let meta = meta("[site]\ndomain = \"https://example.com\"\n[feed]\nlimit = 1\n");
+    assert!(!meta.full_content);
+    let rss = feed::render(FeedFormat::Rss, &meta, &posts(), "/rss.xml");
+    assert!(!rss.contains("content:encoded>"), "{}", rss);
+    assert_eq!(rss.matches("<item>").count(), 1);
+    let atom = feed::render(FeedFormat::Atom, &meta, &posts(), "/atom.xml");
+    assert!(atom.contains("<content type=\"html\">&lt;p&gt;摘要内容&lt;/p&gt;</content>"), "{}", atom);
+
+    let config = Config { data: toml::from_str("[features]\natom = false\n").unwrap() };
+    assert!(FeedFormat::Rss.enabled(&config) && FeedFormat::Json.enabled(&config));
+    assert!(!FeedFormat::Atom.enabled(&config));
+}
+
+#[test]
+fn test_scoped_feeds_and_alternate_links() {
+    let meta = meta(CONFIG).scoped("Rust");
+    assert_eq!(meta.title, "Rust - 测试站点");
+    let posts = posts();
+    // 仅传入部分文章（标签下的文章引用）
+    let rss = feed::render(FeedFormat::Rss, &meta, posts.iter().take(1), "/tags/Rust/rss.xml");
+    assert!(rss.contains("<title>Rust - 测试站点</title>"), "{}", rss);
+    assert!(rss.contains("href=\"https://example.com/tags/Rust/rss.xml\" rel=\"self\""), "{}", rss);
+    assert_eq!(rss.matches("<item>").count(), 1);
+
+    assert_eq!(feed::scoped_dir(&["blog", "技术"]), "/blog/技术/");
+    let config = Config { data: toml::from_str("[site]\nname = \"站点\"\n[features]\natom = false\n").unwrap() };
+    let links = feed::alternate_links(&config, "/columns/3/", "专栏");
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0]["type"], "application/rss+xml");
+    assert_eq!(links[0]["href"], "/columns/3/rss.xml");
+    assert_eq!(links[0]["title"], "专栏 - 站点");
+    assert_eq!(links[1]["href"], "/columns/3/feed.json");
+
+    let config = Config { data: toml::from_str("[feed]\ntaxonomies = false\n").unwrap() };
+    assert!(!feed::taxonomy_feeds_enabled(&config));
+    assert!(feed::alternate_links(&config, "/tags/Rust/", "Rust").is_empty());
+}
+
+#[test]
+fn test_site_feeds_skip_category_readmes() {
+    let root = std::env::temp_dir().join(format!("rustpress_feed_readme_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    std::fs::create_dir_all(md_dir.join("blog")).unwrap();
+    std::fs::write(md_dir.join("blog/README.md"), "---\ntitle: 博客分类首页\ncreateTime: 2024-01-01\n---\n介绍\n").unwrap();
+    std::fs::write(md_dir.join("blog/a.md"), "---\ntitle: 第一篇\ncreateTime: 2024-01-02\n---\n正文\n").unwrap();
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    rustpress::Generator::new(config, &md_dir).expect("创建生成器失败").build(&md_dir, &output_dir).expect("构建失败");
+
+    let read = |name: &str| std::fs::read_to_string(output_dir.join(name)).unwrap_or_default();
+    let (rss, atom, json) = (read("rss.xml"), read("atom.xml"), read("feed.json"));
+    let _ = std::fs::remove_dir_all(&root);
+    for feed in [&rss, &atom, &json] {
+        assert!(feed.contains("第一篇"), "{}", feed);
+        assert!(!feed.contains("博客分类首页"), "{}", feed);
+    }
+}
@@ -0,0 +1,36 @@
+use rustpress::front_matter::{self, FrontMatter, Layout};
+use std::path::Path;
+
+#[test]
+fn test_typed_front_matter_and_diagnostics() {
+    // 中文冒号与缺少空格的写法仍能被容错解析
+    let content = "---\ntitle：\"类型化元数据\"\nlayuot: doc\nlayout:columns\ntag: [\"Rust\"]\nweight: \"3\"\ndraft: false\nproduct_id: x\n---\n正文\n";
+    let raw = front_matter::split(content).expect("未识别到 front matter");
+    assert_eq!(raw.body.trim(), "正文");
+    let metadata = front_matter::parse_metadata(&raw, Path::new("typed.md")).expect("解析失败");
+
+    let fm = FrontMatter::from_value(&metadata);
+    assert_eq!(fm.title.as_deref(), Some("类型化元数据"));
+    assert_eq!(fm.layout, Some(Layout::Columns));
+    assert_eq!(fm.weight, Some(3), "字符串形式的数字也应识别为权重");
+    assert!(!fm.draft);
+    assert!(fm.extra.contains_key("product_id"), "未识别字段应透传到 extra");
+
+    let messages: Vec<String> = front_matter::diagnose(&metadata)
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+    assert!(messages.iter().any(|m| m.contains("`layuot`") && m.contains("`layout`")), "{:?}", messages);
+    assert!(messages.iter().any(|m| m.contains("`tag`") && m.contains("`tags`")), "{:?}", messages);
+    assert!(!messages.iter().any(|m| m.contains("product_id")), "已知字段不应报错");
+}
+
+#[test]
+fn test_unknown_layout_is_reported() {
+    let metadata = serde_json::json!({ "title": "x", "layout": "colums" });
+    let diagnostics = front_matter::diagnose(&metadata);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].key, "layout");
+    assert!(diagnostics[0].message.contains("`columns`"));
+    assert_eq!(Layout::parse("colums"), Layout::Other("colums".to_string()));
+}
@@ -0,0 +1,77 @@
+use rustpress::highlight::{self, FenceAttrs, HighlightMode, HighlightOptions};
+use rustpress::post::ParseOptions;
+use rustpress::{Config, PostParser};
+use std::path::Path;
+
+fn render(markdown: &str, config: &str) -> String {
+    let options = ParseOptions::from_config(&Config { data: toml::from_str(config).unwrap() });
+    let dir = std::env::temp_dir().join(format!("rustpress_highlight_{}_{}", std::process::id(), markdown.len()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("blog")).unwrap();
+    std::fs::write(dir.join("blog/a.md"), format!("---\ntitle: A\n---\n{}", markdown)).unwrap();
+    let (posts, _) = PostParser::list_posts_with_errors(&dir, &options).expect("列出文章失败");
+    let _ = std::fs::remove_dir_all(&dir);
+    posts[0].content().unwrap().to_string()
+}
+
+#[test]
+fn test_fence_attrs() {
+    let attrs = FenceAttrs::parse("rust", r#"title="src/main file.rs" {3-5,8} linenos"#);
+    assert_eq!(attrs.lang, "rust");
+    assert_eq!(attrs.title.as_deref(), Some("src/main file.rs"));
+    assert_eq!(attrs.line_numbers, Some(true));
+    assert!(attrs.is_highlighted(4) && attrs.is_highlighted(8));
+    assert!(!attrs.is_highlighted(6));
+
+    let attrs = FenceAttrs::parse("diff-js", "{2}");
+    assert_eq!(attrs.lang, "js");
+    assert!(attrs.diff && attrs.is_highlighted(2));
+    assert_eq!(FenceAttrs::parse("rust", "diff nolinenos").line_numbers, Some(false));
+    assert!(FenceAttrs::parse("diff", "").lang == "diff");
+}
+
+#[test]
+fn test_code_block_highlighted_at_build_time() {
+    let markdown = "```rust title=\"main.rs\" {2} linenos\nfn main() {\n    let s = \"<hi>\"; // 中文注释\n}\n```\n";
+    let html = render(markdown, "[highlight]\nenable = true\n");
+    assert!(
+        html.contains(r#"<pre class="hl-code code-linenos" data-lang="rust" data-title="main.rs"><code class="language-rust">"#),
+        "{}",
+        html
+    );
+    assert!(html.contains(r#"<span class="code-line" data-line="1">"#), "{}", html);
+    assert!(html.contains(r#"<span class="code-line code-mark" data-line="2">"#), "{}", html);
+    assert!(html.contains("hl-keyword"), "应输出 CSS 类名: {}", html);
+    assert!(html.contains("&lt;hi&gt;"), "代码应被转义: {}", html);
+    assert_eq!(html.matches("<span").count(), html.matches("</span>").count(), "{}", html);
+    assert!(!html.contains(r#"data-line="4""#), "末尾换行不应产生空行: {}", html);
+
+    // diff 模式：标记从代码中去掉，改为行的类名
+    let html = render("```diff-rust\n-let a = 1;\n+let a = 2;\n let b = 3;\n```\n", "[highlight]\nenable = true\n");
+    assert!(html.contains(r#"<span class="code-line code-diff-remove" data-line="1">"#), "{}", html);
+    assert!(html.contains(r#"<span class="code-line code-diff-add" data-line="2">"#), "{}", html);
+    assert!(!html.contains(">+") && !html.contains(">-let"), "{}", html);
+
+    // 内联样式模式
+    let html = render("```rust\nlet a = 1;\n```\n", "[highlight]\nenable = true\nmode = \"inline\"\n");
+    assert!(html.contains("style=\"color:#"), "{}", html);
+
+    // 未启用时保持原样，交由前端处理
+    let html = render("```rust\nlet a = 1;\n```\n", "");
+    assert!(html.contains("<pre><code class=\"language-rust\">let a = 1;\n</code></pre>"), "{}", html);
+}
+
+#[test]
+fn test_theme_css() {
+    let options = HighlightOptions { enable: true, ..HighlightOptions::default() };
+    let css = highlight::theme_css(&options).unwrap();
+    assert!(css.contains(".hl-code {") && css.contains("pre.code-linenos"), "{}", css);
+
+    let inline = HighlightOptions { mode: HighlightMode::Inline, ..options };
+    assert!(!highlight::theme_css(&inline).unwrap().contains("generated by syntect"));
+
+    // 未知主题回退默认主题
+    let config = Config { data: toml::from_str("[highlight]\ntheme = \"nope\"\n").unwrap() };
+    assert_eq!(HighlightOptions::from_config(&config).theme, highlight::DEFAULT_THEME);
+    assert!(Path::new(highlight::CSS_PATH).starts_with("static"));
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use rustpress::generator::MemFs;
+use rustpress::{Config, DevServer, Generator};
+use std::fs;
+use std::path::Path;
+use common::{post_with, write};
+use tower::ServiceExt;
+
+fn post(title: &str, date: &str, tags: &str) -> String {
+    post_with(title, date, tags, "cover: ./assets/a.png\n")
+}
+
+#[test]
+fn test_build_into_mem_fs() {
+    let root = std::env::temp_dir().join(format!("rustpress_in_memory_build_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    write(&md_dir.join("blog/a.md"), &post("一", "2024-01-01", "中文"));
+    write(&md_dir.join("blog/b.md"), &post("二", "2024-02-01", "Solo"));
+    write(&md_dir.join("blog/assets/a.png"), "png");
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    let mem_fs = MemFs::default();
+    let generator = Generator::new(config, &md_dir).expect("创建生成器失败").with_mem_fs(mem_fs.clone());
+
+    // 全量构建只写入内存：页面、源目录附件与清单都在内存中，输出目录不会被创建；资源检查在内存中进行
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+    let file = |key: &str| mem_fs.read().unwrap().get(key).map(|c| String::from_utf8_lossy(c).into_owned());
+    assert!(!output_dir.exists());
+    assert!(file("/index.html").is_some() && file("/blog/assets/a.png").is_some());
+    assert!(file("/tags/中文/index.html").is_some());
+    assert!(file("/.rustpress-manifest.json").is_some());
+    assert!(generator.issues().is_empty(), "{:?}", generator.issues());
+
+    // 增量构建读取内存中的清单：更新变化的文章，删除已消失文章的输出
+    write(&md_dir.join("blog/a.md"), &post("一（修订）", "2024-01-01", "中文"));
+    fs::remove_file(md_dir.join("blog/b.md")).unwrap();
+    generator.build_incremental(&md_dir, &output_dir).expect("增量构建失败");
+    let a_html = file("/blog/a.html");
+    let b_html = file("/blog/b.html");
+    let solo = file("/tags/Solo/index.html");
+    let _ = fs::remove_dir_all(&root);
+    assert!(a_html.unwrap().contains("一（修订）"));
+    assert!(b_html.is_none() && solo.is_none());
+    assert!(!output_dir.exists());
+}
+
+#[tokio::test]
+async fn test_memory_router() {
+    let mem_fs = MemFs::default();
+    {
+        let mut files = mem_fs.write().unwrap();
+        files.insert("/index.html".to_string(), b"<html><body>home</body></html>".to_vec());
+        files.insert("/blog/index.html".to_string(), b"<html><body>blog</body></html>".to_vec());
+        files.insert("/tags/中文/index.html".to_string(), b"<html><body>tag</body></html>".to_vec());
+        files.insert("/css/main.css".to_string(), b"body{}".to_vec());
+        files.insert("/blog/assets/a.png".to_string(), b"png".to_vec());
+    }
+    let router = DevServer::memory_router(mem_fs, "public", None, None, None);
+    let get = |uri: &str| {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let router = router.clone();
+        async move {
+            let response = router.oneshot(request).await.unwrap();
+            let status = response.status();
+            let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+            let (content_type, location) = (header(header::CONTENT_TYPE), header(header::LOCATION));
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, content_type, location, String::from_utf8_lossy(&bytes).into_owned())
+        }
+    };
+
+    let (status, content_type, _, body) = get("/").await;
+    assert_eq!((status, content_type.as_str(), body.contains("home")), (StatusCode::OK, "text/html; charset=utf-8", true));
+    assert_eq!(get("/css/main.css").await.1, "text/css; charset=utf-8");
+    assert_eq!(get("/blog/assets/a.png").await.1, "image/png");
+    // 百分号编码的中文路径与目录地址
+    assert!(get("/tags/%E4%B8%AD%E6%96%87/").await.3.contains("tag"));
+    let (status, _, location, _) = get("/blog?page=2").await;
+    assert!(status.is_redirection());
+    assert_eq!(location, "/blog/?page=2");
+    // 找不到时以 404 返回首页
+    let (status, _, _, body) = get("/missing.html").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains("home"));
+}
+
+#[tokio::test]
+async fn test_memory_router_search_api() {
+    let root = std::env::temp_dir().join(format!("rustpress_in_memory_search_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    write(&md_dir.join("blog/a.md"), "---\ntitle: 异构计算\ncreateTime: 2024-01-01\n---\n正文\n");
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    let mem_fs = MemFs::default();
+    let generator = Generator::new(config.clone(), &md_dir).expect("创建生成器失败").with_mem_fs(mem_fs.clone());
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+
+    let router = DevServer::memory_router(mem_fs, &output_dir, Some(&config), Some(md_dir.clone()), None);
+    let search = |q: &str| {
+        let uri = format!("/api/search?q={}", q);
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let router = router.clone();
+        async move {
+            let response = router.oneshot(request).await.unwrap();
+            let status = response.status();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<serde_json::Value>(&bytes).unwrap())
+        }
+    };
+
+    // 索引只在内存中，接口从内存文件系统读取
+    let (status, body) = search("%E5%BC%82%E6%9E%84").await;
+    assert!(!output_dir.exists());
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["hits"][0]["url"], "/blog/a.html");
+
+    // 重新构建后载入新索引
+    write(&md_dir.join("blog/a.md"), "---\ntitle: 分布式存储\ncreateTime: 2024-01-01\n---\n正文\n");
+    generator.build_incremental(&md_dir, &output_dir).expect("增量构建失败");
+    let (_, body) = search("%E5%AD%98%E5%82%A8").await;
+    let _ = fs::remove_dir_all(&root);
+    assert_eq!(body["total"], 1, "{}", body);
+}
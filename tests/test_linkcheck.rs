@@ -0,0 +1,105 @@
+mod common;
+
+use rustpress::check::Severity;
+use rustpress::linkcheck::{check_links, LinkCheckOptions};
+use rustpress::Config;
+use std::fs;
+use common::write;
+
+/// 在后台线程启动一个本地 HTTP 服务：/ok 返回 200，其余 404
+fn spawn_server() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let app = axum::Router::new().route("/ok", axum::routing::get(|| async { "ok" }));
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            axum::serve(listener, app).await.unwrap();
+        });
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_check_links_internal_and_external() {
+    let root = std::env::temp_dir().join(format!("rustpress_linkcheck_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let server = spawn_server();
+
+    write(&md_dir.join("blog/a.md"), "---\ntitle: 文章\ncreateTime: 2024-01-01\n---\n正文\n");
+    write(
+        &output_dir.join("blog/a.html"),
+        &format!(
+            r#"<h2 id="intro">简介</h2><a href="../index2.html">上一页</a><a href="/static/gone.png">图</a>
+            <a href="{server}/ok">可访问</a><a href="{server}/missing">失效</a><a href="{server}/ok#x">同一地址</a>"#
+        ),
+    );
+    write(
+        &output_dir.join("index.html"),
+        r##"<link rel="stylesheet" href="&#x2F;static&#x2F;main.css">
+        <link rel="preconnect" href="https://fonts.example.com">
+        <a href="/blog/a.html#intro">锚点</a><a href="/blog/a.html#nope">错误锚点</a>
+        <a href="/tags/rust/">标签</a><a href="/search">搜索</a><a href="https://yishulun.com/gone.html">本站</a>
+        <a href="mailto:a@b.c">邮件</a><a href="#top">顶部</a><a href="/api/search?q=x">接口</a>
+        <script>el.src = "/nothing.js"; html = '<img src="/nothing.png">';</script>
+        <!-- <a href="/commented.html">注释</a> -->"##,
+    );
+    write(&output_dir.join("index2.html"), "<p>第二页</p>");
+    write(&output_dir.join("tags/rust/index.html"), "<p>Rust</p>");
+    write(&output_dir.join("search.html"), "<p>搜索</p>");
+    write(&output_dir.join("search/manifest.json"), "{}");
+    write(&output_dir.join("static/main.css"), "body{}");
+
+    let config = Config {
+        data: toml::from_str("[site]\ndomain = \"https://yishulun.com\"\n\n[link_check]\nignore = [\"/api/\"]\n").unwrap(),
+    };
+
+    // 默认只检查站内链接
+    let options = LinkCheckOptions::from_config(&config);
+    assert!(!options.external);
+    assert_eq!(options.ignore, vec!["/api/".to_string()]);
+    let report = check_links(&output_dir, &md_dir, &config, &options).expect("链接检查失败");
+    assert_eq!(report.pages, 5);
+    assert_eq!(report.external, 0);
+    let mut links: Vec<(&str, &str)> = report.issues().map(|i| (i.page.as_str(), i.link.as_str())).collect();
+    links.sort();
+    assert_eq!(
+        links,
+        vec![
+            ("blog/a.html", "/static/gone.png"),
+            ("index.html", "/blog/a.html#nope"),
+            ("index.html", "https://yishulun.com/gone.html"),
+        ]
+    );
+    assert_eq!(report.error_count(), 3, "{}", report);
+    // 按源 Markdown 文件分组，生成页面单独成组
+    let groups: Vec<&String> = report.groups.keys().collect();
+    assert!(groups.iter().any(|g| g.ends_with("blog/a.md")), "{:?}", groups);
+    assert!(groups.iter().any(|g| g.starts_with("index.html")), "{:?}", groups);
+    let anchor = report.issues().find(|i| i.link.ends_with("#nope")).unwrap();
+    assert!(anchor.message.contains("锚点 #nope"), "{}", anchor.message);
+
+    // 站外链接：失效仅作警告，可访问的结果写入缓存
+    let cache_file = root.join("cache/links.json");
+    let options = LinkCheckOptions {
+        external: true,
+        concurrency: 2,
+        cache_file: cache_file.clone(),
+        ..options
+    };
+    let report = check_links(&output_dir, &md_dir, &config, &options).expect("链接检查失败");
+    assert_eq!(report.external, 2);
+    assert_eq!(report.warning_count(), 1, "{}", report);
+    let warning = report.issues().find(|i| i.severity == Severity::Warning).unwrap();
+    assert!(warning.link.ends_with("/missing") && warning.message.contains("404"), "{}", warning);
+    let cache = fs::read_to_string(&cache_file).unwrap();
+    let _ = fs::remove_dir_all(&root);
+    let cache: serde_json::Value = serde_json::from_str(&cache).unwrap();
+    assert_eq!(cache[format!("{}/ok", server)]["ok"], true);
+    assert_eq!(cache[format!("{}/missing", server)]["ok"], false);
+}
+
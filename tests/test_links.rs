@@ -0,0 +1,69 @@
+use rustpress::links::rewrite_md_links;
+use rustpress::post::ParseOptions;
+use rustpress::report::IssueKind;
+use rustpress::PostParser;
+use std::fs;
+
+#[test]
+fn test_relative_md_links_rewritten() {
+    let root = std::env::temp_dir().join(format!("rustpress_links_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    fs::create_dir_all(md_dir.join("blog")).unwrap();
+    fs::create_dir_all(md_dir.join("docs/intro")).unwrap();
+    fs::create_dir_all(md_dir.join("works/app")).unwrap();
+
+    fs::write(md_dir.join("README.md"), "---\ntitle: 首页\n---\n").unwrap();
+    fs::write(
+        md_dir.join("blog/1.md"),
+        "---\ntitle: 第一章\ncreateTime: 2024-01-01\n---\n\
+         [下一章](./2.md#安装) [简介](../docs/intro/README.md) [作品](/works/app/README.md)\n\
+         [首页](../README.md) [中文](中文.md?v=1) [外部](https://example.com/a.md) [锚点](#x)\n\
+         [缺失](./gone.md) [草稿](draft.md)\n",
+    )
+    .unwrap();
+    fs::write(md_dir.join("blog/2.md"), "---\ntitle: 第二章\ncreateTime: 2024-01-02\n---\n正文\n").unwrap();
+    fs::write(md_dir.join("blog/中文.md"), "---\ntitle: 中文\ncreateTime: 2024-01-03\n---\n正文\n").unwrap();
+    fs::write(md_dir.join("blog/draft.md"), "---\ntitle: 草稿\ndraft: true\n---\n正文\n").unwrap();
+    fs::write(md_dir.join("docs/intro/README.md"), "---\ntitle: 简介\n---\n正文\n").unwrap();
+    fs::write(md_dir.join("works/app/README.md"), "---\ntitle: 作品\n---\n正文\n").unwrap();
+
+    let (mut posts, _) = PostParser::list_posts_with_errors(&md_dir, &ParseOptions::default()).expect("列出文章失败");
+    let url_of = |posts: &[rustpress::Post], name: &str| {
+        posts
+            .iter()
+            .find(|p| p.source_path().unwrap().ends_with(name))
+            .and_then(|p| p.url())
+            .unwrap()
+            .to_string()
+    };
+    let intro = url_of(&posts, "docs/intro/README.md");
+    let chinese = url_of(&posts, "中文.md");
+
+    let issues = rewrite_md_links(&mut posts, &md_dir);
+    let content = posts
+        .iter()
+        .find(|p| p.source_path().unwrap().ends_with("blog/1.md"))
+        .and_then(|p| p.content())
+        .unwrap()
+        .to_string();
+    let _ = fs::remove_dir_all(&root);
+
+    // 相对路径、绝对路径与 README 规则，锚点与查询串保留
+    assert!(content.contains(r#"href="/blog/2.html#%E5%AE%89%E8%A3%85""#), "{}", content);
+    assert!(content.contains(&format!(r#"href="{}""#, intro)), "{}", content);
+    assert!(content.contains(r#"href="/works/app.html""#), "{}", content);
+    assert!(content.contains(r#"href="/""#), "{}", content);
+    assert!(content.contains(&format!(r#"href="{}?v=1""#, chinese)), "{}", content);
+    // 外部链接与页内锚点不变
+    assert!(content.contains(r#"href="https://example.com/a.md""#));
+    assert!(content.contains(r##"href="#x""##));
+
+    // 找不到目标的链接保持原样并记为构建问题
+    assert!(content.contains(r#"href="./gone.md""#));
+    assert!(content.contains(r#"href="draft.md""#));
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert!(issues.iter().all(|i| i.kind == IssueKind::Link && i.path.ends_with("blog/1.md")));
+    assert!(issues.iter().any(|i| i.message.contains("不存在") && i.message.contains("gone.md")));
+    assert!(issues.iter().any(|i| i.message.contains("未发布") && i.message.contains("draft.md")));
+}
@@ -0,0 +1,111 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use futures_util::StreamExt;
+use rustpress::livereload::{
+    blocking_issues, css_only_changes, inject_script, ErrorEntry, LiveReload, ReloadEvent, SCRIPT_PATH,
+};
+use rustpress::report::{BuildIssue, IssueKind};
+use rustpress::DevServer;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tower::ServiceExt;
+
+#[test]
+fn test_inject_script() {
+    let tag = format!(r#"<script src="{}" defer></script>"#, SCRIPT_PATH);
+    // 插在最后一个 </body> 之前（大小写不敏感）
+    let html = inject_script("<html><BODY><pre>&lt;/body&gt;</pre></BODY></html>");
+    assert_eq!(html, format!("<html><BODY><pre>&lt;/body&gt;</pre>{}</BODY></html>", tag));
+    // 没有 </body> 时追加到末尾
+    assert_eq!(inject_script("<p>片段</p>"), format!("<p>片段</p>{}", tag));
+}
+
+#[test]
+fn test_css_only_changes() {
+    let theme = Path::new("/site/themes/default/static");
+    let md_dir = Path::new("/site/source");
+    let roots = [theme, md_dir];
+    let paths = |list: &[&str]| list.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+    let css = css_only_changes(
+        &paths(&["/site/themes/default/static/css/main.css", "/site/source/blog/a.CSS", "/site/themes/default/static/css/main.css"]),
+        &roots,
+    );
+    assert_eq!(css, Some(vec!["/blog/a.CSS".to_string(), "/css/main.css".to_string()]));
+    // 混有其他文件、不在源目录下的 CSS 或没有变化时都需要刷新页面
+    assert_eq!(css_only_changes(&paths(&["/site/themes/default/static/css/main.css", "/site/source/blog/a.md"]), &roots), None);
+    assert_eq!(css_only_changes(&paths(&["/site/themes/default/templates/main.css"]), &roots), None);
+    assert_eq!(css_only_changes(&[], &roots), None);
+}
+
+#[test]
+fn test_blocking_issues() {
+    let issues = vec![
+        BuildIssue::new(IssueKind::Parse, "source/blog/a.md", "front matter 解析失败".to_string()),
+        BuildIssue::new(IssueKind::Asset, "source/blog/b.md", "图片不存在".to_string()),
+        BuildIssue::new(IssueKind::Template, "source/blog/c.md", "模板渲染失败".to_string()),
+    ];
+    assert_eq!(
+        blocking_issues(&issues),
+        vec![
+            ErrorEntry::new("source/blog/a.md", "front matter 解析失败"),
+            ErrorEntry::new("source/blog/c.md", "模板渲染失败"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_subscribe_replays_pending_error() {
+    let live = LiveReload::new();
+    let error = ReloadEvent::Error { errors: vec![ErrorEntry::new("a.md", "失败")] };
+    live.send(error.clone());
+
+    // 构建失败后新连接的页面立即收到错误，随后收到新事件
+    let mut events = Box::pin(live.subscribe());
+    assert_eq!(events.next().await, Some(error));
+    live.send(ReloadEvent::Reload);
+    assert_eq!(events.next().await, Some(ReloadEvent::Reload));
+
+    // 修复后新连接的页面不再收到旧错误
+    let mut events = Box::pin(live.subscribe());
+    live.send(ReloadEvent::Css { paths: vec!["/css/main.css".to_string()] });
+    assert_eq!(events.next().await, Some(ReloadEvent::Css { paths: vec!["/css/main.css".to_string()] }));
+}
+
+#[tokio::test]
+async fn test_router_injects_client_only_in_live_mode() {
+    let output_dir = std::env::temp_dir().join(format!("rustpress_livereload_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&output_dir);
+    fs::create_dir_all(output_dir.join("css")).unwrap();
+    fs::write(output_dir.join("index.html"), "<html><body>首页</body></html>").unwrap();
+    fs::write(output_dir.join("css/main.css"), "body{}").unwrap();
+
+    let get = |router: axum::Router, uri: &str| {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        async move {
+            let response = router.oneshot(request).await.unwrap();
+            let status = response.status();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, String::from_utf8_lossy(&bytes).into_owned())
+        }
+    };
+    let live = LiveReload::new();
+    let live_router = DevServer::router(&output_dir, None, None, Some(&live));
+    let plain_router = DevServer::router(&output_dir, None, None, None);
+
+    let (status, html) = get(live_router.clone(), "/index.html").await;
+    let (_, css) = get(live_router.clone(), "/css/main.css").await;
+    let (script_status, script) = get(live_router, SCRIPT_PATH).await;
+    let (_, plain) = get(plain_router.clone(), "/index.html").await;
+    let (plain_script_status, _) = get(plain_router, SCRIPT_PATH).await;
+    let _ = fs::remove_dir_all(&output_dir);
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(html.contains(SCRIPT_PATH) && html.ends_with("</body></html>"), "{}", html);
+    assert_eq!(css, "body{}");
+    assert_eq!(script_status, StatusCode::OK);
+    assert!(script.contains("EventSource"));
+    // 未开启实时刷新时页面原样返回
+    assert_eq!(plain, "<html><body>首页</body></html>");
+    assert_ne!(plain_script_status, StatusCode::OK);
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use rustpress::crypto;
+use rustpress::manifest::{dependency_graph, BuildManifest, SourceEntry, MANIFEST_FILE};
+use rustpress::{Config, Generator, PostParser};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use common::{post, write};
+
+fn outputs(list: &[&str]) -> BTreeSet<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_dependency_graph_reverse_pagination() {
+    let md_dir = std::env::temp_dir().join(format!("rustpress_manifest_graph_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&md_dir);
+    write(&md_dir.join("blog/a.md"), &post("一", "2024-01-01", "Rust"));
+    write(&md_dir.join("blog/b.md"), &post("二", "2024-02-01", "Rust, Rust"));
+    write(&md_dir.join("blog/c.md"), &post("三", "2024-03-01", "Web"));
+    let posts = PostParser::list_posts(&md_dir).expect("列出文章失败");
+    let config = Config {
+        data: toml::from_str("[homepage]\nposts_per_page = 2\n\n[features]\nrss = true\n").unwrap(),
+    };
+
+    let graph = dependency_graph(&config, &posts);
+    let _ = fs::remove_dir_all(&md_dir);
+    let of = |name: &str| {
+        graph
+            .iter()
+            .find(|(source, _)| source.ends_with(name))
+            .map(|(_, outputs)| outputs.clone())
+            .unwrap_or_else(|| panic!("依赖图缺少 {}", name))
+    };
+
+    // 3 篇每页 2 篇：最早一篇在第 1 页，余下两篇在最新一页（index.html 与 index2.html）
+    let a = of("blog/a.md");
+    assert!(a.contains("blog/a.html"));
+    assert!(a.contains("index1.html") && !a.contains("index.html"), "{:?}", a);
+    assert!(a.contains("archives/2024/01/index.html"));
+    let c = of("blog/c.md");
+    assert!(c.contains("index.html") && c.contains("index2.html"), "{:?}", c);
+    assert!(c.contains("tags/Web/index.html") && !c.contains("tags/Rust/index.html"));
+    // 标签与分类沿用首页配置的每页条数：Rust 标签仅一页，blog 分类有两页；重复标签只计一次
+    let b = of("blog/b.md");
+    assert!(b.contains("tags/Rust/index.html") && !b.contains("tags/Rust/index1.html"), "{:?}", b);
+    assert!(b.contains("blog/index1.html") && c.contains("blog/index2.html"), "{:?}", b);
+    assert!(b.contains("rss.xml"));
+}
+
+#[test]
+fn test_manifest_diff() {
+    let entry = |hash: &str, list: &[&str]| SourceEntry { hash: hash.to_string(), outputs: outputs(list), ..SourceEntry::default() };
+    let manifest = |sources: Vec<(&str, SourceEntry)>| BuildManifest {
+        sources: sources.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<BTreeMap<_, _>>(),
+        ..BuildManifest::default()
+    };
+    let previous = manifest(vec![
+        ("a.md", entry("1", &["a.html", "index.html", "tags/x/index.html"])),
+        ("b.md", entry("2", &["b.html", "index1.html"])),
+        ("gone.md", entry("3", &["gone.html", "index.html", "tags/old/index.html"])),
+    ]);
+    let current = manifest(vec![
+        ("a.md", entry("1", &["a.html", "index.html", "tags/x/index.html"])),
+        ("b.md", entry("2", &["b.html", "index.html"])),
+        ("new.md", entry("4", &["new.html", "index.html"])),
+    ]);
+
+    assert!(current.diff(&current).is_empty());
+    let diff = current.diff(&previous);
+    assert_eq!(diff.changed, outputs(&["new.md"]));
+    assert_eq!(diff.removed, outputs(&["gone.md"]));
+    // 内容未变的 b.md 只因分页移动而影响 index1.html / index.html；删除的文章输出需清理
+    assert_eq!(diff.affected, outputs(&["index.html", "new.html"]));
+    assert_eq!(diff.stale, outputs(&["gone.html", "index1.html", "tags/old/index.html"]));
+}
+
+#[test]
+fn test_manifest_diff_key_rotation() {
+    let entry = |key_id: &str| SourceEntry {
+        hash: "1".to_string(),
+        key_id: Some(key_id.to_string()),
+        outputs: outputs(&["columns/1/a.html", "columns/1/index.html"]),
+    };
+    let manifest = |key_id: &str| BuildManifest {
+        sources: BTreeMap::from([("columns/1/a.md".to_string(), entry(key_id))]),
+        ..BuildManifest::default()
+    };
+
+    // 源文件未变而内容密钥变化时，按变化的文章重新生成其全部输出
+    let diff = manifest("new").diff(&manifest("old"));
+    assert_eq!(diff.changed, outputs(&["columns/1/a.md"]));
+    assert_eq!(diff.affected, outputs(&["columns/1/a.html", "columns/1/index.html"]));
+    assert!(manifest("old").diff(&manifest("old")).is_empty());
+}
+
+#[test]
+fn test_incremental_build_uses_manifest() {
+    let root = std::env::temp_dir().join(format!("rustpress_manifest_build_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    write(&md_dir.join("blog/a.md"), &post("一", "2024-01-01", "Rust"));
+    write(&md_dir.join("blog/b.md"), &post("二", "2024-02-01", "Solo"));
+    write(&md_dir.join("blog/assets/b.png"), "png");
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    let generator = Generator::new(config.clone(), &md_dir).expect("创建生成器失败");
+
+    // 全量构建写出清单
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+    let manifest_path = output_dir.join(MANIFEST_FILE);
+    let first = BuildManifest::load(&manifest_path).expect("缺少构建清单");
+    assert!(first.sources.contains_key("blog/a.md") && first.sources.contains_key("blog/assets/b.png"));
+    assert!(first.sources["blog/b.md"].outputs.contains("tags/Solo/index.html"));
+
+    // 无变化时增量构建不改变清单
+    generator.build_incremental(&md_dir, &output_dir).expect("增量构建失败");
+    assert_eq!(BuildManifest::load(&manifest_path), Some(first.clone()));
+
+    // 修改与删除：重新生成变化的文章，删除已消失源文件的输出
+    write(&md_dir.join("blog/a.md"), &post("一（修订）", "2024-01-01", "Rust"));
+    fs::remove_file(md_dir.join("blog/b.md")).unwrap();
+    fs::remove_file(md_dir.join("blog/assets/b.png")).unwrap();
+    generator.build_incremental(&md_dir, &output_dir).expect("增量构建失败");
+    let a_html = fs::read_to_string(output_dir.join("blog/a.html")).unwrap();
+    assert!(a_html.contains("一（修订）"));
+    assert!(!output_dir.join("blog/b.html").exists());
+    assert!(!output_dir.join("blog/assets/b.png").exists());
+    assert!(!output_dir.join("tags/Solo").exists());
+    let second = BuildManifest::load(&manifest_path).expect("缺少构建清单");
+    assert!(!second.sources.contains_key("blog/b.md"));
+    assert_ne!(second.sources["blog/a.md"].hash, first.sources["blog/a.md"].hash);
+
+    // 配置变化需要全量构建
+    let mut changed = config.clone();
+    changed.data.as_table_mut().unwrap().insert("extra".into(), toml::Value::Boolean(true));
+    let templates_dir = root.join("themes/default/templates");
+    let reason = second.full_rebuild_reason(&changed, &templates_dir).expect("计算哈希失败");
+    let unchanged = second.full_rebuild_reason(&config, &templates_dir).expect("计算哈希失败");
+    let _ = fs::remove_dir_all(&root);
+    assert_eq!(reason, Some("配置变化"));
+    assert_eq!(unchanged, None);
+}
+
+#[test]
+fn test_incremental_build_updates_book_siblings() {
+    let root = std::env::temp_dir().join(format!("rustpress_manifest_book_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    let doc = |title: &str| format!("---\ntitle: {}\nlayout: doc\ncreateTime: 2024-01-01\n---\n正文\n", title);
+    write(&md_dir.join("columns/rust/README.md"), &doc("Rust 专栏"));
+    write(&md_dir.join("columns/rust/a.md"), &doc("第一章"));
+    write(&md_dir.join("columns/rust/b.md"), &doc("第二章"));
+    write(&md_dir.join("blog/c.md"), "---\ntitle: 引用\ncreateTime: 2024-02-01\n---\n见[第一章](../columns/rust/a.md)\n");
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    let generator = Generator::new(config.clone(), &md_dir).expect("创建生成器失败");
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+
+    // 同书章节与 README 互相依赖，链接所在文章依赖被链接的文章
+    let manifest = BuildManifest::load(output_dir.join(MANIFEST_FILE)).expect("缺少构建清单");
+    let a = &manifest.sources["columns/rust/a.md"].outputs;
+    assert!(a.contains("columns/rust/b.html") && a.contains("columns/rust/index.html"), "{:?}", a);
+    assert!(a.contains("blog/c.html"), "{:?}", a);
+    assert!(!manifest.sources["blog/c.md"].outputs.contains("columns/rust/a.html"));
+
+    // 只修改 a.md 的标题，b.html 的侧边栏随之更新
+    write(&md_dir.join("columns/rust/a.md"), &doc("第一章（修订）"));
+    generator.build_incremental(&md_dir, &output_dir).expect("增量构建失败");
+    let b_html = fs::read_to_string(output_dir.join("columns/rust/b.html")).unwrap();
+    let readme_html = fs::read_to_string(output_dir.join("columns/rust/index.html")).unwrap();
+    let _ = fs::remove_dir_all(&root);
+    assert!(b_html.contains("第一章（修订）"));
+    assert!(readme_html.contains("第一章（修订）"));
+}
+
+#[test]
+fn test_incremental_build_reencrypts_after_key_change() {
+    let root = std::env::temp_dir().join(format!("rustpress_manifest_keys_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    write(&md_dir.join("columns/1/README.md"), "---\ntitle: 专栏\nlayout: columns\nis_free: false\ndoc_id: columns_1\n---\n目录\n");
+    write(&md_dir.join("columns/1/a.md"), "---\ntitle: 第一章\nlayout: doc\n---\n付费正文\n");
+    let keys_path = root.join("doc_keys.json");
+    let write_key = || {
+        let key = crypto::generate_content_key();
+        let keys = std::collections::HashMap::from([("columns_1".to_string(), crypto::encode_key(&key))]);
+        crypto::write_keys_file(&keys_path, &keys).expect("无法写入密钥文件");
+        crypto::key_id(&key)
+    };
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    let generator = Generator::new(config, &md_dir).expect("创建生成器失败");
+
+    write_key();
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+    // 源文件不变、只更换项目根密钥文件中的密钥：增量构建用新密钥重新加密并更新清单
+    let new_key_id = write_key();
+    generator.build_incremental(&md_dir, &output_dir).expect("增量构建失败");
+    let page = fs::read_to_string(output_dir.join("columns/1/a.html")).unwrap();
+    let manifest = BuildManifest::load(output_dir.join(MANIFEST_FILE)).expect("缺少构建清单");
+    let _ = fs::remove_dir_all(&root);
+    assert!(page.contains(&format!("data-key-id=\"{}\"", new_key_id)), "{}", page);
+    assert_eq!(manifest.sources["columns/1/a.md"].key_id, Some(new_key_id));
+}
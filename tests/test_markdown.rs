@@ -0,0 +1,109 @@
+use rustpress::markdown::MarkdownOptions;
+use rustpress::post::ParseOptions;
+use rustpress::{Config, PostParser};
+use serde_json::{json, Value};
+
+fn config(text: &str) -> Config {
+    Config { data: toml::from_str(text).unwrap() }
+}
+
+fn render(markdown: &str, config_text: &str) -> Value {
+    let options = ParseOptions::from_config(&config(config_text));
+    let dir = std::env::temp_dir().join(format!("rustpress_markdown_{}_{}", std::process::id(), markdown.len()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("blog")).unwrap();
+    std::fs::write(dir.join("blog/a.md"), markdown).unwrap();
+    let (posts, _) = PostParser::list_posts_with_errors(&dir, &options).expect("列出文章失败");
+    let _ = std::fs::remove_dir_all(&dir);
+    posts[0].data.clone()
+}
+
+#[test]
+fn test_markdown_options_from_config_and_overrides() {
+    assert_eq!(MarkdownOptions::from_config(&config("")), MarkdownOptions::default());
+
+    let options = MarkdownOptions::from_config(&config(
+        "[markdown]\nunsafe_html = true\ntable = false\nfront_matter_delimiter = \"---\"\nheader_id_prefix = \"h-\"\n",
+    ));
+    assert!(options.unsafe_html && !options.table);
+    assert_eq!(options.front_matter_delimiter.as_deref(), Some("---"));
+    assert_eq!(options.header_id_prefix, "h-");
+
+    assert!(options.with_overrides(None).is_none());
+    assert!(options.with_overrides(Some(&json!({}))).is_none());
+    let post = options.with_overrides(Some(&json!({"unsafe_html": "off", "math": true}))).unwrap();
+    assert!(!post.unsafe_html && post.math && !post.table);
+}
+
+#[test]
+fn test_default_extensions_unchanged() {
+    let page = render("---\ntitle: A\n---\n| a |\n|---|\n| 1 |\n\n~~删~~ ==亮== <b>粗</b> https://example.com\n", "");
+    let html = page["content"].as_str().unwrap();
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<del>删</del>"));
+    assert!(html.contains("<mark>亮</mark>"));
+    assert!(!html.contains("<b>粗</b>"), "{}", html);
+    assert!(!html.contains("<a href=\"https://example.com\""));
+}
+
+#[test]
+fn test_site_markdown_config() {
+    let markdown = "---\ntitle: A\n---\n## 小节\n\n<b>粗</b> https://example.com x^2^\n\"引号\" -- 破折号\n\n术语\n\n: 释义\n";
+    let page = render(
+        markdown,
+        "[markdown]\nunsafe_html = true\nautolink = true\nsuperscript = true\nsmart_punctuation = true\nhard_breaks = true\ndescription_lists = true\nheader_id_prefix = \"h-\"\n",
+    );
+    let html = page["content"].as_str().unwrap();
+    assert!(html.contains("<b>粗</b>"), "{}", html);
+    assert!(html.contains("<a href=\"https://example.com\">"), "{}", html);
+    assert!(html.contains("x<sup>2</sup><br />"), "{}", html);
+    assert!(html.contains("“引号” – 破折号"), "{}", html);
+    assert!(html.contains("<dl>"), "{}", html);
+    assert!(html.contains("<h2 id=\"h-小节\">"), "{}", html);
+    assert_eq!(page["toc"][0]["anchor"], "h-小节");
+
+    let page = render("---\ntitle: A\n---\n## 小节\n", "[markdown]\nheader_ids = false\n");
+    assert!(page["content"].as_str().unwrap().contains("<h2>小节</h2>"));
+    assert_eq!(page["toc"], json!([]));
+}
+
+#[test]
+fn test_front_matter_markdown_override() {
+    let markdown = "---\ntitle: A\nmarkdown:\n  unsafe_html: true\n  alerts: true\n  wikilinks: true\n  math: true\n---\n<b>粗</b>\n\n> [!NOTE]\n> 提示\n\n[[页面|标题]] $x^2$\n";
+    let page = render(markdown, "");
+    let html = page["content"].as_str().unwrap();
+    assert!(html.contains("<b>粗</b>"), "{}", html);
+    assert!(html.contains("callout callout-note"), "{}", html);
+    assert!(html.contains("<a href=\"%E9%A1%B5%E9%9D%A2\" data-wikilink=\"true\">标题</a>"), "{}", html);
+    assert!(html.contains("<math xmlns="), "{}", html);
+
+    // 未覆盖的键沿用站点配置
+    let page = render(
+        "---\ntitle: A\nmarkdown:\n  table: false\n---\n<b>粗</b>\n\n| a |\n|---|\n| 1 |\n",
+        "[markdown]\nunsafe_html = true\n",
+    );
+    let html = page["content"].as_str().unwrap();
+    assert!(!html.contains("<table>") && html.contains("<b>粗</b>"), "{}", html);
+}
+
+#[test]
+fn test_callouts_with_localized_titles() {
+    let markdown = "---\ntitle: A\n---\n> [!NOTE]\n> 记录\n\n> [!WARNING] 自定义 <标题>\n> 小心\n\n:::tip\n**技巧**\n\n- 一\n:::\n\n::: danger 别这样\n危险\n:::\n\n:::details 展开\n隐藏内容\n:::\n\n:::unknown\n保持原样\n:::\n";
+    let html = render(markdown, "");
+    let html = html["content"].as_str().unwrap();
+    assert!(html.contains("<div class=\"callout callout-note\">\n<p class=\"callout-title\">注意</p>\n<p>记录</p>\n</div>"), "{}", html);
+    assert!(html.contains("<div class=\"callout callout-warning\">\n<p class=\"callout-title\">自定义 &lt;标题&gt;</p>"), "{}", html);
+    assert!(html.contains("<div class=\"callout callout-tip\">\n<p class=\"callout-title\">提示</p>\n<p><strong>技巧</strong></p>\n<ul>"), "{}", html);
+    assert!(html.contains("<div class=\"callout callout-danger\">\n<p class=\"callout-title\">别这样</p>"), "{}", html);
+    assert!(html.contains("<details class=\"callout callout-details\">\n<summary class=\"callout-title\">展开</summary>\n<p>隐藏内容</p>\n</details>"), "{}", html);
+    assert!(html.contains("<div class=\"unknown\">"), "{}", html);
+    assert!(!html.contains("markdown-alert"), "{}", html);
+
+    // 标题可在配置中本地化，文章可关闭提示块
+    let page = render(markdown, "[markdown.callouts]\nnote = \"Note\"\nTIP = \"Hint\"\n");
+    let html = page["content"].as_str().unwrap();
+    assert!(html.contains("<p class=\"callout-title\">Note</p>") && html.contains("<p class=\"callout-title\">Hint</p>"), "{}", html);
+    let page = render(&markdown.replacen("title: A\n", "title: A\nmarkdown:\n  alerts: false\n", 1), "");
+    let html = page["content"].as_str().unwrap();
+    assert!(!html.contains("callout") && html.contains("<blockquote>"), "{}", html);
+}
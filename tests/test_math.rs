@@ -0,0 +1,126 @@
+use rustpress::math;
+use rustpress::post::ParseOptions;
+use rustpress::{Config, PostParser};
+
+/// 去掉 `<math>` 外壳与 TeX 注解，只留公式主体
+fn body(latex: &str, display: bool) -> String {
+    let xml = math::to_mathml(latex, display);
+    let start = xml.find("<semantics>").unwrap() + "<semantics>".len();
+    let end = xml.find("<annotation").unwrap();
+    xml[start..end].to_string()
+}
+
+#[test]
+fn test_scripts_fractions_and_roots() {
+    assert_eq!(body("x^2", false), "<msup><mi>x</mi><mn>2</mn></msup>");
+    assert_eq!(body("a_{ij}^{2}", false), "<msubsup><mi>a</mi><mrow><mi>i</mi><mi>j</mi></mrow><mn>2</mn></msubsup>");
+    assert_eq!(body("x^23", false), "<mrow><msup><mi>x</mi><mn>2</mn></msup><mn>3</mn></mrow>");
+    assert_eq!(body("3.14", false), "<mn>3.14</mn>");
+    assert_eq!(body("\\frac{1}{2}", false), "<mfrac><mn>1</mn><mn>2</mn></mfrac>");
+    assert_eq!(body("\\sqrt[3]{x}", false), "<mroot><mi>x</mi><mn>3</mn></mroot>");
+    assert_eq!(body("\\sqrt x", false), "<msqrt><mi>x</mi></msqrt>");
+    assert_eq!(body("f'", false), "<msup><mi>f</mi><mo>′</mo></msup>");
+    assert_eq!(body("a-b", false), "<mrow><mi>a</mi><mo>−</mo><mi>b</mi></mrow>");
+}
+
+#[test]
+fn test_symbols_functions_and_fonts() {
+    assert_eq!(body("\\alpha \\Omega \\infty", false), "<mrow><mi>α</mi><mi mathvariant=\"normal\">Ω</mi><mi>∞</mi></mrow>");
+    assert_eq!(body("a \\leq b", false), "<mrow><mi>a</mi><mo>≤</mo><mi>b</mi></mrow>");
+    assert_eq!(body("\\not= \\not\\in", false), "<mrow><mo>≠</mo><mo>∉</mo></mrow>");
+    assert_eq!(body("\\sin x", false), "<mrow><mi>sin</mi><mo>&#x2061;</mo><mi>x</mi></mrow>");
+    assert_eq!(body("\\mathbb{R}^n", false), "<msup><mi>ℝ</mi><mi>n</mi></msup>");
+    assert_eq!(body("\\mathbf{v1}", false), "<mrow><mi>𝐯</mi><mn>𝟏</mn></mrow>");
+    assert_eq!(body("\\mathrm{d}x", false), "<mrow><mi mathvariant=\"normal\">d</mi><mi>x</mi></mrow>");
+    assert_eq!(body("\\text{若 } x<0", false), "<mrow><mtext>若 </mtext><mi>x</mi><mo>&lt;</mo><mn>0</mn></mrow>");
+    assert_eq!(body("\\hat{x}", false), "<mover accent=\"true\"><mi>x</mi><mo stretchy=\"false\">^</mo></mover>");
+    assert_eq!(body("a\\,b", false), "<mrow><mi>a</mi><mspace width=\"0.1667em\"></mspace><mi>b</mi></mrow>");
+}
+
+#[test]
+fn test_limits_follow_display_mode() {
+    assert_eq!(
+        body("\\sum_{i=1}^n", true),
+        "<munderover><mo largeop=\"true\">∑</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover>"
+    );
+    assert_eq!(body("\\lim_{x \\to 0}", false), "<mrow><msub><mi>lim</mi><mrow><mi>x</mi><mo>→</mo><mn>0</mn></mrow></msub><mo>&#x2061;</mo></mrow>");
+    assert!(body("\\lim_{x \\to 0}", true).starts_with("<mrow><munder><mi>lim</mi>"));
+    assert!(body("\\int_0^1", true).starts_with("<msubsup><mo largeop=\"true\">∫</mo>"));
+}
+
+#[test]
+fn test_fences_and_environments() {
+    assert_eq!(
+        body("\\left( \\frac{a}{b} \\right]", false),
+        "<mrow><mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">(</mo><mfrac><mi>a</mi><mi>b</mi></mfrac><mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">]</mo></mrow>"
+    );
+    assert_eq!(body("f(x)", false), "<mrow><mi>f</mi><mo stretchy=\"false\">(</mo><mi>x</mi><mo stretchy=\"false\">)</mo></mrow>");
+
+    let cases = body("|x| = \\begin{cases} x & x \\ge 0 \\\\ -x & x < 0 \\end{cases}", true);
+    assert!(cases.contains("<mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">{</mo><mtable displaystyle=\"true\">"), "{}", cases);
+    assert_eq!(cases.matches("<mtr>").count(), 2);
+    assert!(cases.contains("<mtd style=\"text-align:left\"><mi>x</mi></mtd>"), "{}", cases);
+
+    let aligned = body("\\begin{aligned} a &= b \\\\ &= c \\\\ \\end{aligned}", true);
+    assert_eq!(aligned.matches("<mtr>").count(), 2, "{}", aligned);
+    assert!(aligned.contains("<mtd style=\"text-align:right\"><mi>a</mi></mtd><mtd style=\"text-align:left\"><mi></mi><mo>=</mo><mi>b</mi></mtd>"), "{}", aligned);
+
+    let matrix = body("\\begin{bmatrix} 1 & 0 \\\\ 0 & 1 \\end{bmatrix}", true);
+    assert!(matrix.starts_with("<mrow><mo fence=\"true\" stretchy=\"true\" symmetric=\"true\">[</mo><mtable"), "{}", matrix);
+    assert!(!matrix.contains("merror"));
+}
+
+#[test]
+fn test_unsupported_commands_are_reported_not_fatal() {
+    let xml = math::to_mathml("\\foo{x} + \\left( y", false);
+    assert!(xml.contains("<merror data-error=\"不支持的命令 \\foo\"><mtext>\\foo</mtext></merror>"), "{}", xml);
+    assert_eq!(math::unsupported(&xml), ["不支持的命令 \\foo", "缺少 \\right"]);
+
+    let xml = math::to_mathml("\\begin{tikzcd} a \\end{tikzcd} \\begin{matrix} b", true);
+    assert_eq!(math::unsupported(&xml), ["不支持的环境 tikzcd", "缺少 \\end{matrix}"]);
+    assert!(math::unsupported(&math::to_mathml("\\frac{a}{b}", true)).is_empty());
+}
+
+#[test]
+fn test_render_math_in_posts() {
+    let dir = std::env::temp_dir().join(format!("rustpress_math_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("blog")).unwrap();
+    std::fs::write(
+        dir.join("blog/a.md"),
+        "---\ntitle: A\n---\n行内 $a<b$，价格 $5 与 $10。\n\n$$\nE = mc^2\n$$\n\n```math\n\\sqrt{2}\n```\n\n$\\unknown$\n",
+    )
+    .unwrap();
+    let render = |config: &str| {
+        let options = ParseOptions::from_config(&Config { data: toml::from_str(config).unwrap() });
+        let (posts, _) = PostParser::list_posts_with_errors(&dir, &options).expect("列出文章失败");
+        posts[0].content().unwrap().to_string()
+    };
+
+    // 未启用时保持原样
+    let html = render("");
+    assert!(!html.contains("<math"));
+
+    for config in ["[markdown]\nmath = true\n", "[markdown]\nmath = true\n[highlight]\nenable = true\n"] {
+        let html = render(config);
+        assert!(html.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi></mrow><annotation encoding=\"application/x-tex\">a&lt;b</annotation></semantics></math>"), "{}", html);
+        assert!(html.contains("价格 $5 与 $10"), "{}", html);
+        assert!(html.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><semantics><mrow><mi>E</mi>"), "{}", html);
+        assert!(html.contains("<msqrt><mn>2</mn></msqrt>"), "{}", html);
+        assert!(!html.contains("data-math-style"), "{}", html);
+        assert_eq!(math::unsupported(&html), ["不支持的命令 \\unknown"]);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_deep_nesting_is_capped() {
+    // 深层嵌套不应耗尽栈空间，超过上限时整段显示为 merror
+    for latex in ["{".repeat(5000), "\\frac".repeat(5000), format!("{}x{}", "{".repeat(300), "}".repeat(300))] {
+        let html = math::to_mathml(&latex, true);
+        assert_eq!(math::unsupported(&html), vec!["嵌套超过 256 层".to_string()]);
+    }
+    // 上限以内正常转换
+    let html = math::to_mathml(&format!("{}x{}", "{".repeat(200), "}".repeat(200)), false);
+    assert!(math::unsupported(&html).is_empty(), "{}", html);
+}
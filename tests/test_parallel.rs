@@ -0,0 +1,75 @@
+use rustpress::post::ParseOptions;
+use rustpress::{Config, Generator, PostParser};
+use std::fs;
+use std::path::Path;
+
+fn pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap()
+}
+
+#[test]
+fn test_parallel_parse_matches_serial() {
+    let md_dir = std::env::temp_dir().join(format!("rustpress_parallel_parse_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&md_dir);
+    for i in 0..40 {
+        let dir = md_dir.join(format!("blog/{}", i % 4));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("post{}.md", i)),
+            format!("---\ntitle: 文章{}\ncreateTime: 2024-01-{:02} 08:00:{:02}\n---\n正文\n\n```rust\nfn main() {{}}\n\n\n```\n", i, i % 28 + 1, i),
+        )
+        .unwrap();
+    }
+    fs::write(md_dir.join("blog/broken.md"), "---\ntitle: 坏文件\ndescription: a: b\n---\n正文\n").unwrap();
+    fs::create_dir_all(md_dir.join("blog/draft")).unwrap();
+    fs::write(md_dir.join("blog/draft/README.md"), "---\ntitle: 草稿\ndraft: true\n---\n").unwrap();
+    fs::write(md_dir.join("blog/draft/a.md"), "---\ntitle: 草稿文章\n---\n正文\n").unwrap();
+
+    let list = |threads: usize| {
+        pool(threads).install(|| PostParser::list_posts_with_errors(&md_dir, &ParseOptions::default()).expect("列出文章失败"))
+    };
+    let (serial, serial_issues) = list(1);
+    let (parallel, parallel_issues) = list(4);
+    let _ = fs::remove_dir_all(&md_dir);
+
+    // 并行解析的结果（含排序与解析问题）与串行一致；草稿目录被跳过
+    assert_eq!(serial.len(), 40);
+    let data = |posts: &[rustpress::Post]| posts.iter().map(|p| p.data.clone()).collect::<Vec<_>>();
+    assert_eq!(data(&serial), data(&parallel));
+    assert_eq!(serial_issues.len(), 1);
+    assert_eq!(parallel_issues.len(), 1);
+    assert!(parallel_issues[0].path.ends_with("broken.md"));
+    // 代码块尾部多余空行被压缩
+    assert!(!parallel[0].content().unwrap().contains("\n\n</code>"));
+}
+
+#[test]
+fn test_parallel_build_writes_all_pages() {
+    let root = std::env::temp_dir().join(format!("rustpress_parallel_build_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    for i in 0..30 {
+        let dir = md_dir.join(format!("blog/{}", i % 3));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("post{}.md", i)),
+            format!("---\ntitle: 文章{}\ncreateTime: 2024-{:02}-01\ntags: [t{}]\n---\n正文\n", i, i % 12 + 1, i % 5),
+        )
+        .unwrap();
+    }
+    let config = Config::from_file(repo.join("config.toml.example")).expect("配置解析失败");
+    let generator = Generator::new(config, &md_dir).expect("创建生成器失败");
+    let result = pool(4).install(|| generator.build(&md_dir, &output_dir));
+
+    let exists = |rel: &str| output_dir.join(rel).exists();
+    let all_pages = (0..30).all(|i| exists(&format!("blog/{}/post{}.html", i % 3, i)));
+    let tag_pages = (0..5).all(|i| exists(&format!("tags/t{}/index.html", i)));
+    let month_pages = (1..=12).all(|m| exists(&format!("archives/2024/{:02}/index.html", m)));
+    let _ = fs::remove_dir_all(&root);
+    result.expect("并行构建失败");
+    assert!(all_pages && tag_pages && month_pages);
+}
@@ -0,0 +1,239 @@
+use rustpress::crypto::{self, EncryptedPayload};
+use rustpress::{Config, Post};
+use serde_json::json;
+use std::path::Path;
+
+#[test]
+fn test_encrypt_paid_column_posts() {
+    let config = Config {
+        data: toml::from_str(
+            r#"
+[paywall]
+preview_chars = 4
+
+[paywall.keys]
+columns_1 = "Key_123"
+"#,
+        )
+        .expect("配置解析失败"),
+    };
+
+    let mut posts = vec![
+        // 专栏首页：声明整栏付费与 doc_id，本身保持公开
+        Post::from_value(json!({
+            "title": "专栏",
+            "slug": "index",
+            "categories": ["columns", "1"],
+            "is_free": false,
+            "doc_id": "columns_1",
+            "content": "<p>专栏目录</p>"
+        })),
+        // 章节：继承专栏的付费设置
+        Post::from_value(json!({
+            "title": "第一章",
+            "slug": "1",
+            "categories": ["columns", "1"],
+            "content": "<h1>第一章</h1>\n<p>付费正文内容</p>",
+            "summary_text": "付费正文内容"
+        })),
+        // 章节：显式声明免费
+        Post::from_value(json!({
+            "title": "试读",
+            "slug": "2",
+            "categories": ["columns", "1"],
+            "is_free": true,
+            "content": "<p>免费试读</p>"
+        })),
+    ];
+
+    let count = crypto::encrypt_paid_posts(&config, Path::new(""), &mut posts).expect("加密失败");
+    assert_eq!(count, 1, "应只加密一篇付费章节");
+    assert_eq!(posts[0].content(), Some("<p>专栏目录</p>"), "专栏首页不应加密");
+    assert_eq!(posts[2].content(), Some("<p>免费试读</p>"), "免费章节不应加密");
+
+    let locked = posts[1].content().unwrap();
+    assert!(locked.starts_with("<h1>第一章</h1>"), "标题应保留在明文中");
+    assert!(!locked.contains("付费正文内容"), "输出中不应包含付费明文");
+    assert!(locked.contains("付费正文…"), "应包含免费预览");
+    assert!(locked.contains("data-doc-id=\"columns_1\""));
+    assert_eq!(posts[1].summary_text(), Some("付费正文…"), "摘要应替换为试读文本");
+
+    let attr = |name: &str| {
+        let marker = format!("{}=\"", name);
+        let start = locked.find(&marker).unwrap() + marker.len();
+        let end = locked[start..].find('"').unwrap();
+        locked[start..start + end].to_string()
+    };
+    let payload = EncryptedPayload {
+        ciphertext: attr("data-ciphertext"),
+        iv: attr("data-iv"),
+        tag: attr("data-tag"),
+    };
+    let key = crypto::content_key_from_str("columns_1", "Key_123");
+    assert_eq!(attr("data-key-id"), crypto::key_id(&key));
+    let plain = crypto::decrypt_html(&key, &payload).expect("解密失败");
+    assert_eq!(plain, "<p>付费正文内容</p>");
+}
+
+#[test]
+fn test_paid_post_without_key_fails() {
+    let config = Config {
+        data: toml::from_str("").unwrap(),
+    };
+    let mut posts = vec![Post::from_value(json!({
+        "slug": "1",
+        "categories": ["columns", "9"],
+        "is_free": false,
+        "doc_id": "missing_doc",
+        "content": "<p>secret</p>"
+    }))];
+    assert!(
+        crypto::encrypt_paid_posts(&config, Path::new(""), &mut posts).is_err(),
+        "缺少内容密钥时应构建失败，避免泄露明文"
+    );
+}
+
+#[test]
+fn test_keys_file_overrides_config_keys() {
+    let dir = std::env::temp_dir().join(format!("rustpress_keys_{}", std::process::id()));
+    let keys_path = dir.join("doc_keys.json");
+    let new_key = crypto::generate_content_key();
+    let mut file_keys = std::collections::HashMap::new();
+    file_keys.insert("columns_1".to_string(), crypto::encode_key(&new_key));
+    crypto::write_keys_file(&keys_path, &file_keys).expect("无法写入密钥文件");
+
+    let config = Config {
+        data: toml::from_str(&format!(
+            "[paywall]\nkeys_file = {:?}\n\n[paywall.keys]\ncolumns_1 = \"Key_123\"\n",
+            keys_path.to_string_lossy()
+        ))
+        .unwrap(),
+    };
+    let resolved = crypto::resolve_content_key(&config, Path::new("unused"), "columns_1").expect("读取密钥失败");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(resolved, Some(new_key), "密钥文件应覆盖 [paywall.keys] 中的旧密钥");
+}
+
+#[test]
+fn test_keys_file_resolved_against_project_root() {
+    let root = std::env::temp_dir().join(format!("rustpress_keys_root_{}", std::process::id()));
+    let md_dir = root.join("source");
+    let project_root = rustpress::utils::project_root(&md_dir);
+    let key = crypto::generate_content_key();
+    let mut file_keys = std::collections::HashMap::new();
+    file_keys.insert("columns_1".to_string(), crypto::encode_key(&key));
+
+    // 默认与相对路径的密钥文件都位于项目根（md_dir 的上级目录），与当前工作目录无关
+    let default = Config { data: toml::from_str("").unwrap() };
+    let relative = Config { data: toml::from_str("[paywall]\nkeys_file = \"secrets/keys.json\"\n").unwrap() };
+    assert_eq!(crypto::keys_file_path(&default, &project_root), root.join("doc_keys.json"));
+    assert_eq!(crypto::keys_file_path(&relative, &project_root), root.join("secrets/keys.json"));
+
+    crypto::write_keys_file(crypto::keys_file_path(&relative, &project_root), &file_keys).expect("无法写入密钥文件");
+    let resolved = crypto::resolve_content_key(&relative, &project_root, "columns_1").expect("读取密钥失败");
+    let from_cwd = crypto::resolve_content_key(&relative, Path::new(""), "columns_1").expect("读取密钥失败");
+    let _ = std::fs::remove_dir_all(&root);
+    assert_eq!(resolved, Some(key));
+    assert_eq!(from_cwd, None);
+}
+
+#[test]
+fn test_purchase_token_roundtrip() {
+    let claims = crypto::PurchaseClaims {
+        email: "reader@example.com".to_string(),
+        doc_id: "columns_1".to_string(),
+        exp: None,
+    };
+    let token = crypto::sign_purchase_token("local-secret", &claims).expect("签发失败");
+
+    let verified = crypto::verify_purchase_token("local-secret", &token).expect("校验失败");
+    assert_eq!(verified, claims);
+    assert!(
+        crypto::verify_purchase_token("other-secret", &token).is_err(),
+        "签名密钥不同时应拒绝凭证"
+    );
+
+    // 篡改载荷中的 doc_id 后签名不再匹配
+    let parts: Vec<&str> = token.split('.').collect();
+    let forged_payload = token.replace(parts[1], "eyJlbWFpbCI6ImEiLCJkb2NfaWQiOiJjb2x1bW5zXzIifQ");
+    assert!(crypto::verify_purchase_token("local-secret", &forged_payload).is_err());
+}
+
+#[test]
+fn test_rotate_keys_writes_key_after_pages() {
+    let root = std::env::temp_dir().join(format!("rustpress_rotate_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let md_dir = root.join("source");
+    let output_dir = root.join("public");
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    rustpress::copy_dir_recursive(repo.join("themes/default/templates"), root.join("themes/default/templates"))
+        .expect("无法拷贝主题模板");
+    let write = |path: &str, content: &str| {
+        let path = md_dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+    write("columns/1/README.md", "---\ntitle: 专栏\nlayout: columns\nis_free: false\ndoc_id: columns_1\n---\n目录\n");
+    write("columns/1/a.md", "---\ntitle: 第一章\nlayout: doc\n---\n付费正文\n");
+    let example = std::fs::read_to_string(repo.join("config.toml.example")).unwrap();
+    let config: Config = toml::from_str::<toml::Value>(&example.replace("# columns_1 = ", "columns_1 = \"Key_123\"\n# "))
+        .map(|data| Config { data })
+        .expect("配置解析失败");
+    let generator = rustpress::Generator::new(config, &md_dir).expect("创建生成器失败");
+    let keys_path = root.join("doc_keys.json");
+    generator.build(&md_dir, &output_dir).expect("全量构建失败");
+
+    // doc_id 没有付费页面时报错，且不写入密钥文件（首次构建的密钥来自 [paywall.keys]）
+    let missing = generator.rotate_keys(&md_dir, &output_dir, "columns_2");
+    assert!(missing.is_err());
+    assert!(!keys_path.exists());
+
+    // 页面全部写出后才写入新密钥，页面使用新密钥加密
+    let manifest = generator.rotate_keys(&md_dir, &output_dir, "columns_1").expect("轮换失败");
+    let keys = crypto::read_keys_file(&keys_path).expect("读取密钥文件失败");
+    let page = std::fs::read_to_string(output_dir.join("columns/1/a.html")).unwrap_or_default();
+    let build_manifest = rustpress::manifest::BuildManifest::load(output_dir.join(rustpress::manifest::MANIFEST_FILE));
+    let _ = std::fs::remove_dir_all(&root);
+    let new_key = crypto::content_key_from_str("columns_1", &keys["columns_1"]);
+    assert_eq!(crypto::key_id(&new_key), manifest.new_key_id);
+    assert_ne!(manifest.old_key_id, Some(manifest.new_key_id.clone()));
+    assert!(page.contains(&format!("data-key-id=\"{}\"", manifest.new_key_id)), "{}", page);
+    // 经增量构建轮换，构建清单同步记录新密钥
+    let build_manifest = build_manifest.expect("缺少构建清单");
+    assert_eq!(build_manifest.sources["columns/1/a.md"].key_id, Some(manifest.new_key_id.clone()));
+    assert_eq!(manifest.urls, vec!["/columns/1/a.html".to_string()]);
+}
+
+#[tokio::test]
+async fn test_dev_token_endpoint_requires_opt_in() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    let issue = |dev_tokens: bool| async move {
+        let config = Config {
+            data: toml::from_str(&format!("[paywall]\njwt_secret = \"secret\"\ndev_tokens = {}\n", dev_tokens)).unwrap(),
+        };
+        let ctx = rustpress::plugins::ApiContext::new("public", "");
+        let (_, router) = rustpress::plugins::paywall::paywall_api_routes(&config, &ctx).expect("未挂载换钥接口");
+        let request = Request::builder()
+            .method("POST")
+            .uri("/token")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"email":"a@example.com","doc_id":"columns_1"}"#))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default())
+    };
+
+    // 默认不挂载签发接口
+    assert_eq!(issue(false).await.0, StatusCode::NOT_FOUND);
+    // 显式开启后签发的凭证带有效期
+    let (status, body) = issue(true).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let claims = crypto::verify_purchase_token("secret", body["token"].as_str().unwrap()).expect("凭证无效");
+    assert!(claims.exp.is_some_and(|exp| exp > chrono::Utc::now().timestamp()));
+}
@@ -0,0 +1,47 @@
+use rustpress::post::ParseOptions;
+use rustpress::reading::{self, WordCount};
+use rustpress::{Config, Post, PostParser};
+use std::path::Path;
+
+#[test]
+fn test_word_count_mixed_cjk_latin_and_code() {
+    let count = reading::count_text("用 Rust 写一个 well-known 的 CLI，don't panic!");
+    assert_eq!(count, WordCount { cjk_chars: 5, words: 5 });
+
+    // 代码块不计入
+    let html = "<p>你好 world</p>\n<pre><code>fn main() { println!(\"很多很多代码\"); }\n</code></pre>\n<p>再见</p>";
+    assert_eq!(reading::count_html(html), WordCount { cjk_chars: 4, words: 1 });
+
+    assert_eq!(WordCount { cjk_chars: 301, words: 0 }.reading_minutes(300, 200), 2);
+    assert_eq!(WordCount { cjk_chars: 10, words: 10 }.reading_minutes(300, 200), 1);
+    assert_eq!(WordCount::default().reading_minutes(300, 200), 0);
+}
+
+#[test]
+fn test_reading_stats_injected_with_configured_speed() {
+    let config = Config {
+        data: toml::from_str("[reading]\ncjk_chars_per_minute = 100\nwords_per_minute = 50\n").unwrap(),
+    };
+    let options = ParseOptions::from_config(&config);
+    assert_eq!(options.cjk_chars_per_minute, 100);
+
+    let dir = std::env::temp_dir().join(format!("rustpress_reading_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("blog")).unwrap();
+    let body = format!("{}\n\n{}\n", "字".repeat(150), "word ".repeat(30));
+    std::fs::write(dir.join("blog/a.md"), format!("---\ntitle: A\n---\n{}", body)).unwrap();
+
+    let (posts, _) = PostParser::list_posts_with_errors(&dir, &options).expect("列出文章失败");
+    let _ = std::fs::remove_dir_all(&dir);
+    let post: &Post = &posts[0];
+    assert_eq!(post.data["word_count"], 180);
+    // 150 / 100 + 30 / 50 = 2.1 -> 3 分钟
+    assert_eq!(post.data["reading_time_minutes"], 3);
+    assert_eq!(reading::site_totals(&posts), (180, 3));
+
+    // 单文件解析使用默认速度
+    let value = PostParser::parse_file_content(&format!("---\ntitle: A\n---\n{}", body), Path::new("a.md"), Path::new("."))
+        .unwrap()
+        .unwrap();
+    assert_eq!(value["reading_time_minutes"], 1);
+}
@@ -0,0 +1,246 @@
+use rustpress::search::{self, SearchIndex, SearchQuery};
+use rustpress::plugins::search::search_api_routes;
+use rustpress::plugins::ApiContext;
+use rustpress::{Config, Post, PostParser};
+use serde_json::Value;
+use std::path::Path;
+
+fn post(content: &str, file: &str) -> Post {
+    let value = PostParser::parse_file_content(content, Path::new(file), Path::new("source"))
+        .expect("解析失败")
+        .expect("未识别到 front matter");
+    Post::from_value(value)
+}
+
+#[test]
+fn test_tokenize_cjk_bigrams_and_words() {
+    assert_eq!(search::tokenize("静态博客"), ["静态", "态博", "博客"]);
+    assert_eq!(search::tokenize("用 Rust 写 WebAssembly，v1.2"), ["用", "rust", "写", "webassembly", "v1", "2"]);
+    assert_eq!(search::tokenize("中文English混排"), ["中文", "english", "混排"]);
+    assert!(search::tokenize(" ，。! ").is_empty());
+
+    assert_eq!(search::shard_key("rust"), "r");
+    assert_eq!(search::shard_key("2024"), "2");
+    assert_eq!(search::shard_key("静态"), "u97");
+    assert_eq!(search::shard_key("博客"), "u53");
+}
+
+#[test]
+fn test_index_shards_and_docs() {
+    let posts = vec![
+        post(
+            "---\ntitle: Rust 静态博客\ncreateTime: 2024-05-01\ntags: [Rust]\ndescription: 用 Rust 构建\n---\n正文讲解博客生成。\n",
+            "source/blog/a.md",
+        ),
+        post("---\ntitle: 前端\ncreateTime: 2024-04-01\n---\n博客与前端\n", "source/blog/b.md"),
+    ];
+    let index = SearchIndex::build(&posts);
+    assert_eq!(index.docs.len(), 2);
+    assert_eq!(index.docs[0].url, "/blog/a.html");
+    assert_eq!(index.docs[0].date, "2024-05-01");
+    assert_eq!(index.docs[0].snippet, "用 Rust 构建");
+
+    // 标题与标签加权，正文计 1
+    let rust = &index.terms["rust"];
+    assert_eq!(rust, &vec![(0, 18)]);
+    let blog = &index.terms["博客"];
+    assert_eq!(blog.iter().map(|p| p.0).collect::<Vec<_>>(), [0, 1]);
+
+    let files = index.files();
+    assert_eq!(files[0].0, "manifest.json");
+    assert_eq!(files[1].0, "docs.json");
+    let manifest: Value = serde_json::from_str(&files[0].1).unwrap();
+    assert_eq!(manifest["version"], search::INDEX_VERSION);
+    assert_eq!(manifest["docs"], 2);
+    assert!(manifest["shards"]["r"].as_u64().unwrap() >= 1);
+
+    // 每个词项只出现在其分片中
+    let shard: Value = serde_json::from_str(&files.iter().find(|f| f.0 == "u53.json").unwrap().1).unwrap();
+    assert_eq!(shard["博客"][1], serde_json::json!([1, 1]));
+    assert!(shard.get("rust").is_none());
+    // 文档列表不含全文
+    assert!(!files[1].1.contains("正文讲解"));
+}
+
+fn sample_index() -> SearchIndex {
+    let posts = vec![
+        post(
+            "---\ntitle: Rust 静态博客\ncreateTime: 2024-05-01\ntags: [Rust]\ndescription: 用 Rust 构建 <静态> 博客\n---\n正文讲解博客生成。\n",
+            "source/blog/a.md",
+        ),
+        post(
+            "---\ntitle: 前端随笔\ncreateTime: 2024-04-01\ntags: [Web]\ndescription: 博客与前端\n---\n博客与前端，顺带一提 Rust\n",
+            "source/blog/b.md",
+        ),
+        post(
+            "---\ntitle: 读书笔记\ncreateTime: 2024-03-01\ndescription: 无关内容\n---\n读书写博客\n",
+            "source/notes/c.md",
+        ),
+    ];
+    SearchIndex::build(&posts)
+}
+
+fn query(q: &str) -> SearchQuery {
+    SearchQuery {
+        q: q.to_string(),
+        page: 1,
+        per_page: 10,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_search_bm25_filters_and_pagination() {
+    let index = sample_index();
+
+    // 标题与标签命中的文章排在前面
+    let results = index.search(&query("rust"));
+    assert_eq!(results.total, 2);
+    assert_eq!(results.hits[0].url, "/blog/a.html");
+    assert!(results.hits[0].score > results.hits[1].score);
+    assert_eq!(results.hits[0].snippet, "用 <mark>Rust</mark> 构建 &lt;静态&gt; 博客");
+
+    // 命中查询词越多越靠前
+    let results = index.search(&query("前端 博客"));
+    assert_eq!(results.hits[0].url, "/blog/b.html");
+
+    // 前缀匹配
+    assert_eq!(index.search(&query("ru")).total, 2);
+    assert_eq!(index.search(&query("不存在")).total, 0);
+
+    // 标签（不区分大小写）与分类过滤
+    let mut q = query("rust");
+    q.tag = Some("web".to_string());
+    let results = index.search(&q);
+    assert_eq!(results.total, 1);
+    assert_eq!(results.hits[0].url, "/blog/b.html");
+    assert_eq!(results.hits[0].tags, ["Web"]);
+
+    let mut q = query("博客");
+    assert_eq!(index.search(&q).total, 3);
+    q.category = Some("/notes/".to_string());
+    assert_eq!(index.search(&q).hits.iter().map(|h| h.url.as_str()).collect::<Vec<_>>(), ["/notes/c.html"]);
+
+    // 分页
+    let mut q = query("rust");
+    q.per_page = 1;
+    q.page = 2;
+    let results = index.search(&q);
+    assert_eq!((results.total, results.total_pages, results.hits.len()), (2, 2, 1));
+    assert_eq!(results.hits[0].url, "/blog/b.html");
+
+    // 超出范围的页码（含极大值）返回空页而不溢出
+    q.page = usize::MAX;
+    q.per_page = 20;
+    let results = index.search(&q);
+    assert_eq!((results.total, results.page, results.hits.len()), (2, usize::MAX, 0));
+}
+
+#[test]
+fn test_search_index_load_round_trip() {
+    let dir = std::env::temp_dir().join(format!("rustpress_search_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let index = sample_index();
+    for (name, content) in index.files() {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+
+    let loaded = SearchIndex::load(&dir).expect("载入索引失败");
+    assert_eq!(loaded.docs, index.docs);
+    assert_eq!(loaded.texts, index.texts);
+    assert_eq!(loaded.terms, index.terms);
+    assert_eq!(loaded.search(&query("博客")), index.search(&query("博客")));
+
+    // 版本不符时拒绝载入
+    std::fs::write(dir.join("manifest.json"), r#"{"version":999,"docs":0,"shards":{}}"#).unwrap();
+    assert!(SearchIndex::load(&dir).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_snippet_centers_on_first_match() {
+    let filler = "这是一段与查询无关的铺垫文字。".repeat(20);
+    let posts = vec![post(
+        &format!("---\ntitle: 长文\ncreateTime: 2024-05-01\n---\n{}\n\n后面才讲到 WebAssembly 的用法。\n", filler),
+        "source/blog/long.md",
+    )];
+    let index = SearchIndex::build(&posts);
+    assert!(!index.docs[0].snippet.contains("WebAssembly"));
+
+    // 命中词不在摘要内时截取正文中命中词附近的片段
+    let snippet = &index.search(&query("webassembly")).hits[0].snippet;
+    assert!(snippet.starts_with('…'));
+    assert!(snippet.contains("<mark>WebAssembly</mark>"));
+    assert!(snippet.chars().count() <= search::SNIPPET_CHARS + "<mark></mark>…".len());
+
+    // 摘要已含命中词时仍用摘要
+    assert_eq!(index.search(&query("铺垫")).hits[0].snippet.chars().next(), Some('这'));
+
+    // 没有 texts.json 的旧索引退回只高亮摘要
+    let files = index.files();
+    let loaded = SearchIndex::load_with(|name| {
+        files
+            .iter()
+            .find(|f| f.0 == name)
+            .map(|f| f.1.clone())
+            .filter(|_| name != "texts.json")
+            .ok_or_else(|| rustpress::Error::Other(name.to_string()))
+    })
+    .expect("载入索引失败");
+    assert!(loaded.texts.is_empty());
+    assert_eq!(loaded.search(&query("webassembly")).hits[0].snippet, index.docs[0].snippet);
+}
+
+#[test]
+fn test_highlight_escapes_and_merges() {
+    let tokens = search::tokenize("静态博客");
+    assert_eq!(search::highlight("<b>静态博客</b>", &tokens), "&lt;b&gt;<mark>静态博客</mark>&lt;/b&gt;");
+    assert_eq!(search::highlight("a & b", &[]), "a &amp; b");
+}
+
+#[tokio::test]
+async fn test_search_api_route() {
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let output = std::env::temp_dir().join(format!("rustpress_search_api_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&output);
+    let disabled = Config { data: toml::from_str("[search]\napi = false\n").unwrap() };
+    let ctx = ApiContext::new(&output, "");
+    assert!(search_api_routes(&disabled, &ctx).is_none());
+    let config = Config { data: toml::from_str("").unwrap() };
+    let (base, router) = search_api_routes(&config, &ctx).expect("未挂载搜索接口");
+    assert_eq!(base, "/api/search");
+
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    // 尚未构建：索引不可用
+    let res = router.clone().oneshot(get("/?q=rust")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let dir = output.join(search::INDEX_DIR);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, content) in sample_index().files() {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+
+    let res = router.clone().oneshot(get("/?q=")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = router.clone().oneshot(get("/?q=rust&tag=Web&per_page=500")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = serde_json::from_slice(&to_bytes(res.into_body(), usize::MAX).await.unwrap()).unwrap();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["per_page"], 50);
+    assert_eq!(body["hits"][0]["url"], "/blog/b.html");
+
+    // 查询串中的超大页码不会导致溢出
+    let res = router.clone().oneshot(get("/?q=rust&page=18446744073709551615")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = serde_json::from_slice(&to_bytes(res.into_body(), usize::MAX).await.unwrap()).unwrap();
+    assert_eq!((body["total"].as_u64(), body["hits"].as_array().map(Vec::len)), (Some(2), Some(0)));
+    let _ = std::fs::remove_dir_all(&output);
+}
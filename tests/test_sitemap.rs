@@ -0,0 +1,91 @@
+use rustpress::sitemap::{self, SitemapEntry, SitemapOptions};
+use rustpress::{Config, Post, PostParser};
+use serde_json::Value;
+use std::path::Path;
+
+fn post(content: &str, modified_epoch: i64) -> Post {
+    let mut value = PostParser::parse_file_content(content, Path::new("source/blog/a.md"), Path::new("source"))
+        .expect("解析失败")
+        .expect("未识别到 front matter");
+    value["modified_epoch"] = Value::from(modified_epoch);
+    Post::from_value(value)
+}
+
+#[test]
+fn test_lastmod_and_exclusion() {
+    let p = post("---\ntitle: A\ncreateTime: 2024-05-01 10:30:00\nupdated: 2024-06-02 09:00:00\n---\n正文\n", 1893456000);
+    assert!(sitemap::lastmod(&p).to_rfc3339().starts_with("2024-06-02T09:00:00"), "front matter updated 优先");
+
+    // 2030-01-01，晚于发布时间
+    let p = post("---\ntitle: A\ncreateTime: 2024-05-01 10:30:00\n---\n正文\n", 1893456000);
+    assert!(sitemap::lastmod(&p).to_rfc3339().starts_with("2030-01-01"));
+    let p = post("---\ntitle: A\ncreateTime: 2024-05-01 10:30:00\n---\n正文\n", 0);
+    assert!(sitemap::lastmod(&p).to_rfc3339().starts_with("2024-05-01T10:30:00"));
+    assert!(!sitemap::is_excluded(&p));
+
+    assert!(sitemap::is_excluded(&post("---\ntitle: A\nsitemap: false\n---\n正文\n", 0)));
+    assert!(sitemap::is_excluded(&post("---\ntitle: A\nnoindex: true\n---\n正文\n", 0)));
+    assert!(!sitemap::is_excluded(&post("---\ntitle: A\nnoindex: false\n---\n正文\n", 0)));
+}
+
+#[test]
+fn test_image_entries() {
+    let p = post(
+        "---\ntitle: A\ncover: cover.png\nimages:\n  - /assets/b.png\n  - https://cdn.example.com/c.png\n---\n正文\n",
+        0,
+    );
+    let images = sitemap::post_images(&p, "https://example.com");
+    assert_eq!(
+        images,
+        vec![
+            "https://example.com/blog/cover.png",
+            "https://example.com/assets/b.png",
+            "https://cdn.example.com/c.png",
+        ]
+    );
+
+    let config = Config { data: toml::from_str("[sitemap]\nimages = true\n").unwrap() };
+    let options = SitemapOptions::from_config(&config);
+    let entry = SitemapEntry::from_post(&p, "https://example.com", &options);
+    let files = sitemap::render(&[entry], &options, "https://example.com");
+    assert_eq!(files.len(), 1);
+    let xml = &files[0].content;
+    assert!(xml.contains("xmlns:image=\"http://www.google.com/schemas/sitemap-image/1.1\""), "{}", xml);
+    assert!(xml.contains("<image:image><image:loc>https://example.com/assets/b.png</image:loc></image:image>"), "{}", xml);
+    assert!(xml.contains("<loc>https://example.com/blog/a.html</loc>"), "{}", xml);
+    assert!(xml.contains("<lastmod>"), "{}", xml);
+
+    // 默认不输出图片
+    let entry = SitemapEntry::from_post(&p, "https://example.com", &SitemapOptions::default());
+    assert!(entry.images.is_empty());
+}
+
+#[test]
+fn test_split_into_index() {
+    let entries: Vec<SitemapEntry> =
+        (0..5).map(|i| SitemapEntry::new(format!("https://example.com/p{}.html?a=1&b=2", i))).collect();
+    let options = SitemapOptions::default();
+    let files = sitemap::render(&entries, &options, "https://example.com");
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].name, sitemap::SITEMAP_FILE);
+    assert!(files[0].content.contains("?a=1&amp;b=2"), "{}", files[0].content);
+
+    // 按数量拆分
+    let options = SitemapOptions { max_urls: 2, ..SitemapOptions::default() };
+    let files = sitemap::render(&entries, &options, "https://example.com");
+    let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["sitemap_index.xml", "sitemap-1.xml", "sitemap-2.xml", "sitemap-3.xml"]);
+    assert!(files[0].content.contains("<sitemapindex"), "{}", files[0].content);
+    assert!(files[0].content.contains("<loc>https://example.com/sitemap-3.xml</loc>"), "{}", files[0].content);
+    assert_eq!(files[3].content.matches("<url>").count(), 1);
+
+    // 按体积拆分
+    let options = SitemapOptions { max_bytes: 300, ..SitemapOptions::default() };
+    let files = sitemap::render(&entries, &options, "https://example.com");
+    assert!(files.len() > 2);
+    assert!(files[1..].iter().all(|f| f.content.len() <= 300), "{:?}", files);
+
+    // 配置不能超过协议上限
+    let config = Config { data: toml::from_str("[sitemap]\nmax_urls = 100000\n").unwrap() };
+    assert_eq!(SitemapOptions::from_config(&config).max_urls, sitemap::MAX_URLS);
+}
@@ -0,0 +1,58 @@
+use rustpress::toc::{self, Slugger};
+use rustpress::PostParser;
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn parse(content: &str) -> Value {
+    PostParser::parse_file_content(content, Path::new("source/blog/a.md"), Path::new("source"))
+        .expect("解析失败")
+        .expect("未识别到 front matter")
+}
+
+#[test]
+fn test_slugify_chinese_and_dedup() {
+    assert_eq!(toc::slugify("快速开始"), "快速开始");
+    assert_eq!(toc::slugify("Hello, World!"), "hello-world");
+    assert_eq!(toc::slugify("  Rust  与 WebAssembly  "), "rust-与-webassembly");
+    assert_eq!(toc::slugify("？！"), "section");
+
+    let mut slugger = Slugger::default();
+    assert_eq!(slugger.slug("安装"), "安装");
+    assert_eq!(slugger.slug("安装"), "安装-1");
+    assert_eq!(slugger.slug("安装"), "安装-2");
+}
+
+#[test]
+fn test_post_toc_nested_with_stable_anchors() {
+    let post = parse(
+        "---\ntitle: A\n---\n# 标题\n\n## 安装\n\n### 使用 `cargo`\n\n## 配置\n\n### 安装\n\n#### 细节\n\n## 安装\n",
+    );
+    let content = post["content"].as_str().unwrap();
+    assert!(content.contains("<h2 id=\"安装\">安装</h2>"), "{}", content);
+    assert!(content.contains("<h3 id=\"安装-1\">安装</h3>"), "{}", content);
+    assert!(content.contains("<h2 id=\"安装-2\">安装</h2>"), "{}", content);
+    assert!(content.contains("<h3 id=\"使用-cargo\">使用 <code>cargo</code></h3>"), "{}", content);
+
+    // 第一个 H1 为文章标题，不计入目录
+    let toc = &post["toc"];
+    assert_eq!(toc.as_array().unwrap().len(), 3);
+    assert_eq!(toc[0]["text"], "安装");
+    assert_eq!(toc[0]["children"][0], json!({"level": 3, "text": "使用 cargo", "anchor": "使用-cargo", "children": []}));
+    assert_eq!(toc[1]["children"][0]["anchor"], "安装-1");
+    assert_eq!(toc[1]["children"][0]["children"][0]["level"], 4);
+    assert_eq!(toc[2]["anchor"], "安装-2");
+
+    // 同样的内容多次解析得到相同的锚点
+    let again = parse(
+        "---\ntitle: A\n---\n# 标题\n\n## 安装\n\n### 使用 `cargo`\n\n## 配置\n\n### 安装\n\n#### 细节\n\n## 安装\n",
+    );
+    assert_eq!(again["toc"], post["toc"]);
+}
+
+#[test]
+fn test_post_toc_disabled_by_front_matter() {
+    let post = parse("---\ntitle: A\ntoc: false\n---\n## 一\n\n## 二\n");
+    assert_eq!(post["toc"], json!([]));
+    // 锚点仍然生成，便于外部链接
+    assert!(post["content"].as_str().unwrap().contains("<h2 id=\"一\">"));
+}
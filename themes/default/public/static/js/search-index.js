@@ -0,0 +1,139 @@
+// RustPress 搜索索引客户端
+// 构建时生成的索引位于 /search/：manifest.json（清单）、docs.json（标题/URL/日期/摘要）
+// 与按词项首字符划分的分片 <key>.json。查询时只加载查询词所在的分片。
+(function (global) {
+  'use strict';
+
+  var BASE = '/search/';
+  var ready = null;
+  var manifest = null;
+  var docs = [];
+  var shards = {};
+
+  function fetchJson(name) {
+    return fetch(BASE + name).then(function (res) {
+      if (!res.ok) throw new Error('无法加载搜索索引 ' + name + ': ' + res.status);
+      return res.json();
+    });
+  }
+
+  function load() {
+    if (!ready) {
+      ready = Promise.all([fetchJson('manifest.json'), fetchJson('docs.json')]).then(function (data) {
+        manifest = data[0];
+        docs = data[1];
+      });
+    }
+    return ready;
+  }
+
+  function loadShard(key) {
+    if (!manifest.shards || !manifest.shards[key]) return Promise.resolve({});
+    if (!shards[key]) {
+      shards[key] = fetchJson(key + '.json').catch(function () { return {}; });
+    }
+    return shards[key];
+  }
+
+  // 与 src/reading.rs 的 is_cjk 一致
+  function isCjk(cp) {
+    return (cp >= 0x3400 && cp <= 0x4DBF) || (cp >= 0x4E00 && cp <= 0x9FFF) ||
+      (cp >= 0xF900 && cp <= 0xFAFF) || (cp >= 0x20000 && cp <= 0x2FA1F) ||
+      (cp >= 0x3040 && cp <= 0x30FF) || (cp >= 0xAC00 && cp <= 0xD7AF);
+  }
+
+  // 与 src/search.rs 的 tokenize 一致：英文数字按词，中日韩文字按相邻两字
+  function tokenize(text) {
+    var tokens = [];
+    var word = '';
+    var run = [];
+    function flushWord() {
+      if (word) { tokens.push(word); word = ''; }
+    }
+    function flushRun() {
+      if (run.length === 1) tokens.push(run[0]);
+      for (var i = 0; i + 1 < run.length; i++) tokens.push(run[i] + run[i + 1]);
+      run = [];
+    }
+    Array.from(text || '').forEach(function (c) {
+      if (isCjk(c.codePointAt(0))) {
+        flushWord();
+        run.push(c);
+      } else if (/[\p{L}\p{N}]/u.test(c)) {
+        flushRun();
+        word += c.toLowerCase();
+      } else {
+        flushWord();
+        flushRun();
+      }
+    });
+    flushWord();
+    flushRun();
+    return tokens.filter(function (t, i) { return tokens.indexOf(t) === i; });
+  }
+
+  // 与 src/search.rs 的 shard_key 一致
+  function shardKey(term) {
+    var c = Array.from(term)[0];
+    if (/^[A-Za-z0-9]$/.test(c)) return c.toLowerCase();
+    return 'u' + (c.codePointAt(0) >> 8).toString(16);
+  }
+
+  // 搜索：命中查询词越多越靠前，其次按 TF-IDF 得分排序
+  function search(query, limit) {
+    var terms = tokenize(query);
+    if (terms.length === 0) return Promise.resolve([]);
+    return load().then(function () {
+      return Promise.all(terms.map(function (t) { return loadShard(shardKey(t)); }));
+    }).then(function (loaded) {
+      var total = docs.length;
+      var scores = {};
+      var matched = {};
+      terms.forEach(function (term, i) {
+        var shard = loaded[i];
+        // 没有完全匹配时按前缀匹配（输入到一半的单词、单个汉字）
+        var lists = shard[term] ? [shard[term]] : Object.keys(shard)
+          .filter(function (k) { return k.indexOf(term) === 0; })
+          .map(function (k) { return shard[k]; });
+        var seen = {};
+        lists.forEach(function (postings) {
+          var idf = Math.log(1 + total / postings.length);
+          postings.forEach(function (p) {
+            scores[p[0]] = (scores[p[0]] || 0) + p[1] * idf;
+            if (!seen[p[0]]) {
+              seen[p[0]] = true;
+              matched[p[0]] = (matched[p[0]] || 0) + 1;
+            }
+          });
+        });
+      });
+      return Object.keys(scores)
+        .map(function (id) {
+          var doc = docs[id];
+          return {
+            title: doc.title,
+            url: doc.url,
+            date: doc.date,
+            snippet: doc.snippet,
+            score: scores[id],
+            matched: matched[id]
+          };
+        })
+        .sort(function (a, b) { return b.matched - a.matched || b.score - a.score; })
+        .slice(0, limit || 20);
+    });
+  }
+
+  function escapeHtml(text) {
+    return String(text || '').replace(/[&<>"']/g, function (c) {
+      return { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' }[c];
+    });
+  }
+
+  global.RustPressSearchIndex = {
+    load: load,
+    search: search,
+    tokenize: tokenize,
+    escapeHtml: escapeHtml
+  };
+})(window);
@@ -1,8 +1,7 @@
 // RustPress 搜索功能
 class RustPressSearch {
     constructor() {
-        this.searchData = [];
-        this.searchIndex = null;
+        this.searchIndex = window.RustPressSearchIndex;
         this.isLoaded = false;
         this.init();
     }
@@ -14,30 +13,9 @@ class RustPressSearch {
 
     async loadSearchData() {
         try {
-            const response = await fetch('/search.json');
-            this.searchData = await response.json();
-            
-            // 创建Lunr搜索索引
-            const searchData = this.searchData; // 保存引用
-            this.searchIndex = lunr(function () {
-                this.ref('id');
-                this.field('title', { boost: 10 });
-                this.field('content', { boost: 5 });
-                this.field('tags', { boost: 8 });
-                this.field('categories', { boost: 6 });
-                
-                // 支持中文搜索
-                this.pipeline.remove(lunr.stemmer);
-                this.searchPipeline.remove(lunr.stemmer);
-                
-                // 添加文档到索引
-                for (let doc of searchData) {
-                    this.add(doc);
-                }
-            });
-            
+            // 只加载索引清单与文档列表，分片在搜索时按需加载
+            await this.searchIndex.load();
             this.isLoaded = true;
-            console.log('搜索索引加载完成，共', this.searchData.length, '篇文章');
         } catch (error) {
             console.error('搜索数据加载失败:', error);
         }
@@ -123,7 +101,7 @@ class RustPressSearch {
         }
     }
 
-    performSearch(query) {
+    async performSearch(query) {
         const $searchResults = $('#search-results');
         const $searchCount = $('#search-count');
         
@@ -141,19 +119,14 @@ class RustPressSearch {
         }
 
         try {
-            // 使用Lunr进行搜索
-            const results = this.searchIndex.search(query);
-            
-            // 如果Lunr没有结果，尝试简单的文本匹配
-            let finalResults = results;
-            if (results.length === 0) {
-                finalResults = this.fallbackSearch(query);
-            }
+            const results = await this.searchIndex.search(query, 10);
+            // 输入已变化时丢弃过期结果
+            if ($('#search-modal-input').val() !== query) return;
 
-            this.displayResults(finalResults, query);
+            this.displayResults(results, query);
             
             if ($searchCount.length) {
-                $searchCount.text(`找到 ${finalResults.length} 个结果`);
+                $searchCount.text(`找到 ${results.length} 个结果`);
             }
         } catch (error) {
             console.error('搜索出错:', error);
@@ -161,37 +134,6 @@ class RustPressSearch {
         }
     }
 
-    fallbackSearch(query) {
-        const lowerQuery = query.toLowerCase();
-        const results = [];
-        
-        this.searchData.forEach((item, index) => {
-            const titleMatch = item.title.toLowerCase().includes(lowerQuery);
-            const contentMatch = item.content.toLowerCase().includes(lowerQuery);
-            const tagsMatch = item.tags.some(tag => 
-                tag.toLowerCase().includes(lowerQuery)
-            );
-            const categoriesMatch = item.categories.some(cat => 
-                cat.toLowerCase().includes(lowerQuery)
-            );
-            
-            if (titleMatch || contentMatch || tagsMatch || categoriesMatch) {
-                let score = 0;
-                if (titleMatch) score += 10;
-                if (contentMatch) score += 5;
-                if (tagsMatch) score += 8;
-                if (categoriesMatch) score += 6;
-                
-                results.push({
-                    ref: index.toString(),
-                    score: score
-                });
-            }
-        });
-        
-        return results.sort((a, b) => b.score - a.score).slice(0, 10);
-    }
-
     displayResults(results, query) {
         const $searchResults = $('#search-results');
         
@@ -205,23 +147,17 @@ class RustPressSearch {
             return;
         }
 
-        const html = results.map((result, index) => {
-            const item = this.searchData[parseInt(result.ref)];
-            const excerpt = this.generateExcerpt(item.content, query);
+        const html = results.map((item, index) => {
+            const excerpt = this.highlightText(this.searchIndex.escapeHtml(item.snippet), query);
             
             return `
                 <div class="search-result-item" data-index="${index}" data-url="${item.url}">
                     <h3 class="search-result-title">
-                        <a href="${item.url}">${this.highlightText(item.title, query)}</a>
+                        <a href="${item.url}">${this.highlightText(this.searchIndex.escapeHtml(item.title), query)}</a>
                     </h3>
                     <p class="search-result-excerpt">${excerpt}</p>
                     <div class="search-result-meta">
                         <span class="search-result-date">${item.date}</span>
-                        ${item.tags.length > 0 ? `
-                            <span class="search-result-tags">
-                                ${item.tags.map(tag => `<span class="tag">${tag}</span>`).join('')}
-                            </span>
-                        ` : ''}
                     </div>
                 </div>
             `;
@@ -241,29 +177,12 @@ class RustPressSearch {
         });
     }
 
-    generateExcerpt(content, query, maxLength = 150) {
-        const lowerContent = content.toLowerCase();
-        const lowerQuery = query.toLowerCase();
-        const index = lowerContent.indexOf(lowerQuery);
-        
-        if (index === -1) {
-            return content.substring(0, maxLength) + (content.length > maxLength ? '...' : '');
-        }
-        
-        const start = Math.max(0, index - 50);
-        const end = Math.min(content.length, index + query.length + 100);
-        let excerpt = content.substring(start, end);
-        
-        if (start > 0) excerpt = '...' + excerpt;
-        if (end < content.length) excerpt = excerpt + '...';
-        
-        return this.highlightText(excerpt, query);
-    }
-
     highlightText(text, query) {
         if (!query.trim()) return text;
         
-        const regex = new RegExp(`(${query.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')})`, 'gi');
+        // 按空格拆分后逐个高亮查询词
+        const words = query.trim().split(/\s+/).map(w => w.replace(/[.*+?^${}()|[\]\\]/g, '\\$&'));
+        const regex = new RegExp(`(${words.join('|')})`, 'gi');
         return text.replace(regex, '<mark>$1</mark>');
     }
 
@@ -304,9 +223,9 @@ class RustPressSearch {
 
 // 页面加载完成后初始化搜索
 $(document).ready(function() {
-    // 检查是否有Lunr.js
-    if (typeof lunr === 'undefined') {
-        console.warn('Lunr.js 未加载，搜索功能将不可用');
+    // 检查是否加载了搜索索引客户端
+    if (typeof window.RustPressSearchIndex === 'undefined') {
+        console.warn('search-index.js 未加载，搜索功能将不可用');
         return;
     }
     
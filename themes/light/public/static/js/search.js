@@ -2,63 +2,20 @@
 (function () {
   'use strict';
 
-  var searchData = [];
-  var searchIndex = null;
+  var searchIndex = window.RustPressSearchIndex;
   var isLoaded = false;
 
   var INPUT_ID = 'nav-search-input';
   var SUGGESTIONS_ID = 'search-suggestions';
 
+  // 只加载索引清单与文档列表，分片在搜索时按需加载
   function loadSearchData() {
-    return fetch('/search.json', { cache: 'no-store' })
-      .then(function (res) { return res.ok ? res.json() : []; })
-      .then(function (data) {
-        searchData = Array.isArray(data) ? data : (data.items || []);
-        searchData = searchData.map(function (item, idx) {
-          return {
-            id: idx,
-            title: item.title || item.slug || '',
-            content: item.content || '',
-            url: item.url || '',
-            date: item.date || '',
-            tags: item.tags || [],
-            categories: item.categories || []
-          };
-        });
-        // 构建 Lunr 索引
-        if (typeof lunr !== 'undefined') {
-          searchIndex = lunr(function () {
-            this.ref('id');
-            this.field('title', { boost: 10 });
-            this.field('content', { boost: 5 });
-            this.field('tags', { boost: 8 });
-            this.field('categories', { boost: 6 });
-            this.pipeline.remove(lunr.stemmer);
-            this.searchPipeline.remove(lunr.stemmer);
-            for (var i = 0; i < searchData.length; i++) {
-              this.add(searchData[i]);
-            }
-          });
-        }
-        isLoaded = true;
-      })
+    if (!searchIndex) { isLoaded = true; return Promise.resolve(); }
+    return searchIndex.load()
+      .then(function () { isLoaded = true; })
       .catch(function () { isLoaded = true; });
   }
 
-  // 生成摘要
-  function excerpt(text, query, maxLen) {
-    maxLen = maxLen || 150;
-    var lower = text.toLowerCase();
-    var idx = lower.indexOf(query.toLowerCase());
-    if (idx === -1) return text.slice(0, maxLen) + (text.length > maxLen ? '...' : '');
-    var start = Math.max(0, idx - 50);
-    var end = Math.min(text.length, idx + query.length + 100);
-    var out = text.slice(start, end);
-    if (start > 0) out = '...' + out;
-    if (end < text.length) out = out + '...';
-    return out;
-  }
-
   function highlight(text, query) {
     if (!query) return text;
     var safe = query.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
@@ -76,26 +33,19 @@
     if (!query.trim()) { el.innerHTML = ''; el.classList.add('hidden'); return; }
     if (!isLoaded) { el.innerHTML = '<div class="p-3 text-sm text-mist-green">索引加载中…</div>'; el.classList.remove('hidden'); return; }
 
-    var results = [];
-    if (searchIndex) {
-      try {
-        results = searchIndex.search(query);
-      } catch (_) { results = []; }
-    }
-
-    // 降级文本匹配
-    if (results.length === 0) {
-      var qLower = query.toLowerCase();
-      searchData.forEach(function (item) {
-        var text = (item.title + ' ' + item.content + ' ' + item.tags.join(' ') + ' ' + item.categories.join(' ')).toLowerCase();
-        if (text.indexOf(qLower) !== -1) {
-          results.push({ ref: item.id, score: 0 });
-        }
-      });
-    }
-
+    if (!searchIndex) return;
     // 最多显示 8 条
-    results = results.slice(0, 8);
+    searchIndex.search(query, 8).then(function (results) {
+      renderResults(el, results, query);
+    }).catch(function () {
+      renderResults(el, [], query);
+    });
+  }
+
+  function renderResults(el, results, query) {
+    // 输入已变化时丢弃过期结果
+    var input = document.getElementById(INPUT_ID);
+    if (input && input.value !== query) return;
 
     if (results.length === 0) {
       el.innerHTML = '<div class="p-3 text-sm text-mist-green">未找到相关内容</div>';
@@ -104,11 +54,9 @@
     }
 
     var html = '';
-    results.forEach(function (r) {
-      var item = searchData[r.ref] || searchData[parseInt(r.ref)];
-      if (!item) return;
-      var title = highlight(item.title, query);
-      var desc = highlight(excerpt(item.content, query, 120), query);
+    results.forEach(function (item) {
+      var title = highlight(searchIndex.escapeHtml(item.title), query);
+      var desc = highlight(searchIndex.escapeHtml(item.snippet), query);
       html += '<a href="' + item.url + '" class="block px-4 py-2.5 text-sm hover:bg-air-bg border-b border-sage-line/10 last:border-0 transition-colors">';
       html += '<div class="font-medium text-moss-ink">' + title + '</div>';
       html += '<div class="text-mist-green text-xs mt-0.5 line-clamp-2">' + desc + '</div>';